@@ -1,27 +1,67 @@
+use serde::Serialize;
 use vputilslib::equation_handler::EquationHandler;
 
 use crate::{
     fem::{self},
+    results::ResultEnvelope,
     structure::StructureModel,
 };
 use std::ffi::{c_char, CStr, CString};
 
+/// Calculates the structure and returns the results of all load combinations as JSON
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
 #[no_mangle]
-pub extern "C" fn vefem_calculate(structure_json: *const c_char) -> *mut c_char {
-    let calc_model_json = unsafe { CStr::from_ptr(structure_json).to_str().unwrap() };
-    let calc_model = match serde_json::from_str::<StructureModel>(&calc_model_json) {
-        Ok(calc_model) => calc_model,
-        Err(e) => {
-            return CString::new(format!("Invalid JSON. Error: {}", e)).unwrap().into_raw();
-        }
-    };
+pub unsafe extern "C" fn vefem_calculate(structure_json: *const c_char) -> *mut c_char {
+    calculate_from_json(structure_json, |calc_model| {
+        fem::fem_handler::calculate(calc_model, &EquationHandler::new())
+    })
+}
 
-    let results = fem::fem_handler::calculate(&calc_model, &mut EquationHandler::new());
+/// Calculates the structure and returns the envelope of the results over all load combinations
+/// as JSON (see [`ResultEnvelope`])
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn vefem_calculate_envelope(structure_json: *const c_char) -> *mut c_char {
+    calculate_from_json(structure_json, |calc_model| {
+        let results = fem::fem_handler::calculate(calc_model, &EquationHandler::new());
+        ResultEnvelope::new(&results)
+    })
+}
 
-    let results_json = serde_json::to_string_pretty(&results).unwrap();
-    CString::new(results_json).unwrap().into_raw()
+/// Calculates the results of each load case (the loads with the same name with the factor 1.0)
+/// and returns them as JSON (see [`crate::results::LoadCaseResults`])
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn vefem_calculate_load_cases(structure_json: *const c_char) -> *mut c_char {
+    calculate_from_json(structure_json, |calc_model| {
+        fem::fem_handler::calculate_load_cases(calc_model, &EquationHandler::new())
+    })
+}
+
+/// Calculates the linear buckling modes of each load combination and returns them as JSON (see
+/// [`crate::results::BucklingResults`])
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn vefem_calculate_buckling(structure_json: *const c_char) -> *mut c_char {
+    calculate_from_json(structure_json, |calc_model| {
+        fem::fem_handler::calculate_buckling(calc_model, &EquationHandler::new())
+    })
 }
 
+/// Calculates the natural frequencies and the mode shapes and returns them as JSON (see
+/// [`crate::results::ModalResults`])
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
+#[no_mangle]
+pub unsafe extern "C" fn vefem_calculate_modal(structure_json: *const c_char) -> *mut c_char {
+    calculate_from_json(structure_json, |calc_model| {
+        fem::fem_handler::calculate_modal(calc_model, &EquationHandler::new())
+    })
+}
 
 #[no_mangle]
 pub extern "C" fn version() -> *mut c_char {
@@ -29,4 +69,24 @@ pub extern "C" fn version() -> *mut c_char {
     CString::new(version).unwrap().into_raw()
 }
 
-const CARGO_VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
\ No newline at end of file
+/// Deserializes the structure model from the JSON, calculates it with the given function and
+/// returns the results as JSON. If the JSON is invalid, the error message is returned instead.
+/// # Safety
+/// `structure_json` must be a valid pointer to a null terminated string
+unsafe fn calculate_from_json<T: Serialize>(
+    structure_json: *const c_char,
+    calculate: impl FnOnce(&StructureModel) -> T,
+) -> *mut c_char {
+    let calc_model_json = unsafe { CStr::from_ptr(structure_json).to_str().unwrap() };
+    let calc_model = match serde_json::from_str::<StructureModel>(calc_model_json) {
+        Ok(calc_model) => calc_model,
+        Err(e) => {
+            return CString::new(format!("Invalid JSON. Error: {}", e)).unwrap().into_raw();
+        }
+    };
+
+    let results_json = serde_json::to_string_pretty(&calculate(&calc_model)).unwrap();
+    CString::new(results_json).unwrap().into_raw()
+}
+
+const CARGO_VERSION: Option<&'static str> = option_env!("CARGO_PKG_VERSION");
@@ -1,11 +1,24 @@
 ﻿pub use calculation_model::CalcModel;
 
+pub mod active_set;
 pub mod axial_deformation;
+pub mod buckling;
+pub mod concrete_deflection;
+pub mod constraints;
 pub mod deflection;
+pub mod eigen;
 pub mod equivalent_loads;
 pub mod fem_handler;
+pub mod foundation;
+pub mod influence_line;
 pub mod internal_forces;
+pub mod mass;
 pub mod matrices;
+pub mod modal;
+pub mod second_order;
+pub mod skyline;
+pub mod sparse;
 pub mod stiffness;
+pub mod superposition;
 pub mod utils;
 mod calculation_model;
\ No newline at end of file
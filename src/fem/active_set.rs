@@ -0,0 +1,162 @@
+//! The active set of the tension-only and compression-only elements and the one-directional
+//! supports. The load combination is solved with all the elements and supports active, and the
+//! elements and supports that violate their condition are deactivated (and the deactivated ones
+//! that would meet their condition again are reactivated). The structure is solved again until the
+//! active set does not change.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::internal_forces::calculate_axial_force_at;
+use super::CalcModel;
+use crate::loads::load::CalculationLoad;
+use crate::results::{ActiveSetResults, NodeResults};
+use crate::settings::CalculationSettings;
+use crate::structure::{Element, ElementBehaviour, Node, NodeCollection, ReactionDirection};
+
+/// The axial forces and the support reactions (N) smaller than this are not considered to violate
+/// the condition of the element or the support
+const FORCE_TOLERANCE: f64 = 1e-3;
+/// The displacements (mm) smaller than this do not reactivate the element or the support
+const DISPLACEMENT_TOLERANCE: f64 = 1e-9;
+
+/// The inactive elements and support directions of a load combination
+#[derive(Debug, Clone, Default)]
+pub struct ActiveSet {
+    /// The model element numbers of the inactive elements
+    pub inactive_elements: BTreeSet<i32>,
+    /// The inactive support directions as the node numbers and the directions (0=tx, 1=tz, 2=ry)
+    pub inactive_supports: BTreeSet<(i32, usize)>,
+}
+
+impl ActiveSet {
+    /// Returns true if there are any tension-only or compression-only elements or one-directional
+    /// supports in the structure
+    pub fn has_conditions(elements: &[Element], nodes: &NodeCollection) -> bool {
+        elements.iter().any(|e| e.behaviour != ElementBehaviour::Normal)
+            || nodes.values().any(|n| (0..3).any(|dof| get_support_sign(n, dof).is_some()))
+    }
+
+    /// Gets a copy of the nodes where the inactive support directions are released
+    pub fn get_nodes(&self, nodes: &NodeCollection) -> NodeCollection {
+        let mut nodes = nodes.clone();
+        for (node_number, dof) in &self.inactive_supports {
+            if let Some(node) = nodes.get_mut(node_number) {
+                node.support.set_support_lock(*dof, false);
+            }
+        }
+        nodes
+    }
+
+    /// Updates the active set from the results of the previous solution. Returns true if the
+    /// active set changed.
+    /// * `calc_model` - the calculation model of the previous solution
+    /// * `loads` - the loads of the load combination
+    /// * `node_results` - the node results of the previous solution
+    pub fn update(
+        &mut self,
+        calc_model: &CalcModel,
+        loads: &Vec<CalculationLoad>,
+        node_results: &NodeResults,
+        calc_settings: &CalculationSettings,
+    ) -> bool {
+        let mut changed = false;
+        for element in calc_model.structure_elements {
+            let sign = match element.behaviour {
+                ElementBehaviour::Normal => continue,
+                ElementBehaviour::TensionOnly => 1.0,
+                ElementBehaviour::CompressionOnly => -1.0,
+            };
+            let Some(calc_element) =
+                calc_model.calc_elements.get(&element.number).and_then(|e| e.first())
+            else {
+                continue;
+            };
+            let active = if self.inactive_elements.contains(&element.number) {
+                // The inactive element is reactivated if the distance between its end nodes
+                // changes in the direction of its condition
+                let rotation = calc_element.rotation.to_radians();
+                let elongation = rotation.cos()
+                    * (node_results.get_global_displacement(element.node_end, 0)
+                        - node_results.get_global_displacement(element.node_start, 0))
+                    + rotation.sin()
+                        * (node_results.get_global_displacement(element.node_end, 1)
+                            - node_results.get_global_displacement(element.node_start, 1));
+                sign * elongation > DISPLACEMENT_TOLERANCE
+            } else {
+                let axial_force = calculate_axial_force_at(
+                    calc_element.length / 2.0,
+                    calc_element,
+                    loads,
+                    node_results,
+                    calc_settings,
+                );
+                sign * axial_force >= -FORCE_TOLERANCE
+            };
+            changed |= if active {
+                self.inactive_elements.remove(&element.number)
+            } else {
+                self.inactive_elements.insert(element.number)
+            };
+        }
+
+        for node in calc_model.structure_nodes.values() {
+            for dof in 0..3 {
+                let Some(sign) = get_support_sign(node, dof) else {
+                    continue;
+                };
+                let active = if self.inactive_supports.contains(&(node.number, dof)) {
+                    // The released support is reactivated if the node moves against the support
+                    sign * node_results.get_local_displacement(node.number, dof)
+                        < -DISPLACEMENT_TOLERANCE
+                } else {
+                    sign * node_results.get_support_reaction(node.number, dof) >= -FORCE_TOLERANCE
+                };
+                changed |= if active {
+                    self.inactive_supports.remove(&(node.number, dof))
+                } else {
+                    self.inactive_supports.insert((node.number, dof))
+                };
+            }
+        }
+        changed
+    }
+
+    /// Gets the results of the active set
+    /// * `converged` - false if the active set did not converge
+    /// * `iterations` - the number of the iterations
+    pub fn get_results(
+        &self,
+        calc_model: &CalcModel,
+        converged: bool,
+        iterations: usize,
+    ) -> ActiveSetResults {
+        let elements = calc_model
+            .structure_elements
+            .iter()
+            .filter(|e| e.behaviour != ElementBehaviour::Normal)
+            .map(|e| (e.number, !self.inactive_elements.contains(&e.number)))
+            .collect();
+        let mut supports = BTreeMap::new();
+        for node in calc_model.structure_nodes.values() {
+            if (0..3).any(|dof| get_support_sign(node, dof).is_some()) {
+                let active = [0, 1, 2].map(|dof| !self.inactive_supports.contains(&(node.number, dof)));
+                supports.insert(node.number, active);
+            }
+        }
+        ActiveSetResults { converged, iterations, elements, supports }
+    }
+}
+
+/// Gets the sign of the allowed reaction of the locked support direction: 1.0 for the positive
+/// reactions and -1.0 for the negative reactions. Returns None if the direction is not locked or
+/// it takes reactions in both directions.
+fn get_support_sign(node: &Node, dof: usize) -> Option<f64> {
+    if !node.support.get_support_lock(dof) {
+        return None;
+    }
+    match node.support.get_reaction_direction(dof) {
+        ReactionDirection::Both => None,
+        ReactionDirection::Positive => Some(1.0),
+        ReactionDirection::Negative => Some(-1.0),
+    }
+}
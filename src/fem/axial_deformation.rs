@@ -24,8 +24,8 @@ pub fn calculate_at(
         element, loads, calc_settings
     );
     // TODO The displacement at the end of the element is not taken into account.
-    let local_displacements = results.get_elem_local_displacements(
-        element
+    let local_displacements = results.get_elem_local_end_displacements(
+        element, loads, calc_settings
     );
 
     let e_m = element.elastic_modulus;
@@ -35,15 +35,15 @@ pub fn calculate_at(
         if load.element_number != element.calc_el_num {
             continue;
         }
-        // The factor to handle skewed loads
-        let x_dir_factor = load.rotation.to_radians().cos();
+        // The factor to handle skewed loads (the load component in the direction of the element)
+        let x_dir_factor = (load.rotation - element.rotation).to_radians().cos();
         match load.load_type {
             load::CalculationLoadType::Point => {
                 if load.offset_start <= x {
                     s_integral -= load.strength * x_dir_factor * (x - load.offset_start);
                 }
             }
-            load::CalculationLoadType::Rotational => {}
+            load::CalculationLoadType::Rotational | load::CalculationLoadType::Curvature => {}
             load::CalculationLoadType::Line => {
                 if load.offset_start <= x {
                     let load_length = x - load.offset_start;
@@ -58,9 +58,9 @@ pub fn calculate_at(
             load::CalculationLoadType::Triangular => {
                 // Triangular load with max load at left hand side
                 if load.offset_start < load.offset_end {
-                    s_integral -= handle_triang_ltr(load, x)
+                    s_integral -= handle_triang_ltr(load, x, x_dir_factor)
                 } else {
-                    s_integral -= handle_triang_rtl(load, x)
+                    s_integral -= handle_triang_rtl(load, x, x_dir_factor)
                 }
             }
             load::CalculationLoadType::Strain => {
@@ -81,8 +81,7 @@ pub fn calculate_at(
 
 /// Calculates the integral of the axial force function at x for a triangular load with the maximum load at the left hand side.
 /// ltr = Left to right
-fn handle_triang_ltr(load: &CalculationLoad, x: f64) -> f64 {
-    let x_dir_factor = load.rotation.to_radians().cos();
+fn handle_triang_ltr(load: &CalculationLoad, x: f64, x_dir_factor: f64) -> f64 {
     if load.offset_start <= x {
         if load.offset_end <= x {
             // Load strength shrinks and ends before x
@@ -119,8 +118,7 @@ fn handle_triang_ltr(load: &CalculationLoad, x: f64) -> f64 {
 
 /// Calculates the integral of the axial force function at x for a triangular load with the maximum load at the right hand side.
 /// ltr = Left to right
-fn handle_triang_rtl(load: &CalculationLoad, x: f64) -> f64 {
-    let x_dir_factor = load.rotation.to_radians().cos();
+fn handle_triang_rtl(load: &CalculationLoad, x: f64, x_dir_factor: f64) -> f64 {
     // Load offsets at left or right hand side
     let left = load.offset_end;
     let right = load.offset_start;
@@ -0,0 +1,74 @@
+//! The linear buckling (eigenvalue) analysis. The critical load factors α_cr are the eigenvalues of
+//! the generalized eigenproblem (K + α K_g) φ = 0, where K is the elastic stiffness matrix and K_g
+//! the geometric stiffness matrix from the axial forces of the load combination. The lowest
+//! eigenvalues are solved with the subspace iteration (see [super::eigen]).
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+use super::eigen;
+use super::fem_handler::StiffnessFactorization;
+use super::sparse::SparseMatrix;
+use super::CalcModel;
+use crate::settings::calc_settings::BucklingSettings;
+
+/// A solved buckling mode
+#[derive(Debug, Clone)]
+pub struct EigenMode {
+    /// The critical load factor α_cr
+    pub critical_load_factor: f64,
+    /// The mode shape normalized so that the largest translation is 1.0
+    pub mode_shape: Vec<f64>,
+}
+
+/// Solves the lowest critical load factors and the mode shapes. Returns the modes in the ascending
+/// order of the critical load factors and whether the factors converged. Only the positive
+/// critical load factors are returned (the factors that would reverse the axial forces are left
+/// out), so the modes are empty if there is no compression.
+/// * `factorization` - the factorized elastic stiffness matrix
+/// * `geometric_stiffness` - the global geometric stiffness matrix of the axial forces
+/// * `settings` - the buckling settings
+pub fn solve_modes(
+    factorization: &StiffnessFactorization,
+    geometric_stiffness: &SparseMatrix,
+    settings: &BucklingSettings,
+) -> (Vec<EigenMode>, bool) {
+    // K φ = α (-K_g) φ. The geometric stiffness is negative for the compressed elements.
+    let mut matrix = geometric_stiffness.clone();
+    matrix.scale(-1.0);
+    let (pairs, converged) = eigen::solve_lowest(
+        factorization,
+        &matrix,
+        settings.mode_count,
+        settings.max_iterations,
+        settings.tolerance,
+    );
+    let modes = pairs
+        .into_iter()
+        .map(|pair| EigenMode {
+            critical_load_factor: pair.eigenvalue,
+            mode_shape: eigen::normalize_to_largest_translation(pair.vector),
+        })
+        .collect();
+    (modes, converged)
+}
+
+/// Gets the effective buckling lengths of the compressed elements for the critical load factor.
+/// The buckling length is calculated from the critical axial force of the calculation element with
+/// the largest compression: L_cr = π √(EI / (α_cr N_Ed)).
+pub fn get_buckling_lengths(calc_model: &CalcModel, critical_load_factor: f64) -> BTreeMap<i32, f64> {
+    let mut result = BTreeMap::new();
+    for (el_number, calc_elements) in &calc_model.calc_elements {
+        let Some(element) = calc_elements
+            .iter()
+            .filter(|e| e.axial_force < 0.0)
+            .min_by(|a, b| a.axial_force.total_cmp(&b.axial_force))
+        else {
+            continue;
+        };
+        let critical_force = -element.axial_force * critical_load_factor;
+        let bending_stiffness = element.elastic_modulus * element.major_smoa;
+        result.insert(*el_number, PI * (bending_stiffness / critical_force).sqrt());
+    }
+    result
+}
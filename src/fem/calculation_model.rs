@@ -1,7 +1,8 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use crate::structure::{CalculationElement, Element, Node};
+use crate::structure::{CalculationElement, Constraint, Element, Node};
 
+#[derive(Clone)]
 pub struct CalcModel<'a> {
     /// The structure nodes
     pub structure_nodes: &'a BTreeMap<i32, Node>,
@@ -11,6 +12,14 @@ pub struct CalcModel<'a> {
     pub structure_elements: &'a Vec<Element>,
     /// The calculation elements map. The key is the model element number for the calculation elements
     pub calc_elements: BTreeMap<i32, Vec<CalculationElement<'a>>>,
+    /// The model element numbers of the inactive elements (the tension-only and compression-only
+    /// elements whose condition is not met). The inactive elements have no stiffness.
+    pub inactive_elements: BTreeSet<i32>,
+    /// The constraints between the structure nodes
+    pub constraints: &'a [Constraint],
+    /// The penalties of the constraint equations. The penalties are calculated once when the
+    /// constraints are set (see [super::constraints::get_penalties]).
+    pub constraint_penalties: Vec<f64>,
 }
 
 impl<'a> CalcModel<'a> {
@@ -26,11 +35,14 @@ impl<'a> CalcModel<'a> {
             extra_nodes,
             structure_elements,
             calc_elements,
+            inactive_elements: BTreeSet::new(),
+            constraints: &[],
+            constraint_penalties: vec![],
         }
     }
 
-    pub fn get_all_calc_elements(&'a self) -> Vec<&'a CalculationElement<'a>> {
-        let mut result: Vec<&'a CalculationElement> = Vec::new();
+    pub fn get_all_calc_elements(&self) -> Vec<&CalculationElement<'a>> {
+        let mut result: Vec<&CalculationElement<'a>> = Vec::new();
         for (_, v) in &self.calc_elements {
             for e in v.iter() {
                 result.push(e);
@@ -50,6 +62,17 @@ impl<'a> CalcModel<'a> {
         result
     }
 
+    /// Gets the calculation element of the model element at the position measured from the start
+    /// of the model element. The position at the split of two calculation elements is on the
+    /// latter one and the end of the model element is on the last one.
+    pub fn get_calc_element_at(&self, model_el_num: i32, pos_on_element: f64) -> Option<&CalculationElement<'a>> {
+        let calc_elements = self.calc_elements.get(&model_el_num)?;
+        calc_elements
+            .iter()
+            .find(|e| pos_on_element < e.offset_from_model_el + e.length)
+            .or(calc_elements.last())
+    }
+
     pub fn get_node_count(&self) -> usize {
         self.structure_nodes.len() + self.extra_nodes.len()
     }
@@ -0,0 +1,500 @@
+//! The deflections of the reinforced concrete elements by EN 1992-1-1 7.4.3. The curvature of the
+//! element is interpolated between the uncracked and the fully cracked states with the distribution
+//! coefficient ζ:
+//!
+//! 1/r = ζ * 1/r<sub>II</sub> + (1 - ζ) * 1/r<sub>I</sub>
+//!
+//! ζ = 1 - β * (M<sub>cr</sub> / M)²
+//!
+//! The concrete elements are split into calculation elements (segments) and each segment gets
+//! an effective second moment of area by the moment at the middle of the segment. Because the
+//! moments of statically indeterminate structures depend on the stiffnesses, the calculation is
+//! iterated until the displacements converge.
+//!
+//! The long-term deflections of the quasi-permanent load combinations take the creep into account
+//! with the effective modulus of elasticity E<sub>c,eff</sub> = E<sub>cm</sub> / (1 + φ(∞,t<sub>0</sub>))
+//! and the shrinkage with the shrinkage curvature 1/r<sub>cs</sub> = ε<sub>cs</sub> * α<sub>e</sub> * S / I
+//! (EN 1992-1-1 7.4.3 (5) and (6)).
+
+use std::collections::{BTreeMap, HashMap};
+
+use vputilslib::{equation_handler::EquationHandler, geometry2d};
+
+use crate::{
+    loads::load::{CalculationLoad, CalculationLoadType},
+    material::{Concrete, ConcreteCalcType, MaterialData},
+    profile::{smoa, PolygonProfile, Profile},
+    reinforcement::utils::elastic_centroid,
+    results::NodeResults,
+    settings::CalculationSettings,
+    structure::{CalculationElement, Element},
+};
+
+use super::internal_forces::calculate_moment_at;
+
+/// The section properties of a reinforced concrete element that are needed for the interpolation
+/// between the uncracked and the fully cracked states.
+#[derive(Debug, Copy, Clone)]
+pub struct CrackingProperties {
+    /// The second moment of area of the uncracked section (with reinforcement)
+    pub smoa_uncracked: f64,
+    /// The second moment of area of the cracked section with the positive bending moment
+    /// (compression on top)
+    pub smoa_cracked_positive: f64,
+    /// The second moment of area of the cracked section with the negative bending moment
+    /// (compression at the bottom)
+    pub smoa_cracked_negative: f64,
+    /// The cracking moment with the positive bending moment (tension at the bottom, Nmm)
+    pub cracking_moment_positive: f64,
+    /// The cracking moment with the negative bending moment (tension on top, Nmm)
+    pub cracking_moment_negative: f64,
+}
+
+impl CrackingProperties {
+    /// Gets the effective second moment of area for given bending moment. The value is the
+    /// harmonic interpolation of the uncracked and cracked values with the distribution
+    /// coefficient ζ.
+    /// * `moment` - the bending moment (Nmm)
+    /// * `beta` - the coefficient for the duration of the loading (β)
+    pub fn get_effective_smoa(&self, moment: f64, beta: f64) -> f64 {
+        let (smoa_cracked, cracking_moment) = if moment >= 0.0 {
+            (self.smoa_cracked_positive, self.cracking_moment_positive)
+        } else {
+            (self.smoa_cracked_negative, self.cracking_moment_negative)
+        };
+        let zeta = get_distribution_coefficient(moment, cracking_moment, beta);
+        1.0 / (zeta / smoa_cracked + (1.0 - zeta) / self.smoa_uncracked)
+    }
+}
+
+/// Calculates the distribution coefficient ζ (EN 1992-1-1 expression 7.19). The ratio of the
+/// stresses σ<sub>sr</sub>/σ<sub>s</sub> is replaced with the ratio of the moments
+/// M<sub>cr</sub>/M. The value is zero for the uncracked sections (|M| < M<sub>cr</sub>).
+pub fn get_distribution_coefficient(moment: f64, cracking_moment: f64, beta: f64) -> f64 {
+    let moment = moment.abs();
+    if moment <= cracking_moment || moment == 0.0 {
+        return 0.0;
+    }
+    1.0 - beta * (cracking_moment / moment).powi(2)
+}
+
+/// Gets the cracking properties for the element. Returns None if the element is not a reinforced
+/// concrete element with a polygon profile.
+pub fn get_cracking_properties(
+    element: &Element,
+    calc_settings: &CalculationSettings,
+) -> Option<CrackingProperties> {
+    let concrete = match &element.material {
+        MaterialData::Concrete(c) => c,
+        _ => return None,
+    };
+    let profile = match &element.profile {
+        Profile::PolygonProfile(p) => p,
+        _ => return None,
+    };
+    if concrete.reinforcement.main_rebars.is_empty() {
+        return None;
+    }
+    Some(cracking_properties_for(profile, concrete, calc_settings))
+}
+
+/// Calculates the cracking properties for the reinforced concrete section
+fn cracking_properties_for(
+    profile: &PolygonProfile,
+    concrete: &Concrete,
+    calc_settings: &CalculationSettings,
+) -> CrackingProperties {
+    let mut uncracked = concrete.clone();
+    uncracked.concrete_calc_type = ConcreteCalcType::WithReinforcement;
+    let smoa_uncracked = smoa::smoa_with_reinf(profile, &uncracked, calc_settings);
+    let (_, cog_y) = elastic_centroid(profile, &uncracked, calc_settings);
+    let (bottom, top) = profile
+        .polygon
+        .points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), p| (min.min(p.y), max.max(p.y)));
+    let f_ctm = concrete.get_mean_tensile_strength();
+
+    CrackingProperties {
+        smoa_uncracked,
+        smoa_cracked_positive: smoa::smoa_cracked(profile, concrete, true),
+        smoa_cracked_negative: smoa::smoa_cracked(profile, concrete, false),
+        cracking_moment_positive: f_ctm * smoa_uncracked / (cog_y - bottom),
+        cracking_moment_negative: f_ctm * smoa_uncracked / (top - cog_y),
+    }
+}
+
+/// Gets the cracking properties for all the reinforced concrete elements. The key is the element
+/// number. Returns an empty map if the concrete deflections are not enabled in the settings. The
+/// tapered elements are skipped (see [ConcreteProperties]).
+pub fn get_cracking_properties_map(
+    elements: &Vec<Element>,
+    calc_settings: &CalculationSettings,
+) -> HashMap<i32, CrackingProperties> {
+    let mut result = HashMap::new();
+    if !calc_settings.concrete_deflections.enabled {
+        return result;
+    }
+    for e in elements {
+        if e.section_variation.is_active() {
+            continue;
+        }
+        if let Some(props) = get_cracking_properties(e, calc_settings) {
+            result.insert(e.number, props);
+        }
+    }
+    result
+}
+
+/// Gets the split positions for the elements in the cracking properties map. The elements are
+/// split into equal segments by the segment count in the settings.
+pub fn get_split_positions(
+    elements: &Vec<Element>,
+    nodes: &BTreeMap<i32, crate::structure::Node>,
+    cracking_properties: &HashMap<i32, CrackingProperties>,
+    calc_settings: &CalculationSettings,
+) -> HashMap<i32, Vec<i64>> {
+    let mut result = HashMap::new();
+    let segment_count = calc_settings.concrete_deflections.segment_count.max(1);
+    for e in elements {
+        if !cracking_properties.contains_key(&e.number) {
+            continue;
+        }
+        let length = e.get_length(nodes);
+        let positions = (1..segment_count)
+            .map(|i| (length * i as f64 / segment_count as f64).round() as i64)
+            .collect();
+        result.insert(e.number, positions);
+    }
+    result
+}
+
+/// Updates the second moments of area of the calculation elements by the moments at the middle
+/// of the calculation elements.
+/// * `beta` - the coefficient for the duration of the loading (β)
+pub fn update_effective_stiffness(
+    calc_elements: &mut BTreeMap<i32, Vec<CalculationElement>>,
+    cracking_properties: &HashMap<i32, CrackingProperties>,
+    loads: &Vec<CalculationLoad>,
+    node_results: &NodeResults,
+    beta: f64,
+    calc_settings: &CalculationSettings,
+) {
+    for (el_number, elements) in calc_elements.iter_mut() {
+        let props = match cracking_properties.get(el_number) {
+            Some(p) => p,
+            None => continue,
+        };
+        for element in elements.iter_mut() {
+            let moment =
+                calculate_moment_at(element.length / 2.0, element, loads, node_results, calc_settings);
+            element.major_smoa = props.get_effective_smoa(moment, beta);
+        }
+    }
+}
+
+/// The creep and shrinkage properties of a concrete element for the long-term loads
+#[derive(Debug, Copy, Clone)]
+pub struct LongTermProperties {
+    /// The final creep coefficient φ(∞,t<sub>0</sub>)
+    pub creep_coefficient: f64,
+    /// The final total shrinkage strain ε<sub>cs</sub>
+    pub shrinkage_strain: f64,
+    /// The effective modulus of elasticity E<sub>c,eff</sub> (MPa)
+    pub elastic_modulus: f64,
+    /// The area of the section calculated with the effective modulus of elasticity (mm²)
+    pub profile_area: f64,
+    /// The second moment of area of the uncracked section calculated with the effective modulus of
+    /// elasticity (mm⁴)
+    pub major_smoa: f64,
+    /// The shrinkage curvature of the uncracked section (1/mm)
+    pub shrinkage_curvature_uncracked: f64,
+    /// The shrinkage curvature of the cracked section with the positive bending moment (1/mm)
+    pub shrinkage_curvature_cracked_positive: f64,
+    /// The shrinkage curvature of the cracked section with the negative bending moment (1/mm)
+    pub shrinkage_curvature_cracked_negative: f64,
+}
+
+impl LongTermProperties {
+    /// Gets the shrinkage curvature for given bending moment. The value is interpolated between
+    /// the uncracked and the cracked values with the distribution coefficient ζ. If the cracking
+    /// properties are not given, the section is considered to be uncracked.
+    /// * `moment` - the bending moment (Nmm)
+    /// * `cracking_properties` - the cracking properties calculated with the effective modulus
+    /// * `beta` - the coefficient for the duration of the loading (β)
+    pub fn get_shrinkage_curvature(
+        &self,
+        moment: f64,
+        cracking_properties: Option<&CrackingProperties>,
+        beta: f64,
+    ) -> f64 {
+        let props = match cracking_properties {
+            Some(p) => p,
+            None => return self.shrinkage_curvature_uncracked,
+        };
+        let (curvature_cracked, cracking_moment) = if moment >= 0.0 {
+            (self.shrinkage_curvature_cracked_positive, props.cracking_moment_positive)
+        } else {
+            (self.shrinkage_curvature_cracked_negative, props.cracking_moment_negative)
+        };
+        let zeta = get_distribution_coefficient(moment, cracking_moment, beta);
+        zeta * curvature_cracked + (1.0 - zeta) * self.shrinkage_curvature_uncracked
+    }
+}
+
+/// Gets the notional size of the cross section of the element (h<sub>0</sub> = 2A<sub>c</sub>/u, mm).
+/// The whole perimeter of the section is assumed to be exposed to drying.
+pub fn get_notional_size(element: &Element, calc_settings: &CalculationSettings) -> f64 {
+    let area = match &element.profile {
+        Profile::PolygonProfile(p) => geometry2d::calculate_area(&p.polygon).abs(),
+        p => p.get_area(&element.material, calc_settings),
+    };
+    2.0 * area / element.profile.get_perimeter()
+}
+
+/// Gets the creep and shrinkage properties for the element. Returns None if the element is not
+/// a concrete element.
+pub fn get_long_term_properties(
+    element: &Element,
+    calc_settings: &CalculationSettings,
+) -> Option<LongTermProperties> {
+    let concrete = match &element.material {
+        MaterialData::Concrete(c) => c,
+        _ => return None,
+    };
+    let notional_size = get_notional_size(element, calc_settings);
+    let creep_coefficient = concrete.get_creep_coefficient(notional_size);
+    let shrinkage_strain = concrete.get_shrinkage_strain(notional_size);
+    // The section properties are calculated with the effective modulus, which increases the
+    // effect of the reinforcement (α<sub>e</sub> = E<sub>s</sub> / E<sub>c,eff</sub>)
+    let mut effective = concrete.clone();
+    effective.elastic_modulus = concrete.get_effective_elastic_modulus(notional_size);
+    let effective_material = MaterialData::Concrete(effective.clone());
+
+    let mut props = LongTermProperties {
+        creep_coefficient,
+        shrinkage_strain,
+        elastic_modulus: effective.elastic_modulus,
+        profile_area: element.profile.get_area(&effective_material, calc_settings),
+        major_smoa: element.profile.get_major_second_mom_of_area(&effective_material, calc_settings),
+        shrinkage_curvature_uncracked: 0.0,
+        shrinkage_curvature_cracked_positive: 0.0,
+        shrinkage_curvature_cracked_negative: 0.0,
+    };
+    let profile = match &element.profile {
+        Profile::PolygonProfile(p) => p,
+        _ => return Some(props),
+    };
+    if concrete.reinforcement.main_rebars.is_empty() {
+        return Some(props);
+    }
+    effective.concrete_calc_type = ConcreteCalcType::WithReinforcement;
+    // The first moment of area of the transformed reinforcement about given axis
+    let first_moment = |y_axis: f64| -> f64 {
+        let mut sum = 0.0;
+        for r in &effective.reinforcement.main_rebars {
+            for s in r.get_calculation_rebars(profile, &EquationHandler::new()) {
+                let alpha_e = s.reinf_data.get_elastic_modulus() / effective.elastic_modulus;
+                sum += alpha_e * s.area * (y_axis - s.y);
+            }
+        }
+        sum
+    };
+    let (_, cog_y) = elastic_centroid(profile, &effective, calc_settings);
+    let smoa_uncracked = smoa::smoa_with_reinf(profile, &effective, calc_settings);
+    props.shrinkage_curvature_uncracked = shrinkage_strain * first_moment(cog_y) / smoa_uncracked;
+    for compression_on_top in [true, false] {
+        let y_na = smoa::neutral_axis_cracked(profile, &effective, compression_on_top);
+        let smoa_cracked = smoa::smoa_cracked(profile, &effective, compression_on_top);
+        let curvature = shrinkage_strain * first_moment(y_na) / smoa_cracked;
+        if compression_on_top {
+            props.shrinkage_curvature_cracked_positive = curvature;
+        } else {
+            props.shrinkage_curvature_cracked_negative = curvature;
+        }
+    }
+    Some(props)
+}
+
+/// Gets the cracking properties of the element for the long-term loads. The properties are
+/// calculated with the effective modulus of elasticity.
+fn get_long_term_cracking_properties(
+    element: &Element,
+    long_term_properties: &LongTermProperties,
+    calc_settings: &CalculationSettings,
+) -> Option<CrackingProperties> {
+    let mut effective = match &element.material {
+        MaterialData::Concrete(c) => c.clone(),
+        _ => return None,
+    };
+    effective.elastic_modulus = long_term_properties.elastic_modulus;
+    let profile = match &element.profile {
+        Profile::PolygonProfile(p) => p,
+        _ => return None,
+    };
+    if effective.reinforcement.main_rebars.is_empty() {
+        return None;
+    }
+    Some(cracking_properties_for(profile, &effective, calc_settings))
+}
+
+/// The properties of the concrete elements for the deflection calculations. The key of the maps
+/// is the element number. The properties are calculated with the profile of the element, so they
+/// are not calculated for the tapered elements (see [crate::structure::SectionVariation]). The
+/// tapered elements are calculated with the uncracked short-term section properties of their
+/// segments.
+#[derive(Debug, Default)]
+pub struct ConcreteProperties {
+    /// The cracking properties for the short-term loads. Empty if the cracking is not enabled.
+    pub cracking: HashMap<i32, CrackingProperties>,
+    /// The creep and shrinkage properties. Empty if the long-term deflections are not enabled.
+    pub long_term: HashMap<i32, LongTermProperties>,
+    /// The cracking properties for the long-term loads. Empty if the cracking or the long-term
+    /// deflections are not enabled.
+    pub long_term_cracking: HashMap<i32, CrackingProperties>,
+}
+
+impl ConcreteProperties {
+    /// Gets the properties for the concrete elements by the concrete deflection settings
+    pub fn new(elements: &Vec<Element>, calc_settings: &CalculationSettings) -> Self {
+        let settings = &calc_settings.concrete_deflections;
+        if settings.enabled || settings.long_term {
+            for e in elements.iter().filter(|e| e.section_variation.is_active()) {
+                if let MaterialData::Concrete(_) = e.material {
+                    println!(
+                        "The cracking and the long-term deflections are not calculated for the tapered concrete element {}. The element is calculated with the uncracked short-term section properties.",
+                        e.number
+                    );
+                }
+            }
+        }
+        let cracking = get_cracking_properties_map(elements, calc_settings);
+        let mut long_term = HashMap::new();
+        let mut long_term_cracking = HashMap::new();
+        if calc_settings.concrete_deflections.long_term {
+            for e in elements.iter().filter(|e| !e.section_variation.is_active()) {
+                let props = match get_long_term_properties(e, calc_settings) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                if cracking.contains_key(&e.number) {
+                    if let Some(c) = get_long_term_cracking_properties(e, &props, calc_settings) {
+                        long_term_cracking.insert(e.number, c);
+                    }
+                }
+                long_term.insert(e.number, props);
+            }
+        }
+        Self { cracking, long_term, long_term_cracking }
+    }
+
+    /// Gets the cracking properties for the short-term or the long-term loads
+    pub fn get_cracking(&self, long_term: bool) -> &HashMap<i32, CrackingProperties> {
+        if long_term {
+            &self.long_term_cracking
+        } else {
+            &self.cracking
+        }
+    }
+}
+
+/// Sets the effective modulus of elasticity and the section properties calculated with it to the
+/// calculation elements of the concrete elements.
+pub fn apply_long_term_properties(
+    calc_elements: &mut BTreeMap<i32, Vec<CalculationElement>>,
+    long_term_properties: &HashMap<i32, LongTermProperties>,
+) {
+    for (el_number, elements) in calc_elements.iter_mut() {
+        let props = match long_term_properties.get(el_number) {
+            Some(p) => p,
+            None => continue,
+        };
+        for element in elements.iter_mut() {
+            element.elastic_modulus = props.elastic_modulus;
+            element.profile_area = props.profile_area;
+            element.major_smoa = props.major_smoa;
+        }
+    }
+}
+
+/// Creates the shrinkage curvature loads for the calculation elements of the concrete elements.
+/// The curvatures are interpolated by the moments at the middle of the calculation elements. If
+/// the previous results are not given, the sections are considered to be uncracked.
+/// * `previous` - the loads and the node results of the previous solution
+pub fn get_shrinkage_loads(
+    calc_elements: &BTreeMap<i32, Vec<CalculationElement>>,
+    concrete_properties: &ConcreteProperties,
+    previous: Option<(&Vec<CalculationLoad>, &NodeResults)>,
+    calc_settings: &CalculationSettings,
+) -> Vec<CalculationLoad> {
+    let beta = calc_settings.concrete_deflections.long_term_load_duration_coefficient;
+    let mut result = vec![];
+    for (el_number, elements) in calc_elements.iter() {
+        let props = match concrete_properties.long_term.get(el_number) {
+            Some(p) => p,
+            None => continue,
+        };
+        let cracking_properties = concrete_properties.long_term_cracking.get(el_number);
+        for element in elements.iter() {
+            let moment = match previous {
+                Some((loads, node_results)) => {
+                    calculate_moment_at(element.length / 2.0, element, loads, node_results, calc_settings)
+                }
+                None => 0.0,
+            };
+            let curvature = props.get_shrinkage_curvature(moment, cracking_properties, beta);
+            if curvature == 0.0 {
+                continue;
+            }
+            result.push(CalculationLoad {
+                name: "Shrinkage".to_string(),
+                load_type: CalculationLoadType::Curvature,
+                offset_start: 0.0,
+                offset_end: element.length,
+                strength: curvature,
+                rotation: 0.0,
+                element_number: element.calc_el_num,
+            });
+        }
+    }
+    result
+}
+
+/// Checks if the displacements have converged, i.e. the largest change of the displacements is
+/// smaller than the tolerance times the largest displacement.
+pub fn displacements_converged(previous: &[f64], current: &[f64], tolerance: f64) -> bool {
+    let max_displacement = current.iter().fold(0.0f64, |acc, d| acc.max(d.abs()));
+    let max_change = previous
+        .iter()
+        .zip(current.iter())
+        .fold(0.0f64, |acc, (p, c)| acc.max((p - c).abs()));
+    max_change <= tolerance * max_displacement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_coefficient() {
+        assert_eq!(get_distribution_coefficient(10e6, 20e6, 1.0), 0.0);
+        assert_eq!(get_distribution_coefficient(-40e6, 20e6, 1.0), 0.75);
+        assert_eq!(get_distribution_coefficient(40e6, 20e6, 0.5), 0.875);
+    }
+
+    #[test]
+    fn test_effective_smoa() {
+        let props = CrackingProperties {
+            smoa_uncracked: 4.0,
+            smoa_cracked_positive: 1.0,
+            smoa_cracked_negative: 2.0,
+            cracking_moment_positive: 10.0,
+            cracking_moment_negative: 20.0,
+        };
+        assert_eq!(props.get_effective_smoa(5.0, 1.0), 4.0);
+        // ζ = 0.75 => 1 / (0.75 / 1 + 0.25 / 4)
+        assert!((props.get_effective_smoa(20.0, 1.0) - 1.0 / 0.8125).abs() < 1e-12);
+        // ζ = 0.75 => 1 / (0.75 / 2 + 0.25 / 4)
+        assert!((props.get_effective_smoa(-40.0, 1.0) - 1.0 / 0.4375).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,136 @@
+//! The nodal constraints (the equal degrees of freedom and the rigid links, see [Constraint]). The
+//! constraints are applied to the global stiffness matrix with the penalty method: each constraint
+//! equation c u = 0 adds the stiffness α c<sup>T</sup> c, where the penalty α is large compared to
+//! the assembled stiffnesses of the constrained degrees of freedom. The constraint forces are the forces of the penalty
+//! stiffnesses (-α c<sup>T</sup> c u).
+//!
+//! The support reactions are calculated from the same global stiffness matrix, so the reaction at
+//! a supported node includes the constraint forces at the node. E.g. the support of the slave node
+//! of a rigid link takes the forces that the link carries from the master node.
+
+use super::sparse::SparseMatrix;
+use super::{stiffness, CalcModel};
+use crate::results::NodeResults;
+use crate::settings::CalculationSettings;
+use crate::structure::{Constraint, ConstraintType, NodeCollection};
+
+/// The penalty of the constraint equation relative to the largest diagonal value of the assembled
+/// stiffness matrix at the tied degrees of freedom of the equation
+const PENALTY_FACTOR: f64 = 1e8;
+
+/// The constraint equation as the rows of the global matrices and the coefficients
+struct ConstraintEquation {
+    terms: Vec<(usize, f64)>,
+    /// True for the equations of the rotations
+    rotation: bool,
+}
+
+/// Adds the penalty stiffnesses of the constraints to the global stiffness matrix (in the global
+/// coordinate system, before the support rotations are applied)
+pub fn apply_constraints_to_stiffness(calc_model: &CalcModel, matrix: &mut SparseMatrix) {
+    if calc_model.constraints.is_empty() {
+        return;
+    }
+    for (equation, penalty) in get_equations(calc_model).iter().zip(&calc_model.constraint_penalties) {
+        for (row, row_coefficient) in &equation.terms {
+            for (col, col_coefficient) in &equation.terms {
+                matrix.add(*row, *col, penalty * row_coefficient * col_coefficient);
+            }
+        }
+    }
+}
+
+/// Gets the forces (N, Nmm) that the constraints apply to the nodes in the global coordinate
+/// system in the same order as the displacements. Returns an empty vector if there are no
+/// constraints.
+pub fn get_constraint_forces(calc_model: &CalcModel, node_results: &NodeResults) -> Vec<f64> {
+    if calc_model.constraints.is_empty() {
+        return vec![];
+    }
+    let mut forces = vec![0.0; node_results.global_displacements.len()];
+    for (equation, penalty) in get_equations(calc_model).iter().zip(&calc_model.constraint_penalties) {
+        let violation: f64 = equation
+            .terms
+            .iter()
+            .map(|(row, coefficient)| coefficient * node_results.global_displacements[*row])
+            .sum();
+        for (row, coefficient) in &equation.terms {
+            forces[*row] -= penalty * coefficient * violation;
+        }
+    }
+    forces
+}
+
+/// Gets the penalties of the constraint equations (in the order of [get_equations]) from the
+/// diagonal values of the assembled stiffness matrix at the tied degrees of freedom. The penalty
+/// is relative to the stiffness of the constrained degrees of freedom instead of the stiffest
+/// element, because a too large penalty loses the precision of the solution (e.g. the horizontal
+/// stiffness of a column is its bending stiffness, which is small compared to its axial stiffness).
+/// The penalties are calculated once for the calculation model (see
+/// [CalcModel::constraint_penalties]), so the same penalties are used for the stiffness matrix and
+/// the constraint forces, also when the tension-only or compression-only elements become inactive.
+pub fn get_penalties(calc_model: &CalcModel, settings: &CalculationSettings) -> Vec<f64> {
+    let matrix = stiffness::create_joined_stiffness_matrix(calc_model, settings);
+    let diagonal = |row: usize| matrix.get(row, row).abs();
+    // E.g. the nodes that have no elements have no stiffness
+    let max_diagonal = (0..matrix.nrows()).map(diagonal).fold(1.0, f64::max);
+    get_equations(calc_model)
+        .iter()
+        .map(|equation| {
+            let tied_diagonal = equation
+                .terms
+                .iter()
+                .filter(|(row, _)| (row % 3 == 2) == equation.rotation)
+                .map(|(row, _)| diagonal(*row))
+                .fold(0.0, f64::max);
+            let tied_diagonal = if tied_diagonal > 0.0 { tied_diagonal } else { max_diagonal };
+            tied_diagonal * PENALTY_FACTOR
+        })
+        .collect()
+}
+
+/// Gets the constraint equations of all the constraints. The constraints between the nodes that
+/// do not exist are skipped.
+fn get_equations(calc_model: &CalcModel) -> Vec<ConstraintEquation> {
+    calc_model
+        .constraints
+        .iter()
+        .flat_map(|c| get_constraint_equations(c, calc_model.structure_nodes))
+        .collect()
+}
+
+/// Gets the constraint equations of the tied degrees of freedom of the constraint. The rigid link
+/// moves the slave node by the rotation of the master node (-θ * z, θ * x), where (x, z) is the
+/// offset from the master node to the slave node (see
+/// [super::matrices::get_rigid_offset_matrix]).
+fn get_constraint_equations(constraint: &Constraint, nodes: &NodeCollection) -> Vec<ConstraintEquation> {
+    let (Some(master), Some(slave)) = (
+        nodes.get(&constraint.master_node),
+        nodes.get(&constraint.slave_node),
+    ) else {
+        return vec![];
+    };
+    if master.number == slave.number {
+        return vec![];
+    }
+    let dof = 3;
+    let m = (master.number - 1) as usize * dof;
+    let s = (slave.number - 1) as usize * dof;
+    let offset_x = slave.point.x - master.point.x;
+    let offset_z = slave.point.y - master.point.y;
+    let rigid = constraint.constraint_type == ConstraintType::RigidLink;
+    let mut equations = vec![];
+    for i in (0..dof).filter(|i| constraint.is_tied(*i)) {
+        // u_s - u_m - (rotation terms of the rigid link) = 0
+        let mut terms = vec![(s + i, 1.0), (m + i, -1.0)];
+        if rigid {
+            match i {
+                0 => terms.push((m + 2, offset_z)),
+                1 => terms.push((m + 2, -offset_x)),
+                _ => {}
+            }
+        }
+        equations.push(ConstraintEquation { terms, rotation: i == 2 });
+    }
+    equations
+}
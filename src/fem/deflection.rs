@@ -24,12 +24,13 @@ pub fn calculate_at(
         element, loads, 
         settings
     );
-    let local_displacements = results.get_elem_local_displacements(
-        element
+    let local_displacements = results.get_elem_local_end_displacements(
+        element, loads,
+        settings
     );
 
     let e_m = element.elastic_modulus;
-    let s_mom_area = element.profile.get_major_second_mom_of_area(&element.material, settings);
+    let s_mom_area = element.major_smoa;
 
     for load in loads {
         if load.element_number != element.calc_el_num {
@@ -70,6 +71,10 @@ pub fn calculate_at(
                     d_integral += handle_triang_rtl(element, load, x)
                 }
             }
+            load::CalculationLoadType::Curvature => {
+                // The double integral of the imposed curvature times EI
+                d_integral += e_m * s_mom_area * load.strength * x.powi(2) / 2.0;
+            }
             load::CalculationLoadType::Strain => {}
         };
     }
@@ -83,7 +88,61 @@ pub fn calculate_at(
     d_integral -= local_reactions[(2, 0)] * x.powi(2) / 2.0;
 
     // The deflection value
-    d_integral / (e_m * s_mom_area)
+    let mut deflection = d_integral / (e_m * s_mom_area);
+
+    // The shear deflection of Timoshenko beam (the integral of the shear force divided by G*As)
+    let ga_s = element.get_shear_stiffness(settings);
+    if ga_s > 0.0 {
+        let mut s_integral = -local_reactions[(1, 0)] * x;
+        for load in loads {
+            if load.element_number != element.calc_el_num {
+                continue;
+            }
+            s_integral -= shear_integral_from_load(x, element, load);
+        }
+        deflection += s_integral / ga_s;
+    }
+
+    deflection
+}
+
+/// Calculates the integral ∫ q(t) * (x - t) dt from the start of the element to x for the load
+/// component in the elements local Z-direction (the first moment of the load about x). Used to
+/// integrate the shear force of the element.
+pub fn shear_integral_from_load(x: f64, element: &CalculationElement, load: &CalculationLoad) -> f64 {
+    let z_dir_factor = (load.rotation - element.rotation).to_radians().sin();
+    match load.load_type {
+        load::CalculationLoadType::Point => {
+            if load.offset_start <= x {
+                load.strength * z_dir_factor * (x - load.offset_start)
+            } else {
+                0.0
+            }
+        }
+        load::CalculationLoadType::Line | load::CalculationLoadType::Triangular => {
+            let left = load.offset_start.min(load.offset_end);
+            let right = load.offset_start.max(load.offset_end).min(x);
+            if right <= left {
+                return 0.0;
+            }
+            // The strength of the load at t
+            let strength_at = |t: f64| -> f64 {
+                match load.load_type {
+                    load::CalculationLoadType::Triangular => {
+                        // The maximum value is at the offset start
+                        load.strength * (1.0 - (t - load.offset_start).abs() / load.get_length())
+                    }
+                    _ => load.strength,
+                }
+            };
+            // Simpson's rule is exact for the (at most) quadratic integrand
+            let f = |t: f64| strength_at(t) * z_dir_factor * (x - t);
+            (right - left) / 6.0 * (f(left) + 4.0 * f((left + right) / 2.0) + f(right))
+        }
+        load::CalculationLoadType::Rotational
+        | load::CalculationLoadType::Strain
+        | load::CalculationLoadType::Curvature => 0.0,
+    }
 }
 
 /// Calculates the moment at x for a triangular load with the maximum load at the left hand side.
@@ -0,0 +1,216 @@
+//! The generalized eigenproblem K φ = λ B φ, where K is the elastic stiffness matrix and B a
+//! symmetric matrix (the mass matrix in the modal analysis or the negative geometric stiffness
+//! matrix in the buckling analysis). The lowest eigenvalues are solved with the subspace
+//! iteration, which uses the factorized stiffness matrix, so only small dense eigenproblems (the
+//! size of the subspace) are solved.
+
+use nalgebra::{DMatrix, SymmetricEigen};
+
+use super::fem_handler::StiffnessFactorization;
+use super::sparse::SparseMatrix;
+
+/// The eigenvalues of the reduced stiffness matrix that are smaller than this times the largest
+/// eigenvalue are left out (the iteration vectors are linearly dependent)
+const DEPENDENCY_TOLERANCE: f64 = 1e-10;
+
+/// A solved eigenvalue and the eigenvector
+#[derive(Debug, Clone)]
+pub struct EigenPair {
+    /// The eigenvalue λ
+    pub eigenvalue: f64,
+    /// The eigenvector in the same order as the displacements. The vector is normalized so that
+    /// φᵀ B φ = 1.
+    pub vector: Vec<f64>,
+}
+
+/// Solves the lowest positive eigenvalues and the eigenvectors with the subspace iteration. Returns
+/// the eigenpairs in the ascending order of the eigenvalues and whether the eigenvalues converged.
+/// The eigenvalues are solved only in the subspace where B is positive, so there are no eigenpairs
+/// if B has no positive diagonal values at the unknown rows.
+/// * `factorization` - the factorized stiffness matrix
+/// * `matrix` - the matrix B
+/// * `count` - the number of the eigenpairs
+/// * `max_iterations` - the maximum number of the iterations
+/// * `tolerance` - the iteration is stopped when the relative change of the eigenvalues is smaller
+///   than the tolerance
+pub fn solve_lowest(
+    factorization: &StiffnessFactorization,
+    matrix: &SparseMatrix,
+    count: usize,
+    max_iterations: usize,
+    tolerance: f64,
+) -> (Vec<EigenPair>, bool) {
+    if factorization.is_singular() {
+        return (vec![], false);
+    }
+    // The subspace is larger than the number of the eigenpairs to speed up the convergence
+    let subspace_size = (2 * count).min(count + 8).min(factorization.unknown_rows().len());
+    let mut vectors = get_starting_vectors(factorization, matrix, subspace_size);
+    if vectors.ncols() == 0 {
+        return (vec![], true);
+    }
+
+    let mut eigenvalues: Vec<f64> = vec![];
+    let mut converged = false;
+    for _ in 0..max_iterations {
+        let loads = matrix * &vectors;
+        let new_vectors = factorization.solve(&loads);
+        // The projections to the subspace. K Y equals to the loads at the unknown rows and the
+        // other rows of Y are zero, so Yᵀ K Y = Yᵀ * loads.
+        let reduced_stiffness = symmetric(new_vectors.transpose() * &loads);
+        let reduced_matrix = symmetric(new_vectors.transpose() * (matrix * &new_vectors));
+        let Some((inverse_eigenvalues, eigenvectors)) =
+            solve_reduced(reduced_stiffness, reduced_matrix)
+        else {
+            return (vec![], true);
+        };
+        vectors = new_vectors * eigenvectors;
+        // The eigenvalues of the reduced problem are the inverses of the eigenvalues
+        let new_eigenvalues: Vec<f64> = inverse_eigenvalues
+            .iter()
+            .filter(|e| **e > 0.0)
+            .take(count)
+            .map(|e| 1.0 / e)
+            .collect();
+        converged = new_eigenvalues.len() == eigenvalues.len()
+            && new_eigenvalues
+                .iter()
+                .zip(&eigenvalues)
+                .all(|(new, old)| ((new - old) / new).abs() <= tolerance);
+        eigenvalues = new_eigenvalues;
+        if converged {
+            break;
+        }
+    }
+
+    // The vectors are stiffness orthonormal (φᵀ K φ = 1), so φᵀ B φ = 1 / λ
+    let pairs = eigenvalues
+        .iter()
+        .enumerate()
+        .map(|(i, eigenvalue)| EigenPair {
+            eigenvalue: *eigenvalue,
+            vector: vectors.column(i).iter().map(|v| v * eigenvalue.sqrt()).collect(),
+        })
+        .collect();
+    (pairs, converged)
+}
+
+/// Gets the translation with the largest absolute value (with its sign) of the vector (e.g. a mode
+/// shape). The rotations are every third value of the vector.
+pub fn get_largest_translation(vector: &[f64]) -> f64 {
+    vector
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % 3 != 2)
+        .fold(0.0f64, |acc, (_, v)| if v.abs() > acc.abs() { *v } else { acc })
+}
+
+/// Normalizes the vector (e.g. a mode shape) so that the largest translation is 1.0 (see
+/// [get_largest_translation])
+pub fn normalize_to_largest_translation(mut vector: Vec<f64>) -> Vec<f64> {
+    let largest = get_largest_translation(&vector);
+    if largest != 0.0 {
+        for value in vector.iter_mut() {
+            *value /= largest;
+        }
+    }
+    vector
+}
+
+/// Gets the starting vectors of the subspace iteration. The first vector is the diagonal of B and
+/// the others are unit vectors at the rows with the largest ratios of B and the stiffness. Only the
+/// rows with positive diagonal values of B are used.
+fn get_starting_vectors(
+    factorization: &StiffnessFactorization,
+    matrix: &SparseMatrix,
+    size: usize,
+) -> DMatrix<f64> {
+    let stiffness = factorization.stiffness();
+    let mut ratios: Vec<(usize, f64)> = factorization
+        .unknown_rows()
+        .iter()
+        .map(|r| (*r, matrix.get(*r, *r) / stiffness.get(*r, *r).abs()))
+        .filter(|(_, ratio)| *ratio > 0.0)
+        .collect();
+    ratios.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let size = size.min(ratios.len());
+    let mut vectors = DMatrix::zeros(stiffness.nrows(), size);
+    if size == 0 {
+        return vectors;
+    }
+    for (row, _) in ratios.iter() {
+        vectors[(*row, 0)] = matrix.get(*row, *row);
+    }
+    for (col, (row, _)) in ratios.iter().take(size - 1).enumerate() {
+        vectors[(*row, col + 1)] = 1.0;
+    }
+    vectors
+}
+
+/// Solves the reduced eigenproblem B_r q = μ K_r q, where μ = 1 / λ. The reduced stiffness matrix
+/// is transformed into the identity matrix with its eigenvectors, which also leaves out the linearly
+/// dependent iteration vectors. Returns the eigenvalues μ in the descending order and the
+/// corresponding eigenvectors (K_r orthonormal), or None if the reduced stiffness matrix is zero.
+fn solve_reduced(
+    stiffness: DMatrix<f64>,
+    matrix: DMatrix<f64>,
+) -> Option<(Vec<f64>, DMatrix<f64>)> {
+    let stiffness_eigen = SymmetricEigen::new(stiffness);
+    let max_eigenvalue = stiffness_eigen.eigenvalues.max();
+    if max_eigenvalue <= 0.0 {
+        return None;
+    }
+    let kept: Vec<usize> = (0..stiffness_eigen.eigenvalues.len())
+        .filter(|i| stiffness_eigen.eigenvalues[*i] > max_eigenvalue * DEPENDENCY_TOLERANCE)
+        .collect();
+    let mut transformation = DMatrix::zeros(matrix.nrows(), kept.len());
+    for (col, i) in kept.iter().enumerate() {
+        let scale = 1.0 / stiffness_eigen.eigenvalues[*i].sqrt();
+        transformation
+            .column_mut(col)
+            .copy_from(&(stiffness_eigen.eigenvectors.column(*i) * scale));
+    }
+    let reduced = symmetric(transformation.transpose() * matrix * &transformation);
+    let eigen = SymmetricEigen::new(reduced);
+    let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+    order.sort_by(|a, b| eigen.eigenvalues[*b].total_cmp(&eigen.eigenvalues[*a]));
+    let eigenvalues = order.iter().map(|i| eigen.eigenvalues[*i]).collect();
+    let mut eigenvectors = DMatrix::zeros(kept.len(), order.len());
+    for (col, i) in order.iter().enumerate() {
+        eigenvectors.column_mut(col).copy_from(&eigen.eigenvectors.column(*i));
+    }
+    Some((eigenvalues, transformation * eigenvectors))
+}
+
+/// Returns the symmetric part of the matrix to remove the rounding errors of the projections
+fn symmetric(matrix: DMatrix<f64>) -> DMatrix<f64> {
+    (&matrix + matrix.transpose()) * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::DVector;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_to_largest_translation() {
+        let vector = normalize_to_largest_translation(vec![0.0, -2.0, 5.0, 1.0, 0.5, -3.0]);
+        assert_eq!(vector, vec![0.0, 1.0, -2.5, -0.5, -0.25, 1.5]);
+    }
+
+    #[test]
+    fn test_solve_reduced() {
+        // Uncoupled problem with the eigenvalues μ = b / k: 0.5, 3.0 and -1.0
+        let stiffness = DMatrix::from_diagonal(&DVector::from_vec(vec![2.0, 1.0, 4.0]));
+        let matrix = DMatrix::from_diagonal(&DVector::from_vec(vec![1.0, 3.0, -4.0]));
+        let (eigenvalues, eigenvectors) = solve_reduced(stiffness.clone(), matrix).unwrap();
+        let expected = [3.0, 0.5, -1.0];
+        for (value, expected) in eigenvalues.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-12);
+        }
+        // The eigenvectors are stiffness orthonormal
+        let identity = eigenvectors.transpose() * stiffness * &eigenvectors;
+        assert!((identity - DMatrix::<f64>::identity(3, 3)).abs().max() < 1e-12);
+    }
+}
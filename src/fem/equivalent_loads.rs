@@ -1,4 +1,4 @@
-﻿use crate::fem::{matrices, stiffness};
+﻿use crate::fem::{deflection, matrices, stiffness};
 use crate::loads::load::{CalculationLoad, CalculationLoadType};
 use crate::settings::CalculationSettings;
 use crate::structure::CalculationElement;
@@ -22,7 +22,10 @@ pub(super) fn create(
     let mut i_normalized: usize;
 
     for elem in calc_model.get_all_calc_elements() {
-        let el_global_eq_loads = get_element_g_eq_loads(&elem, loads, settings);
+        // The loads at the ends of the element are transformed to the nodes through the rigid
+        // end offsets
+        let el_global_eq_loads =
+            matrices::apply_rigid_offsets_to_loads(elem, get_element_g_eq_loads(&elem, loads, settings));
         // The index of the start node
         let s = (elem.node_start - 1) as usize;
         // The index of the end node
@@ -52,14 +55,32 @@ pub(super) fn create(
 }
 
 /// Creates the equivalent load matrix in global coordinates for given element
-/// The returned matrix is in the size of \[6 rows, 1 columns] (a column vector)
+/// The returned matrix is in the size of \[6 rows, 1 columns] (a column vector). The loads are at
+/// the ends of the element, i.e. the rigid end offsets are not applied.
 pub fn get_element_g_eq_loads(
     element: &CalculationElement,
     loads: &Vec<CalculationLoad>,
     settings: &CalculationSettings,
+) -> DMatrix<f64> {
+    let rot_matrix = matrices::get_rotation_matrix(element.rotation).transpose();
+    rot_matrix * get_element_local_eq_loads(element, loads, settings, false)
+}
+
+/// Creates the equivalent load matrix in elements local coordinate system for given element.
+/// With `ignore_releases` the loads are the fixed end loads of the element, i.e. the loads at the
+/// released degrees of freedom are not moved to the other degrees of freedom (or through the
+/// release springs to the nodes).
+/// The returned matrix is in the size of \[6 rows, 1 columns] (a column vector)
+pub fn get_element_local_eq_loads(
+    element: &CalculationElement,
+    loads: &Vec<CalculationLoad>,
+    settings: &CalculationSettings,
+    ignore_releases: bool,
 ) -> DMatrix<f64> {
     let dof = 3;
     let mut result_vector = DMatrix::<f64>::zeros(dof * 2, 1);
+    let el_length = element.length;
+    let el_rotation = element.rotation;
     let mut linked_loads: Vec<&CalculationLoad> = Vec::new();
     // Gather the loads that are linked to the given element
     for l in loads {
@@ -67,50 +88,64 @@ pub fn get_element_g_eq_loads(
             linked_loads.push(l);
         }
     }
-    let rot_matrix = matrices::get_rotation_matrix(element.rotation).transpose();
-    let el_length = element.length;
-    let el_rotation = element.rotation;
     // Iterate through the linked loads and add them to the equivalent load matrix
     for load in linked_loads {
         match load.load_type {
             CalculationLoadType::Point => {
-                let mut element_eql_matrix_lc = handle_point_load(el_length, el_rotation, load);
-                element_eql_matrix_lc = handle_releases(&element_eql_matrix_lc, element, settings);
-                let element_eql_matrix_gl = &rot_matrix * element_eql_matrix_lc;
-                result_vector += element_eql_matrix_gl;
+                let element_eql_matrix_lc = handle_point_load(el_length, el_rotation, load);
+                result_vector += handle_shear_deformation(element_eql_matrix_lc, element, load, settings);
             }
             CalculationLoadType::Line => {
-                let mut element_eql_matrix_lc = handle_line_load(el_length, el_rotation, load);
-                element_eql_matrix_lc = handle_releases(&element_eql_matrix_lc, element, settings);
-                let element_eql_matrix_gl = &rot_matrix * element_eql_matrix_lc;
-                result_vector += element_eql_matrix_gl;
+                let element_eql_matrix_lc = handle_line_load(el_length, el_rotation, load);
+                result_vector += handle_shear_deformation(element_eql_matrix_lc, element, load, settings);
             }
             CalculationLoadType::Triangular => {
-                let mut element_eql_matrix_lc = handle_triangular_load(el_length, el_rotation, load);
-                element_eql_matrix_lc = handle_releases(&element_eql_matrix_lc, element, settings);
-                let element_eql_matrix_gl = &rot_matrix * element_eql_matrix_lc;
-                result_vector += element_eql_matrix_gl;
+                let element_eql_matrix_lc = handle_triangular_load(el_length, el_rotation, load);
+                result_vector += handle_shear_deformation(element_eql_matrix_lc, element, load, settings);
             }
             CalculationLoadType::Rotational => {
-                let mut element_eql_matrix_lc = handle_rotational_load(el_length, load);
-                element_eql_matrix_lc = handle_releases(&element_eql_matrix_lc, element, settings);
-                let element_eql_matrix_gl = &rot_matrix * element_eql_matrix_lc;
-                result_vector += element_eql_matrix_gl;
+                let element_eql_matrix_lc = handle_rotational_load(el_length, load);
+                result_vector += handle_shear_deformation(element_eql_matrix_lc, element, load, settings);
             }
             CalculationLoadType::Strain => {
-                let val = element.elastic_modulus * element.profile.get_area(&element.material, settings) / el_length
+                let val = element.elastic_modulus * element.profile_area / el_length
                     * load.strength;
-                let mut element_eql_matrix_lc =DMatrix::from_row_slice(6, 1, &[-val, 0.0, 0.0, val, 0.0, 0.0]);
-                element_eql_matrix_lc = handle_releases(&element_eql_matrix_lc, element, settings);
-                let element_eql_matrix_gl = &rot_matrix * element_eql_matrix_lc;
-                result_vector += element_eql_matrix_gl;
+                result_vector += DMatrix::from_row_slice(6, 1, &[-val, 0.0, 0.0, val, 0.0, 0.0]);
+            }
+            CalculationLoadType::Curvature => {
+                // The fixed end moments that keep the element straight (M = -EI * κ)
+                let val = element.elastic_modulus * element.major_smoa * load.strength;
+                result_vector += DMatrix::from_row_slice(6, 1, &[0.0, 0.0, -val, 0.0, 0.0, val]);
             }
         }
     }
 
+    // The releases are handled for the sum of the loads (the handling is linear)
+    if !ignore_releases {
+        result_vector = handle_releases(&result_vector, element, settings);
+    }
     result_vector
 }
 
+/// Handles the shear deformations of the Timoshenko beam. The (Euler-Bernoulli) equivalent loads leave
+/// the relative shear deflection Δ = 1/(G*As) * ∫V dx between the ends of the element. The deflection
+/// is cancelled with the end forces of the relative displacement Δ (K * \[0, 0, 0, 0, Δ, 0]).
+fn handle_shear_deformation(
+    eq_load_matrix: DMatrix<f64>,
+    element: &CalculationElement,
+    load: &CalculationLoad,
+    settings: &CalculationSettings,
+) -> DMatrix<f64> {
+    let ga_s = element.get_shear_stiffness(settings);
+    if ga_s <= 0.0 {
+        return eq_load_matrix;
+    }
+    let l = element.length;
+    let delta = (eq_load_matrix[1] * l - deflection::shear_integral_from_load(l, element, load)) / ga_s;
+    let el_stiff_matrix = stiffness::get_element_stiffness_matrix(element, settings, true);
+    eq_load_matrix + el_stiff_matrix.columns(4, 1) * delta
+}
+
 fn handle_releases(eq_load_matrix: &DMatrix<f64>, element: &CalculationElement, settings: &CalculationSettings) -> DMatrix<f64> {
     let dof = 3;
     let release_count = element.releases.start_release_count() + element.releases.end_release_count();
@@ -136,12 +171,17 @@ fn handle_releases(eq_load_matrix: &DMatrix<f64>, element: &CalculationElement,
         }
     }
 
-    let reduced = fp - parts.kpf * kff_inv * ff;
+    let reduced = fp - &parts.kpf * &kff_inv * &ff;
+    // The loads at the released directions are carried to the nodes by the release springs
+    // (zero without the springs)
+    let spring_loads = &parts.springs * &kff_inv * &ff;
     let mut reduced_counter = 0;
+    let mut spring_counter = 0;
 
     for i in 0..dof*2 {
         if element.releases.get_release_value(i).unwrap() {
-            result[i] = 0.0;
+            result[i] = spring_loads[spring_counter];
+            spring_counter += 1;
         } else {
             result[i] = reduced[reduced_counter];
             reduced_counter += 1;
@@ -1,26 +1,36 @@
 ﻿#![allow(dead_code)]
 
 use nalgebra::DMatrix;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use vputilslib::equation_handler::EquationHandler;
 
-use super::{matrices, CalcModel};
-use crate::loads::{CalcLoadCombination, Load};
+use super::active_set::ActiveSet;
+use super::concrete_deflection::{self, ConcreteProperties};
+use super::skyline::{LdlFactorization, SkylineMatrix};
+use super::sparse::{self, SparseMatrix};
+use super::{
+    buckling, constraints, foundation, influence_line, matrices, modal, second_order, superposition, CalcModel,
+};
+use crate::loads::load::{CalculationLoad, LoadType, PrescribedDisplacement};
+use crate::loads::load_combination::LoadCombinationType;
+use crate::loads::CalcLoadCombination;
+use crate::settings::calc_settings::AnalysisType;
 use crate::settings::CalculationSettings;
 use crate::{
     fem::{
         internal_forces::calc_internal_forces,
-        matrices::{
-            get_unknown_translation_eq_loads_rows, get_unknown_translation_rows,
-            get_unknown_translation_stiffness_rows,
-        },
+        matrices::get_unknown_translation_rows,
     },
     loads,
-    loads::LoadCombination,
-    results::{CalculationResults, NodeResults},
+    loads::{Load, LoadCombination},
+    results::{
+        BucklingMode, BucklingResults, CalculationResults, InfluenceLine, InfluenceQuantity,
+        InternalForceResults, LoadCaseResults, LongTermResults, ModalResults, NodeResults,
+        SecondOrderResults,
+    },
     structure::{Node, StructureModel},
 };
 use crate::structure::NodeCollection;
@@ -37,54 +47,124 @@ pub fn calculate(
     let elements = &struct_model.elements;
     let loads = &struct_model.loads;
     let calc_settings = &struct_model.calc_settings;
-    let (calc_elements, extra_nodes) =
-        crate::structure::utils::get_calc_elements(elements, nodes, &HashMap::new(), calc_settings);
-    let calc_model = CalcModel::new(&nodes, extra_nodes, &elements, calc_elements);
+    let concrete_properties = ConcreteProperties::new(elements, calc_settings);
+    let calc_model = create_calc_model(struct_model, &concrete_properties, None);
 
-    let col_height = super::utils::col_height(nodes);
+    // The extra nodes (created when splitting the elements) are included in the matrices
+    let col_height = calc_model.get_node_count() * 3;
 
-    let load_combinations = if struct_model.load_combinations.is_empty() {
-        &vec![LoadCombination::default()]
+    // The stiffness matrix is the same for all the load combinations, so it is factorized only
+    // once and the displacements of all the combinations are solved as multiple right hand sides
+    let factorization = StiffnessFactorization::new(
+        nodes,
+        col_height,
+        matrices::create_global_stiffness_matrix(&calc_model, calc_settings),
+    );
+    let calc_load_combinations = get_calc_load_combinations(struct_model, equation_handler);
+    let calculation_loads: Vec<Vec<CalculationLoad>> = calc_load_combinations
+        .iter()
+        .map(|lc| loads::utils::extract_calculation_loads(&calc_model, loads, lc, equation_handler))
+        .collect();
+    let prescribed_displacements: Vec<Vec<PrescribedDisplacement>> = calc_load_combinations
+        .iter()
+        .map(|lc| {
+            loads::utils::extract_prescribed_displacements(&calc_model, loads, lc, equation_handler)
+        })
+        .collect();
+    let params = &SolveParams {
+        equation_handler,
+        calc_settings,
+        nodes,
+        col_height,
+        prescribed_displacements: &[],
+    };
+
+    // The superposition is valid only for the first-order analysis. The axle trains are set only
+    // to the combinations of the train positions, so they are not in the load cases.
+    let load_cases = if calc_settings.load_case_superposition
+        && calc_settings.analysis_type == AnalysisType::FirstOrder
+        && !loads.iter().any(|l| l.load_type == LoadType::AxleTrain)
+    {
+        superposition::get_load_cases(&calc_model, loads, equation_handler)
     } else {
-        &struct_model.load_combinations
+        vec![]
     };
+    // The linear results of the load combinations. With the superposition the internal forces
+    // are combined from the load cases too.
+    let linear_results: Vec<(NodeResults, Option<BTreeMap<i32, InternalForceResults>>)> =
+        if load_cases.is_empty() {
+            solve_linear(&calc_model, &factorization, &calculation_loads, &prescribed_displacements, params)
+                .into_iter()
+                .map(|node_results| (node_results, None))
+                .collect()
+        } else {
+            let case_loads: Vec<Vec<CalculationLoad>> =
+                load_cases.iter().map(|c| c.loads.clone()).collect();
+            let case_prescribed_displacements: Vec<Vec<PrescribedDisplacement>> =
+                load_cases.iter().map(|c| c.prescribed_displacements.clone()).collect();
+            let case_node_results = solve_linear(
+                &calc_model,
+                &factorization,
+                &case_loads,
+                &case_prescribed_displacements,
+                params,
+            );
+            let case_internal_forces: Vec<BTreeMap<i32, InternalForceResults>> = case_loads
+                .iter()
+                .zip(&case_node_results)
+                .map(|(l, r)| calc_internal_forces(&calc_model, l, r, calc_settings))
+                .collect();
+            calc_load_combinations
+                .iter()
+                .map(|lc| {
+                    let factors: Vec<f64> = load_cases.iter().map(|c| c.get_factor(lc)).collect();
+                    let node_results = superposition::combine_node_results(
+                        &case_node_results,
+                        &factors,
+                        equation_handler,
+                        nodes,
+                    );
+                    let internal_forces =
+                        superposition::combine_internal_forces(&case_internal_forces, &factors);
+                    (node_results, Some(internal_forces))
+                })
+                .collect()
+        };
 
     let results: Arc<Mutex<Vec<CalculationResults>>> = Arc::new(Mutex::new(Vec::new()));
 
     let calc_model = &calc_model;
-    let equation_handler = &equation_handler;
+    let concrete_properties = &concrete_properties;
     let result_clone = results.clone();
     thread::scope(move |s| {
-        for model_lc in load_combinations {
-            let calc_load_combinations =
-                loads::lc_utils::get_calc_load_combinations(model_lc, &loads);
-            for lc in calc_load_combinations.into_iter() {
-                let result_clone = result_clone.clone();
-                if calc_settings.calc_threaded {
-                    s.spawn(move || {
-                        calc_lc(
-                            calc_model,
-                            loads,
-                            lc,
-                            equation_handler,
-                            result_clone,
-                            calc_settings,
-                            nodes,
-                            col_height,
-                        );
-                    });
-                } else {
+        let lc_iter = calc_load_combinations
+            .into_iter()
+            .zip(calculation_loads.into_iter().zip(prescribed_displacements))
+            .zip(linear_results);
+        for ((lc, lc_loads), linear_results) in lc_iter {
+            let result_clone = result_clone.clone();
+            if calc_settings.calc_threaded {
+                s.spawn(move || {
                     calc_lc(
                         calc_model,
-                        loads,
+                        concrete_properties,
                         lc,
-                        equation_handler,
+                        lc_loads,
+                        linear_results,
                         result_clone,
-                        calc_settings,
-                        nodes,
-                        col_height,
+                        params,
                     );
-                }
+                });
+            } else {
+                calc_lc(
+                    calc_model,
+                    concrete_properties,
+                    lc,
+                    lc_loads,
+                    linear_results,
+                    result_clone,
+                    params,
+                );
             }
         }
     });
@@ -99,38 +179,540 @@ pub fn calculate(
     result_list
 }
 
+/// Calculates the results of each load case (the loads with the same name with the factor 1.0).
+/// The load cases are calculated with the linear analysis, so the cracking and the long-term
+/// effects of the concrete elements are not included.
+/// * 'struct_model' - the structure model
+/// * 'equation_handler' - equation handler that can contain custom variables set by the user.
+///   The 'L' variable is reserved for the length of the element.
+pub fn calculate_load_cases(
+    struct_model: &StructureModel,
+    equation_handler: &EquationHandler,
+) -> Vec<LoadCaseResults> {
+    let nodes = &struct_model.nodes;
+    let calc_settings = &struct_model.calc_settings;
+    let concrete_properties = ConcreteProperties::new(&struct_model.elements, calc_settings);
+    let calc_model = create_calc_model(struct_model, &concrete_properties, None);
+    let col_height = calc_model.get_node_count() * 3;
+    let params = &SolveParams {
+        equation_handler,
+        calc_settings,
+        nodes,
+        col_height,
+        prescribed_displacements: &[],
+    };
+
+    let factorization = StiffnessFactorization::new(
+        nodes,
+        col_height,
+        matrices::create_global_stiffness_matrix(&calc_model, calc_settings),
+    );
+    let load_cases = superposition::get_load_cases(&calc_model, &struct_model.loads, equation_handler);
+    // The moving loads are split into multiple load cases by the elements. Join them back together
+    // so that there is one result for each load name.
+    let mut names: Vec<&String> = Vec::new();
+    for case in load_cases.iter() {
+        if !names.contains(&&case.name) {
+            names.push(&case.name);
+        }
+    }
+    let case_loads: Vec<Vec<CalculationLoad>> = names
+        .iter()
+        .map(|name| {
+            load_cases.iter().filter(|c| &c.name == *name).flat_map(|c| c.loads.clone()).collect()
+        })
+        .collect();
+    let case_prescribed_displacements: Vec<Vec<PrescribedDisplacement>> = names
+        .iter()
+        .map(|name| {
+            load_cases
+                .iter()
+                .filter(|c| &c.name == *name)
+                .flat_map(|c| c.prescribed_displacements.clone())
+                .collect()
+        })
+        .collect();
+    let node_results = solve_linear(
+        &calc_model,
+        &factorization,
+        &case_loads,
+        &case_prescribed_displacements,
+        params,
+    );
+    names
+        .into_iter()
+        .zip(case_loads.iter().zip(node_results))
+        .map(|(name, (loads, node_results))| {
+            let internal_force_results =
+                calc_internal_forces(&calc_model, loads, &node_results, calc_settings);
+            LoadCaseResults {
+                load_name: name.clone(),
+                node_results,
+                internal_force_results,
+            }
+        })
+        .collect()
+}
+
+/// Calculates the influence line of the response quantity. The unit point load (1 kN) is stepped
+/// along the path elements (see [influence_line::get_load_positions]) and each load position is
+/// solved as a load case with the linear analysis, so the cracking of the concrete elements and
+/// the nonlinear analyses are not included. The loads of the structure model are not used.
+/// * 'struct_model' - the structure model
+/// * 'equation_handler' - equation handler that can contain custom variables set by the user.
+///   The 'L' variable is reserved for the length of the element.
+/// * 'quantity' - the response quantity
+/// * 'path_elements' - the model element numbers the unit load travels over in the order of the
+///   path
+pub fn calculate_influence_line(
+    struct_model: &StructureModel,
+    equation_handler: &EquationHandler,
+    quantity: &InfluenceQuantity,
+    path_elements: &[i32],
+) -> InfluenceLine {
+    let nodes = &struct_model.nodes;
+    let calc_settings = &struct_model.calc_settings;
+    let settings = &calc_settings.influence_line;
+    let concrete_properties = ConcreteProperties::new(&struct_model.elements, calc_settings);
+    let calc_model = create_calc_model(struct_model, &concrete_properties, None);
+    let col_height = calc_model.get_node_count() * 3;
+    let params = &SolveParams {
+        equation_handler,
+        calc_settings,
+        nodes,
+        col_height,
+        prescribed_displacements: &[],
+    };
+
+    let factorization = StiffnessFactorization::new(
+        nodes,
+        col_height,
+        matrices::create_global_stiffness_matrix(&calc_model, calc_settings),
+    );
+    let positions =
+        influence_line::get_load_positions(&struct_model.elements, nodes, path_elements, settings);
+    let mut lc = CalcLoadCombination::default();
+    lc.loads_n_factors.insert(influence_line::UNIT_LOAD_NAME.to_string(), 1.0);
+    let calculation_loads: Vec<Vec<CalculationLoad>> = positions
+        .iter()
+        .map(|position| {
+            let unit_load = influence_line::get_unit_load(position, settings);
+            loads::utils::extract_calculation_loads(&calc_model, &vec![unit_load], &lc, equation_handler)
+        })
+        .collect();
+    // The supports are not displaced by the unit load
+    let prescribed_displacements = vec![Vec::new(); calculation_loads.len()];
+    let node_results = solve_linear(
+        &calc_model,
+        &factorization,
+        &calculation_loads,
+        &prescribed_displacements,
+        params,
+    );
+    let ordinates = positions
+        .into_iter()
+        .zip(calculation_loads.iter().zip(node_results))
+        .map(|(mut ordinate, (loads, node_results))| {
+            ordinate.value =
+                influence_line::get_response(&calc_model, quantity, loads, &node_results, calc_settings);
+            ordinate
+        })
+        .collect();
+    InfluenceLine { quantity: *quantity, ordinates }
+}
+
+/// Calculates the linear buckling modes of each load combination. The axial forces of the elements
+/// are calculated with the first-order analysis and the critical load factors are solved from the
+/// elastic and the geometric stiffness matrices (see [buckling]). All the elements are split into
+/// segments by the segment count of the buckling settings.
+/// * 'struct_model' - the structure model
+/// * 'equation_handler' - equation handler that can contain custom variables set by the user.
+///   The 'L' variable is reserved for the length of the element.
+pub fn calculate_buckling(
+    struct_model: &StructureModel,
+    equation_handler: &EquationHandler,
+) -> Vec<BucklingResults> {
+    let nodes = &struct_model.nodes;
+    let calc_settings = &struct_model.calc_settings;
+    let concrete_properties = ConcreteProperties::new(&struct_model.elements, calc_settings);
+    let calc_model = create_calc_model(struct_model, &concrete_properties, Some(calc_settings.buckling.segment_count));
+    let col_height = calc_model.get_node_count() * 3;
+    let params = &SolveParams {
+        equation_handler,
+        calc_settings,
+        nodes,
+        col_height,
+        prescribed_displacements: &[],
+    };
+
+    // The elastic stiffness matrix is the same for all the load combinations
+    let factorization = StiffnessFactorization::new(
+        nodes,
+        col_height,
+        matrices::create_global_stiffness_matrix(&calc_model, calc_settings),
+    );
+    let calc_load_combinations = get_calc_load_combinations(struct_model, equation_handler);
+    let calculation_loads: Vec<Vec<CalculationLoad>> = calc_load_combinations
+        .iter()
+        .map(|lc| loads::utils::extract_calculation_loads(&calc_model, &struct_model.loads, lc, equation_handler))
+        .collect();
+    let prescribed_displacements: Vec<Vec<PrescribedDisplacement>> = calc_load_combinations
+        .iter()
+        .map(|lc| loads::utils::extract_prescribed_displacements(&calc_model, &struct_model.loads, lc, equation_handler))
+        .collect();
+    let node_results = solve_linear(
+        &calc_model,
+        &factorization,
+        &calculation_loads,
+        &prescribed_displacements,
+        params,
+    );
+
+    let mut result_list: Vec<BucklingResults> = calc_load_combinations
+        .iter()
+        .zip(calculation_loads.iter().zip(node_results))
+        .map(|(lc, (lc_loads, node_results))| {
+            let mut lc_calc_model = calc_model.clone();
+            second_order::update_axial_forces(
+                &mut lc_calc_model.calc_elements,
+                lc_loads,
+                &node_results,
+                calc_settings,
+            );
+            let geometric_stiffness = matrices::create_global_geometric_stiffness_matrix(&lc_calc_model);
+            let (modes, converged) =
+                buckling::solve_modes(&factorization, &geometric_stiffness, &calc_settings.buckling);
+            let modes = modes
+                .into_iter()
+                .map(|mode| BucklingMode {
+                    buckling_lengths: buckling::get_buckling_lengths(&lc_calc_model, mode.critical_load_factor),
+                    critical_load_factor: mode.critical_load_factor,
+                    mode_shape: mode.mode_shape,
+                })
+                .collect();
+            BucklingResults {
+                load_combination: lc.parent_load_combination.clone(),
+                load_comb_num: lc.parent_load_combination_number,
+                sub_load_comb_num: lc.sub_number,
+                converged,
+                modes,
+            }
+        })
+        .collect();
+
+    // Sort the results by sub load combination number
+    result_list.sort_by_key(|r| r.sub_load_comb_num);
+
+    result_list
+}
+
+/// Calculates the natural frequencies and the mode shapes of the structure. The masses are
+/// calculated from the elements and the nodes (see [crate::fem::mass]). If the mass load
+/// combination is set in the modal settings, the loads of the load combination are converted
+/// into masses and there is a result for each sub load combination of it. Otherwise there is a
+/// single result without the masses of the loads. All the elements are split into segments by the
+/// segment count of the modal settings.
+/// * 'struct_model' - the structure model
+/// * 'equation_handler' - equation handler that can contain custom variables set by the user.
+///   The 'L' variable is reserved for the length of the element.
+pub fn calculate_modal(
+    struct_model: &StructureModel,
+    equation_handler: &EquationHandler,
+) -> Vec<ModalResults> {
+    let nodes = &struct_model.nodes;
+    let calc_settings = &struct_model.calc_settings;
+    let modal_settings = &calc_settings.modal;
+    let concrete_properties = ConcreteProperties::new(&struct_model.elements, calc_settings);
+    let calc_model = create_calc_model(struct_model, &concrete_properties, Some(modal_settings.segment_count));
+    let col_height = calc_model.get_node_count() * 3;
+
+    let factorization = StiffnessFactorization::new(
+        nodes,
+        col_height,
+        matrices::create_global_stiffness_matrix(&calc_model, calc_settings),
+    );
+    // The load combinations whose loads are converted into masses
+    let calc_load_combinations: Vec<Option<CalcLoadCombination>> =
+        if modal_settings.mass_load_combination.is_empty() {
+            vec![None]
+        } else {
+            struct_model
+                .load_combinations
+                .iter()
+                .filter(|lc| lc.name == modal_settings.mass_load_combination)
+                .flat_map(|model_lc| {
+                    loads::lc_utils::get_calc_load_combinations(
+                        model_lc,
+                        &struct_model.loads,
+                        &struct_model.elements,
+                        &calc_settings.partial_factors,
+                    )
+                })
+                .map(Some)
+                .collect()
+        };
+    // The self weights are not converted into masses, because the masses of the elements are
+    // always included
+    let mass_loads: Vec<Load> = struct_model
+        .loads
+        .iter()
+        .filter(|l| l.load_type != LoadType::SelfWeight)
+        .cloned()
+        .collect();
+
+    let mut result_list: Vec<ModalResults> = calc_load_combinations
+        .into_iter()
+        .map(|lc| {
+            let load_masses = match &lc {
+                Some(lc) => {
+                    let calculation_loads = loads::utils::extract_calculation_loads(
+                        &calc_model,
+                        &mass_loads,
+                        lc,
+                        equation_handler,
+                    );
+                    modal::get_load_masses(&calc_model, &calculation_loads, calc_settings)
+                }
+                None => vec![],
+            };
+            let mass_matrix = matrices::create_global_mass_matrix(
+                &calc_model,
+                modal_settings.mass_matrix,
+                &load_masses,
+            );
+            let (modes, converged) =
+                modal::solve_modes(&factorization, &mass_matrix, &calc_model, modal_settings);
+            let (load_combination, load_comb_num, sub_load_comb_num) = match lc {
+                Some(lc) => (lc.parent_load_combination, lc.parent_load_combination_number, lc.sub_number),
+                None => (String::new(), 0, 0),
+            };
+            ModalResults {
+                load_combination,
+                load_comb_num,
+                sub_load_comb_num,
+                converged,
+                total_mass: modal::get_total_mass(&mass_matrix, &calc_model),
+                modes,
+            }
+        })
+        .collect();
+
+    // Sort the results by sub load combination number
+    result_list.sort_by_key(|r| r.sub_load_comb_num);
+
+    result_list
+}
+
+/// Gets the calculation load combinations of the structure model. If there are no load
+/// combinations, the default load combination is used. The combinations with axle train loads are
+/// replaced with the combinations of the train positions. The sub numbers are unique over all the
+/// load combinations (see [loads::lc_utils::set_unique_sub_numbers]).
+fn get_calc_load_combinations(
+    struct_model: &StructureModel,
+    equation_handler: &EquationHandler,
+) -> Vec<CalcLoadCombination> {
+    let load_combinations = if struct_model.load_combinations.is_empty() {
+        &vec![LoadCombination::default()]
+    } else {
+        &struct_model.load_combinations
+    };
+    let mut calc_load_combinations: Vec<CalcLoadCombination> = load_combinations
+        .iter()
+        .flat_map(|model_lc| {
+            let calc_lcs = loads::lc_utils::get_calc_load_combinations(
+                model_lc,
+                &struct_model.loads,
+                &struct_model.elements,
+                &struct_model.calc_settings.partial_factors,
+            );
+            loads::lc_utils::add_axle_train_positions(
+                model_lc,
+                calc_lcs,
+                &struct_model.loads,
+                &struct_model.elements,
+                &struct_model.nodes,
+                &struct_model.calc_settings.axle_trains,
+                equation_handler,
+            )
+        })
+        .collect();
+    loads::lc_utils::set_unique_sub_numbers(&mut calc_load_combinations);
+    calc_load_combinations
+}
+
+/// Creates the calculation model from the structure model. The reinforced concrete elements are
+/// split into segments to take the cracking into account. In the second-order analysis all the
+/// elements are split into segments for the geometric stiffness. The elements with an elastic
+/// foundation are always split into segments.
+/// * `segment_count` - if set, all the elements are split into the segment count (the buckling and
+///   the modal analyses)
+fn create_calc_model<'a>(
+    struct_model: &'a StructureModel,
+    concrete_properties: &ConcreteProperties,
+    segment_count: Option<usize>,
+) -> CalcModel<'a> {
+    let nodes = &struct_model.nodes;
+    let elements = &struct_model.elements;
+    let calc_settings = &struct_model.calc_settings;
+    let mut split_positions = concrete_deflection::get_split_positions(
+        elements,
+        nodes,
+        &concrete_properties.cracking,
+        calc_settings,
+    );
+    if let Some(segment_count) = segment_count {
+        crate::structure::utils::add_split_positions(&mut split_positions, elements, nodes, segment_count);
+    } else if calc_settings.analysis_type == AnalysisType::SecondOrder {
+        let segment_count = calc_settings.second_order.segment_count;
+        crate::structure::utils::add_split_positions(&mut split_positions, elements, nodes, segment_count);
+    }
+    let segment_count = calc_settings.foundation.segment_count;
+    foundation::add_split_positions(&mut split_positions, elements, nodes, segment_count);
+    let (calc_elements, extra_nodes) =
+        crate::structure::utils::get_calc_elements(elements, nodes, &split_positions, calc_settings);
+    let mut calc_model = CalcModel::new(nodes, extra_nodes, elements, calc_elements);
+    if !struct_model.constraints.is_empty() {
+        calc_model.constraints = &struct_model.constraints;
+        calc_model.constraint_penalties = constraints::get_penalties(&calc_model, calc_settings);
+    }
+    calc_model
+}
+
+/// Solves the node results for the loads of multiple load combinations (or load cases) with the
+/// factorized stiffness matrix of the calculation model
+/// * `prescribed_displacements` - the prescribed displacements of the supports in the same order
+///   as the loads
+fn solve_linear(
+    calc_model: &CalcModel,
+    factorization: &StiffnessFactorization,
+    calculation_loads: &[Vec<CalculationLoad>],
+    prescribed_displacements: &[Vec<PrescribedDisplacement>],
+    params: &SolveParams,
+) -> Vec<NodeResults> {
+    let mut equivalent_loads = DMatrix::zeros(params.col_height, calculation_loads.len());
+    let mut prescribed_matrix = DMatrix::zeros(params.col_height, calculation_loads.len());
+    for (i, (loads, prescribed)) in calculation_loads.iter().zip(prescribed_displacements).enumerate() {
+        let lc_equivalent_loads =
+            matrices::create_global_equivalent_loads_matrix(calc_model, params.calc_settings, loads);
+        equivalent_loads.column_mut(i).copy_from(&lc_equivalent_loads.column(0));
+        let lc_prescribed_matrix =
+            matrices::create_global_prescribed_displacements_matrix(calc_model, params.nodes, prescribed);
+        prescribed_matrix.column_mut(i).copy_from(&lc_prescribed_matrix.column(0));
+    }
+    let displacements = factorization.solve_prescribed(&equivalent_loads, &prescribed_matrix);
+    let reactions = factorization.get_reactions(&displacements, &equivalent_loads);
+    (0..calculation_loads.len())
+        .map(|i| create_node_results(calc_model, &displacements, &reactions, i, params))
+        .collect()
+}
+
+/// Calculates the results of the load combination from the linear results (the node results and
+/// the internal forces if they are already combined from the load cases)
+/// * 'lc_loads' - the calculation loads and the prescribed displacements of the load combination
 fn calc_lc(
     calc_model: &CalcModel,
-    loads: &Vec<Load>,
+    concrete_properties: &ConcreteProperties,
     lc: CalcLoadCombination,
-    equation_handler: &EquationHandler,
+    lc_loads: (Vec<CalculationLoad>, Vec<PrescribedDisplacement>),
+    linear_results: (NodeResults, Option<BTreeMap<i32, InternalForceResults>>),
     result_clone: Arc<Mutex<Vec<CalculationResults>>>,
-    calc_settings: &CalculationSettings,
-    nodes: &NodeCollection,
-    col_height: usize,
+    params: &SolveParams,
 ) {
-    let calculation_loads =
-        &loads::utils::extract_calculation_loads(calc_model, loads, &lc, equation_handler);
+    let (calculation_loads, prescribed_displacements) = lc_loads;
+    let calculation_loads = &calculation_loads;
+    let params = &SolveParams { prescribed_displacements: &prescribed_displacements, ..*params };
+    let calc_settings = params.calc_settings;
+    let (node_results, linear_internal_forces) = linear_results;
 
-    let mut calc_matrices = matrices::create_global_calculation_matrix(calc_model, calc_settings, 
-                                                                       calculation_loads);
-    let displacements = calculate_displacements(
-        nodes,
-        col_height,
-        &mut calc_matrices.stiffness,
-        &mut calc_matrices.equivalent_loads,
-    );
+    // The tension-only and compression-only elements and the one-directional supports are
+    // iterated with the linear stiffnesses until the active set is stable. The later steps are
+    // calculated with the final active set.
+    let mut active_set_model = None;
+    let mut active_set_nodes = None;
+    let mut active_set_results = None;
+    let node_results = if ActiveSet::has_conditions(calc_model.structure_elements, params.nodes) {
+        let mut as_calc_model = calc_model.clone();
+        let (node_results, active_set, converged, iterations) =
+            iterate_active_set(&mut as_calc_model, calculation_loads, node_results, params);
+        active_set_results = Some(active_set.get_results(calc_model, converged, iterations));
+        active_set_nodes = Some(active_set.get_nodes(params.nodes));
+        active_set_model = Some(as_calc_model);
+        node_results
+    } else {
+        node_results
+    };
+    let params = &SolveParams {
+        nodes: active_set_nodes.as_ref().unwrap_or(params.nodes),
+        ..*params
+    };
+    let calc_model = active_set_model.as_ref().unwrap_or(calc_model);
+    // The internal forces superposed from the load cases do not apply to the final active set
+    let linear_internal_forces = linear_internal_forces.filter(|_| active_set_model.is_none());
+
+    // If there are reinforced concrete elements, the stiffnesses of the calculation elements are
+    // iterated by the moments until the displacements converge
+    let mut lc_calc_model = None;
+    let node_results = if concrete_properties.cracking.is_empty() {
+        node_results
+    } else {
+        let mut cracked_calc_model = calc_model.clone();
+        let (node_results, _) = iterate_concrete_stiffness(
+            &mut cracked_calc_model,
+            concrete_properties,
+            false,
+            calculation_loads,
+            calculation_loads.clone(),
+            node_results,
+            params,
+        );
+        lc_calc_model = Some(cracked_calc_model);
+        node_results
+    };
 
-    let reactions = calculate_reactions(&calc_matrices.stiffness, &displacements, 
-                                        &calc_matrices.equivalent_loads);
+    // The second-order analysis is iterated from the first-order results
+    let mut second_order = None;
+    let node_results = if calc_settings.analysis_type == AnalysisType::SecondOrder {
+        let mut so_calc_model = lc_calc_model.take().unwrap_or_else(|| calc_model.clone());
+        let first_order_forces =
+            calc_internal_forces(&so_calc_model, calculation_loads, &node_results, calc_settings);
+        let (node_results, converged, iterations) =
+            iterate_second_order(&mut so_calc_model, calculation_loads, node_results, params);
+        lc_calc_model = Some(so_calc_model);
+        second_order = Some((first_order_forces, converged, iterations));
+        node_results
+    } else {
+        node_results
+    };
 
-    let displacements = displacements.column(0).as_slice().to_vec();
-    let reactions = reactions.column(0).as_slice().to_vec();
+    let internal_force_results = match (lc_calc_model.as_ref(), linear_internal_forces) {
+        (None, Some(internal_forces)) => internal_forces,
+        (lc_calc_model, _) => calc_internal_forces(
+            lc_calc_model.unwrap_or(calc_model),
+            calculation_loads,
+            &node_results,
+            calc_settings,
+        ),
+    };
 
-    let node_results = NodeResults::new(displacements, reactions, nodes.len(), &equation_handler, 
-                                        &nodes);
-    let internal_force_results =
-        calc_internal_forces(calc_model, calculation_loads, &node_results, calc_settings);
+    // The final deflections with the creep and the shrinkage are calculated for the
+    // quasi-permanent combinations
+    let long_term_results = if !concrete_properties.long_term.is_empty()
+        && matches!(lc.combination_type, LoadCombinationType::SLSqp { .. })
+    {
+        Some(calc_long_term(calc_model, concrete_properties, calculation_loads, params))
+    } else {
+        None
+    };
+
+    let second_order_results =
+        second_order.map(|(first_order_forces, converged, iterations)| SecondOrderResults {
+            converged,
+            iterations,
+            moment_amplifications: second_order::get_moment_amplifications(
+                &first_order_forces,
+                &internal_force_results,
+            ),
+        });
 
     let result = CalculationResults {
         load_combination: lc.parent_load_combination.clone(),
@@ -138,10 +720,376 @@ fn calc_lc(
         sub_load_comb_num: lc.sub_number,
         node_results,
         internal_force_results,
+        long_term_results,
+        second_order_results,
+        active_set_results,
+        axle_train_position: lc.axle_train_position,
     };
     result_clone.deref().lock().unwrap().push(result);
 }
 
+/// The parameters that are shared by all the solutions of a load combination
+struct SolveParams<'a> {
+    equation_handler: &'a EquationHandler,
+    calc_settings: &'a CalculationSettings,
+    nodes: &'a NodeCollection,
+    col_height: usize,
+    /// The prescribed displacements of the supports of the load combination. Empty when the
+    /// parameters are shared by multiple load combinations (see [solve_linear]).
+    prescribed_displacements: &'a [PrescribedDisplacement],
+}
+
+/// Calculates the long-term results of the load combination. The concrete elements are calculated
+/// with the effective modulus of elasticity and the shrinkage curvatures are added to the loads.
+fn calc_long_term(
+    calc_model: &CalcModel,
+    concrete_properties: &ConcreteProperties,
+    calculation_loads: &[CalculationLoad],
+    params: &SolveParams,
+) -> LongTermResults {
+    let mut lt_calc_model = calc_model.clone();
+    concrete_deflection::apply_long_term_properties(
+        &mut lt_calc_model.calc_elements,
+        &concrete_properties.long_term,
+    );
+    let mut loads = calculation_loads.to_vec();
+    loads.extend(concrete_deflection::get_shrinkage_loads(
+        &lt_calc_model.calc_elements,
+        concrete_properties,
+        None,
+        params.calc_settings,
+    ));
+    let mut node_results = solve_node_results(&lt_calc_model, &loads, params);
+    if !concrete_properties.long_term_cracking.is_empty() {
+        (node_results, loads) = iterate_concrete_stiffness(
+            &mut lt_calc_model,
+            concrete_properties,
+            true,
+            calculation_loads,
+            loads,
+            node_results,
+            params,
+        );
+    }
+    let internal_force_results =
+        calc_internal_forces(&lt_calc_model, &loads, &node_results, params.calc_settings);
+    LongTermResults { node_results, internal_force_results }
+}
+
+/// Iterates the effective stiffnesses of the cracked concrete elements until the displacements
+/// converge. For the long-term loads the shrinkage curvatures are updated too. Returns the node
+/// results and the loads of the last solution.
+/// * `base_loads` - the loads of the load combination (without the shrinkage curvatures)
+/// * `loads` - the loads that were used to solve the node results
+fn iterate_concrete_stiffness(
+    calc_model: &mut CalcModel,
+    concrete_properties: &ConcreteProperties,
+    long_term: bool,
+    base_loads: &[CalculationLoad],
+    mut loads: Vec<CalculationLoad>,
+    mut node_results: NodeResults,
+    params: &SolveParams,
+) -> (NodeResults, Vec<CalculationLoad>) {
+    let settings = &params.calc_settings.concrete_deflections;
+    let beta = if long_term {
+        settings.long_term_load_duration_coefficient
+    } else {
+        settings.load_duration_coefficient
+    };
+    for _ in 0..settings.max_iterations {
+        // The shrinkage curvatures are got before the stiffnesses are changed, because the
+        // moments are calculated with the stiffnesses of the previous solution
+        let new_loads = if long_term {
+            let mut new_loads = base_loads.to_vec();
+            new_loads.extend(concrete_deflection::get_shrinkage_loads(
+                &calc_model.calc_elements,
+                concrete_properties,
+                Some((&loads, &node_results)),
+                params.calc_settings,
+            ));
+            Some(new_loads)
+        } else {
+            None
+        };
+        concrete_deflection::update_effective_stiffness(
+            &mut calc_model.calc_elements,
+            concrete_properties.get_cracking(long_term),
+            &loads,
+            &node_results,
+            beta,
+            params.calc_settings,
+        );
+        if let Some(new_loads) = new_loads {
+            loads = new_loads;
+        }
+        let new_node_results = solve_node_results(calc_model, &loads, params);
+        let converged = concrete_deflection::displacements_converged(
+            &node_results.displacements,
+            &new_node_results.displacements,
+            settings.tolerance,
+        );
+        node_results = new_node_results;
+        if converged {
+            break;
+        }
+    }
+    (node_results, loads)
+}
+
+/// Iterates the active set of the tension-only and compression-only elements and the
+/// one-directional supports (see [ActiveSet]). The inactive elements are set to the calculation
+/// model. Returns the node results, the active set, whether the active set converged and the
+/// number of the iterations. The iteration is stopped if the structure becomes a mechanism.
+fn iterate_active_set(
+    calc_model: &mut CalcModel,
+    loads: &Vec<CalculationLoad>,
+    mut node_results: NodeResults,
+    params: &SolveParams,
+) -> (NodeResults, ActiveSet, bool, usize) {
+    let mut active_set = ActiveSet::default();
+    let max_iterations = params.calc_settings.active_set.max_iterations;
+    for iteration in 1..=max_iterations {
+        if !active_set.update(calc_model, loads, &node_results, params.calc_settings) {
+            return (node_results, active_set, true, iteration);
+        }
+        calc_model.inactive_elements = active_set.inactive_elements.clone();
+        let nodes = active_set.get_nodes(params.nodes);
+        let factorization = StiffnessFactorization::new(
+            &nodes,
+            params.col_height,
+            matrices::create_global_stiffness_matrix(calc_model, params.calc_settings),
+        );
+        if factorization.is_singular() {
+            return (node_results, active_set, false, iteration);
+        }
+        let equivalent_loads =
+            matrices::create_global_equivalent_loads_matrix(calc_model, params.calc_settings, loads);
+        let prescribed_displacements = matrices::create_global_prescribed_displacements_matrix(
+            calc_model,
+            &nodes,
+            params.prescribed_displacements,
+        );
+        let displacements = factorization.solve_prescribed(&equivalent_loads, &prescribed_displacements);
+        let reactions = factorization.get_reactions(&displacements, &equivalent_loads);
+        node_results = create_node_results(calc_model, &displacements, &reactions, 0, params);
+    }
+    (node_results, active_set, false, max_iterations)
+}
+
+/// Iterates the second-order (P-Δ) displacements. The axial forces of the calculation elements are
+/// updated from the previous solution until the displacements converge. Returns the node results,
+/// whether the iteration converged and the number of the iterations. The iteration is stopped if
+/// the structure becomes unstable (the compressive forces exceed the critical loads).
+fn iterate_second_order(
+    calc_model: &mut CalcModel,
+    loads: &Vec<CalculationLoad>,
+    mut node_results: NodeResults,
+    params: &SolveParams,
+) -> (NodeResults, bool, usize) {
+    let settings = &params.calc_settings.second_order;
+    for iteration in 1..=settings.max_iterations {
+        second_order::update_axial_forces(
+            &mut calc_model.calc_elements,
+            loads,
+            &node_results,
+            params.calc_settings,
+        );
+        let factorization = StiffnessFactorization::new(
+            params.nodes,
+            params.col_height,
+            matrices::create_global_stiffness_matrix(calc_model, params.calc_settings),
+        );
+        if !factorization.is_positive_definite() {
+            println!(
+                "Warning: the second-order analysis is unstable (the compressive forces exceed the \
+                 critical loads) at the iteration {}",
+                iteration
+            );
+            return (node_results, false, iteration);
+        }
+        let equivalent_loads =
+            matrices::create_global_equivalent_loads_matrix(calc_model, params.calc_settings, loads);
+        let prescribed_displacements = matrices::create_global_prescribed_displacements_matrix(
+            calc_model,
+            params.nodes,
+            params.prescribed_displacements,
+        );
+        let displacements = factorization.solve_prescribed(&equivalent_loads, &prescribed_displacements);
+        let reactions = factorization.get_reactions(&displacements, &equivalent_loads);
+        let new_node_results = create_node_results(calc_model, &displacements, &reactions, 0, params);
+        let converged = concrete_deflection::displacements_converged(
+            &node_results.displacements,
+            &new_node_results.displacements,
+            settings.tolerance,
+        );
+        node_results = new_node_results;
+        if converged {
+            return (node_results, true, iteration);
+        }
+    }
+    println!(
+        "Warning: the second-order analysis did not converge in {} iterations",
+        settings.max_iterations
+    );
+    (node_results, false, settings.max_iterations)
+}
+
+/// Solves the displacements and the support reactions for the given calculation model and loads.
+/// The stiffness matrix is created and factorized for the calculation model, so this is used
+/// when the stiffnesses differ from the linear solution (e.g. cracked concrete elements).
+fn solve_node_results(
+    calc_model: &CalcModel,
+    calculation_loads: &Vec<CalculationLoad>,
+    params: &SolveParams,
+) -> NodeResults {
+    let factorization = StiffnessFactorization::new(
+        params.nodes,
+        params.col_height,
+        matrices::create_global_stiffness_matrix(calc_model, params.calc_settings),
+    );
+    let equivalent_loads = matrices::create_global_equivalent_loads_matrix(
+        calc_model,
+        params.calc_settings,
+        calculation_loads,
+    );
+    let prescribed_displacements = matrices::create_global_prescribed_displacements_matrix(
+        calc_model,
+        params.nodes,
+        params.prescribed_displacements,
+    );
+    let displacements = factorization.solve_prescribed(&equivalent_loads, &prescribed_displacements);
+    let reactions = factorization.get_reactions(&displacements, &equivalent_loads);
+
+    create_node_results(calc_model, &displacements, &reactions, 0, params)
+}
+
+/// Creates the node results from the given column of the displacement and reaction matrices. The
+/// constraint forces are calculated from the displacements.
+fn create_node_results(
+    calc_model: &CalcModel,
+    displacements: &DMatrix<f64>,
+    reactions: &DMatrix<f64>,
+    column: usize,
+    params: &SolveParams,
+) -> NodeResults {
+    let displacements = displacements.column(column).iter().copied().collect();
+    let reactions = reactions.column(column).iter().copied().collect();
+
+    let mut node_results =
+        NodeResults::new(displacements, reactions, params.nodes.len(), params.equation_handler, params.nodes);
+    node_results.constraint_forces = constraints::get_constraint_forces(calc_model, &node_results);
+    node_results
+}
+
+/// The factorized global stiffness matrix. The factorization is used to solve the displacements
+/// for any number of equivalent load vectors (e.g. all the load combinations) without factorizing
+/// the stiffness matrix again.
+///
+/// The unknown rows are renumbered with the reverse Cuthill-McKee ordering to reduce the profile
+/// of the stiffness matrix and the matrix is factorized with the LDLᵀ factorization of the
+/// skyline matrix. The rows without any stiffness (e.g. the rotation of a node where all the
+/// elements are released) are left out and their displacements are zero.
+pub struct StiffnessFactorization {
+    /// The global stiffness matrix without the support springs
+    stiffness: SparseMatrix,
+    /// The unknown rows in the order of the factorization
+    order: Vec<usize>,
+    /// The factorization. None if the stiffness matrix is singular (the structure is a mechanism).
+    factorization: Option<LdlFactorization>,
+    col_height: usize,
+}
+
+impl StiffnessFactorization {
+    /// Factorizes the global stiffness matrix. The support springs are included in the
+    /// factorization, but not in the stored stiffness matrix (used for the support reactions).
+    /// * `nodes` - the structure nodes
+    /// * `col_height` - the height of the displacement matrix (the node count * 3)
+    /// * `stiffness` - the global stiffness matrix with the support rotations applied
+    pub fn new(nodes: &BTreeMap<i32, Node>, col_height: usize, mut stiffness: SparseMatrix) -> Self {
+        apply_support_spring_values(nodes, &mut stiffness);
+        // Get the rows with unknown translations to calculate the displacements for them.
+        let unknown_translation_rows: Vec<usize> = get_unknown_translation_rows(nodes, &stiffness)
+            .into_iter()
+            .map(|r| r as usize)
+            .filter(|r| stiffness[(*r, *r)] != 0.0)
+            .collect();
+        // The degrees of freedom of the nodes are kept together when renumbering the rows
+        let order = sparse::get_renumbered_rows(&stiffness, &unknown_translation_rows, 3);
+        let factorization = SkylineMatrix::from_sparse(&stiffness, &order).factorize();
+        remove_support_spring_values(nodes, &mut stiffness);
+        Self { stiffness, order, factorization, col_height }
+    }
+
+    /// Gets the global stiffness matrix (without the support springs)
+    pub fn stiffness(&self) -> &SparseMatrix {
+        &self.stiffness
+    }
+
+    /// Gets the unknown rows in the order of the factorization. The displacements of the other
+    /// rows are always zero.
+    pub fn unknown_rows(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns true if the stiffness matrix is singular (the structure is a mechanism)
+    pub fn is_singular(&self) -> bool {
+        self.factorization.is_none()
+    }
+
+    /// Returns true if the stiffness matrix is positive definite (the structure is stable). The
+    /// stiffness matrix of a compressed structure is not positive definite if the loads exceed
+    /// the critical loads.
+    pub fn is_positive_definite(&self) -> bool {
+        self.factorization.as_ref().is_some_and(|f| f.negative_pivot_count() == 0)
+    }
+
+    /// Solves the displacements for the equivalent loads. Each column of the equivalent loads
+    /// matrix is a separate right hand side and the displacements are returned in the same
+    /// columns. If the stiffness matrix is singular, all the displacements are zero.
+    pub fn solve(&self, equivalent_loads: &DMatrix<f64>) -> DMatrix<f64> {
+        let mut displacements = DMatrix::zeros(self.col_height, equivalent_loads.ncols());
+        let Some(factorization) = &self.factorization else {
+            return displacements;
+        };
+        for col in 0..equivalent_loads.ncols() {
+            let rhs: Vec<f64> = self.order.iter().map(|r| equivalent_loads[(*r, col)]).collect();
+            let solution = factorization.solve(&rhs);
+            for (i, row) in self.order.iter().enumerate() {
+                displacements[(*row, col)] = solution[i];
+            }
+        }
+        displacements
+    }
+
+    /// Solves the displacements like [Self::solve] with the prescribed displacements at the
+    /// locked rows (e.g. the support settlements). The loads from the prescribed displacements
+    /// (-K u_p) are added to the equivalent loads and the prescribed displacements are added to
+    /// the solved displacements, so the support reactions include the effect of the prescribed
+    /// displacements too.
+    /// * `prescribed_displacements` - the prescribed displacements in the same shape as the
+    ///   equivalent loads (zero at the unknown rows)
+    pub fn solve_prescribed(
+        &self,
+        equivalent_loads: &DMatrix<f64>,
+        prescribed_displacements: &DMatrix<f64>,
+    ) -> DMatrix<f64> {
+        if prescribed_displacements.iter().all(|d| *d == 0.0) {
+            return self.solve(equivalent_loads);
+        }
+        let loads = equivalent_loads - &self.stiffness * prescribed_displacements;
+        self.solve(&loads) + prescribed_displacements
+    }
+
+    /// Gets the support reactions for the solved displacements (see [calculate_reactions])
+    pub fn get_reactions(
+        &self,
+        displacements: &DMatrix<f64>,
+        equivalent_loads: &DMatrix<f64>,
+    ) -> DMatrix<f64> {
+        calculate_reactions(&self.stiffness, displacements, equivalent_loads)
+    }
+}
+
 /// Calculates the displacement matrix for given elements, nodes and loads. The displacement matrix
 /// is in global coordinates.
 /// To get the displacement for certain node, the corresponding row can be got with nodes
@@ -152,62 +1100,54 @@ fn calc_lc(
 /// 1 = translation in Z-axis
 /// 2 = rotation about Y-axis`.
 /// ```
-/// The global stiffness matrix and the equivalent loads matrix rae modified and the modifications
-/// are not reveresed in this function. Clone the matrixes they need to be kept as is.
+/// The stiffness matrix is factorized with [StiffnessFactorization]. Use it directly to solve
+/// multiple load vectors with the same stiffness matrix.
+///
+/// The global stiffness matrix and the equivalent loads matrix are not modified.
 pub fn calculate_displacements(
     nodes: &BTreeMap<i32, Node>,
     col_height: usize,
-    global_stiff_matrix: &mut DMatrix<f64>,
+    global_stiff_matrix: &mut SparseMatrix,
     global_equivalent_loads_matrix: &mut DMatrix<f64>,
 ) -> DMatrix<f64> {
-    apply_support_spring_values(nodes, global_stiff_matrix);
-    // Get the rows with unknown translations to calculate the displacements for them.
-    let unknown_translation_rows = get_unknown_translation_rows(nodes, &global_stiff_matrix);
-    let unknown_translation_stiffness_rows =
-        get_unknown_translation_stiffness_rows(&unknown_translation_rows, &global_stiff_matrix);
-    let unknown_eq_loads_rows = get_unknown_translation_eq_loads_rows(
-        &unknown_translation_rows,
-        &global_equivalent_loads_matrix,
-    );
-    let displacement: DMatrix<f64>;
-    // If there are big number of rows with unknown translations, use cholesky decomposition for
-    // solving the system of equations. Otherwise use regular inversion (might not be necessary,
-    // maybe could always solve with cholesky. Could be benchmarked).
-    if unknown_translation_stiffness_rows.nrows() > 100 {
-        displacement =
-            displacements_cholesky(unknown_translation_stiffness_rows, &unknown_eq_loads_rows)
-                .unwrap_or(DMatrix::zeros(col_height, 1));
-    } else {
-        let stiffness_matrix_inverted = invert_stiff_matrix(unknown_translation_stiffness_rows);
-
-        displacement = if let Some(inverted) = stiffness_matrix_inverted {
-            inverted * unknown_eq_loads_rows
-        } else {
-            DMatrix::zeros(col_height, 1)
-        };
-    }
-    // Create the full displacement matrix by adding the calculated displacements to the unknown
-    // displacements (other rows are zero)
-    let mut full_displacement_matrix: DMatrix<f64> = DMatrix::zeros(col_height, 1);
-    for i in 0..unknown_translation_rows.len() {
-        full_displacement_matrix[(unknown_translation_rows[i] as usize, 0)] = displacement[(i, 0)];
-    }
-    remove_support_spring_values(nodes, global_stiff_matrix);
+    let prescribed_displacements = DMatrix::zeros(col_height, global_equivalent_loads_matrix.ncols());
+    calculate_displacements_prescribed(
+        nodes,
+        col_height,
+        global_stiff_matrix,
+        global_equivalent_loads_matrix,
+        &prescribed_displacements,
+    )
+}
 
-    full_displacement_matrix
+/// Calculates the displacement matrix like [calculate_displacements] with the prescribed
+/// displacements (e.g. the support settlements) as the known non-zero values of the locked rows
+/// (see [matrices::create_global_prescribed_displacements_matrix]).
+pub fn calculate_displacements_prescribed(
+    nodes: &BTreeMap<i32, Node>,
+    col_height: usize,
+    global_stiff_matrix: &mut SparseMatrix,
+    global_equivalent_loads_matrix: &mut DMatrix<f64>,
+    prescribed_displacements: &DMatrix<f64>,
+) -> DMatrix<f64> {
+    let factorization =
+        StiffnessFactorization::new(nodes, col_height, std::mem::take(global_stiff_matrix));
+    let displacements =
+        factorization.solve_prescribed(global_equivalent_loads_matrix, prescribed_displacements);
+    *global_stiff_matrix = factorization.stiffness;
+    displacements
 }
 
 fn apply_support_spring_values(
     nodes: &BTreeMap<i32, Node>,
-    global_stiff_matrix: &mut DMatrix<f64>,
+    global_stiff_matrix: &mut SparseMatrix,
 ) {
     let dof = 3;
     for node in nodes.values() {
         for i in 0..dof {
             if node.support.get_support_spring(i) != 0.0 && node.number > 0 {
-                let node_number = node.number as usize;
-                global_stiff_matrix[((node_number - 1) * dof + i, (node_number - 1) * dof + i)] +=
-                    node.support.get_support_spring(i);
+                let row = (node.number as usize - 1) * dof + i;
+                global_stiff_matrix.add(row, row, node.support.get_support_spring(i));
             }
         }
     }
@@ -216,41 +1156,19 @@ fn apply_support_spring_values(
 /// Removes the support spring values from the stiffness matrix.
 fn remove_support_spring_values(
     nodes: &BTreeMap<i32, Node>,
-    global_stiff_matrix: &mut DMatrix<f64>,
+    global_stiff_matrix: &mut SparseMatrix,
 ) {
     let dof = 3;
     for node in nodes.values() {
         for i in 0..dof {
             if node.support.get_support_spring(i) != 0.0 && node.number > 0 {
-                let node_number = node.number as usize;
-                global_stiff_matrix[((node_number - 1) * dof + i, (node_number - 1) * dof + i)] -=
-                    node.support.get_support_spring(i);
+                let row = (node.number as usize - 1) * dof + i;
+                global_stiff_matrix.add(row, row, -node.support.get_support_spring(i));
             }
         }
     }
 }
 
-/// Calculates the displacements using cholesky decomposition for given rows that have unknown translations
-/// * 'unknown_translation_stiffness_rows' - stiffness matrix rows that have unknown translations
-/// * 'unknown_eq_loads' - equivalent loads at the same rows as the stiffness matrix
-fn displacements_cholesky(
-    unknown_translation_stiffness_rows: DMatrix<f64>,
-    unknown_eq_loads: &DMatrix<f64>,
-) -> Option<DMatrix<f64>> {
-    match unknown_translation_stiffness_rows.cholesky() {
-        Some(cholesky) => Some(cholesky.solve(&unknown_eq_loads)),
-        None => None,
-    }
-}
-
-/// Creates the inverted stiffness matrix for given matrix. If the matrix is larger than 100x100,
-/// cholesky decomposition is used for inversion. Otherwise regular inversion is used.
-/// * 'matrix' - matrix to invert (should be the stiffness matrix with uknonwn translations)
-fn invert_stiff_matrix(matrix: DMatrix<f64>) -> Option<DMatrix<f64>> {
-    // println!("Using regular inversion...");
-    return matrix.try_inverse();
-}
-
 /// Calculates the support reaction matrix for given elements, nodes and loads. The reaction matrix
 /// is in global coordinates. To get the support reaction for certain node, the corresponding row
 /// can be got with nodes `number - 1 * dir` where
@@ -261,7 +1179,7 @@ fn invert_stiff_matrix(matrix: DMatrix<f64>) -> Option<DMatrix<f64>> {
 /// 2 = rotation about Y-axis`.
 /// ```
 pub fn calculate_reactions(
-    global_stiff_matrix: &DMatrix<f64>,
+    global_stiff_matrix: &SparseMatrix,
     global_displacement_matrix: &DMatrix<f64>,
     global_equivalent_loads_matrix: &DMatrix<f64>,
 ) -> DMatrix<f64> {
@@ -318,6 +1236,7 @@ mod tests {
             loads: vec![load],
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = crate::fem::fem_handler::calculate(&calc_model, &mut EquationHandler::new());
         println!("Calculation time: {:?}", timer.elapsed().unwrap());
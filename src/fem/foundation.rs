@@ -0,0 +1,81 @@
+//! The elastic (Winkler) foundation of the elements. The foundation stiffness is added to the
+//! stiffness matrix of the calculation elements (see
+//! [super::stiffness::get_element_foundation_stiffness_matrix]) and the elements with a foundation
+//! are split into segments. The internal forces and the deflections are calculated with the soil
+//! pressures as linearly varying loads on the segments.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::loads::load::{CalculationLoad, CalculationLoadType};
+use crate::results::NodeResults;
+use crate::structure::{CalculationElement, Element, Node};
+
+use super::CalcModel;
+
+/// Adds the split positions of the elements with an elastic foundation into the given split
+/// positions. The elements are split into equal segments by the segment count.
+pub fn add_split_positions(
+    split_positions: &mut HashMap<i32, Vec<i64>>,
+    elements: &[Element],
+    nodes: &BTreeMap<i32, Node>,
+    segment_count: usize,
+) {
+    let foundation_elements = elements.iter().filter(|e| e.foundation.is_active());
+    crate::structure::utils::add_split_positions(split_positions, foundation_elements, nodes, segment_count);
+}
+
+/// Gets the soil pressures of the calculation elements with an elastic foundation as loads. The
+/// pressures are interpolated linearly from the end displacements of the calculation elements and
+/// they are split into two triangular loads in the local z-direction and two in the local
+/// x-direction.
+pub fn get_soil_pressure_loads(
+    calc_model: &CalcModel,
+    node_results: &NodeResults,
+) -> Vec<CalculationLoad> {
+    let mut loads = Vec::new();
+    for element in calc_model.get_all_calc_elements() {
+        if !element.foundation.is_active() {
+            continue;
+        }
+        let (k_z, k_x) = element.foundation.get_stiffness();
+        let local_displacements = node_results.get_elem_local_displacements(element);
+        // The loads resist the displacements
+        let perpendicular_rotation = element.rotation + 90.0;
+        for (strength, rotation, at_start) in [
+            (-k_z * local_displacements[(1, 0)], perpendicular_rotation, true),
+            (-k_z * local_displacements[(4, 0)], perpendicular_rotation, false),
+            (-k_x * local_displacements[(0, 0)], element.rotation, true),
+            (-k_x * local_displacements[(3, 0)], element.rotation, false),
+        ] {
+            if strength != 0.0 {
+                loads.push(get_triangular_load(element, strength, rotation, at_start));
+            }
+        }
+    }
+    loads
+}
+
+/// Gets a triangular load over the whole calculation element with the maximum value at the start
+/// or at the end of the element
+fn get_triangular_load(
+    element: &CalculationElement,
+    strength: f64,
+    rotation: f64,
+    at_start: bool,
+) -> CalculationLoad {
+    // The maximum value of the triangular load is at the offset start
+    let (offset_start, offset_end) = if at_start {
+        (0.0, element.length)
+    } else {
+        (element.length, 0.0)
+    };
+    CalculationLoad {
+        name: String::new(),
+        load_type: CalculationLoadType::Triangular,
+        offset_start,
+        offset_end,
+        strength,
+        rotation,
+        element_number: element.calc_el_num,
+    }
+}
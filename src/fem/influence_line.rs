@@ -0,0 +1,130 @@
+//! The influence lines. A unit point load is stepped along the path elements and each load
+//! position is solved as a load case with the same factorized stiffness matrix (see
+//! [super::fem_handler::calculate_influence_line]). The ordinates are the values of the response
+//! quantity at the load positions.
+
+use crate::loads::load::CalculationLoad;
+use crate::loads::{Load, LoadGroup};
+use crate::results::{ForceType, InfluenceOrdinate, InfluenceQuantity, NodeResults};
+use crate::settings::calc_settings::InfluenceLineSettings;
+use crate::settings::CalculationSettings;
+use crate::structure::{utils, Element, NodeCollection};
+
+use super::internal_forces::{
+    calc_internal_forces, calculate_axial_force_at, calculate_moment_at, calculate_shear_at,
+};
+use super::{deflection, foundation, CalcModel};
+
+/// The name of the unit load
+pub const UNIT_LOAD_NAME: &str = "Influence unit load";
+
+/// Gets the positions of the unit load along the path elements (see
+/// [crate::structure::utils::get_element_path]). The ordinates are returned with zero values. The
+/// shared node of the consecutive elements is included only once.
+/// * `path_elements` - the model element numbers of the path in the order of the path
+pub fn get_load_positions(
+    elements: &[Element],
+    nodes: &NodeCollection,
+    path_elements: &[i32],
+    settings: &InfluenceLineSettings,
+) -> Vec<InfluenceOrdinate> {
+    let step_count = settings.step_count.max(1);
+    let mut positions = Vec::new();
+    for path_element in utils::get_element_path(elements, nodes, path_elements) {
+        for step in 0..=step_count {
+            if step == 0 && path_element.connected {
+                continue;
+            }
+            let path_position =
+                path_element.path_start + path_element.length * step as f64 / step_count as f64;
+            positions.push(InfluenceOrdinate {
+                path_position,
+                element_number: path_element.element_number,
+                pos_on_element: path_element.get_pos_on_element(path_position),
+                value: 0.0,
+            });
+        }
+    }
+    positions
+}
+
+/// Gets the unit point load (1 kN) at the load position
+pub fn get_unit_load(position: &InfluenceOrdinate, settings: &InfluenceLineSettings) -> Load {
+    Load::new_point_load(
+        UNIT_LOAD_NAME.to_string(),
+        position.element_number.to_string(),
+        position.pos_on_element.to_string(),
+        "1".to_string(),
+        settings.load_rotation,
+        LoadGroup::PERMANENT,
+    )
+}
+
+/// Gets the value of the response quantity from the results of a single load position
+pub fn get_response(
+    calc_model: &CalcModel,
+    quantity: &InfluenceQuantity,
+    loads: &Vec<CalculationLoad>,
+    node_results: &NodeResults,
+    calc_settings: &CalculationSettings,
+) -> f64 {
+    match *quantity {
+        InfluenceQuantity::SupportReaction { node_number, direction } => {
+            node_results.get_support_reaction(node_number, direction)
+        }
+        InfluenceQuantity::Displacement { node_number, direction } => {
+            node_results.get_global_displacement(node_number, direction)
+        }
+        InfluenceQuantity::InternalForce { element_number, position, force_type } => {
+            get_internal_force(
+                calc_model,
+                element_number,
+                position,
+                force_type,
+                loads,
+                node_results,
+                calc_settings,
+            )
+        }
+    }
+}
+
+/// Gets the internal force of the model element at the position. Only the calculation element at
+/// the position is calculated, except the soil pressures, which are taken from all the internal
+/// forces of the element.
+fn get_internal_force(
+    calc_model: &CalcModel,
+    element_number: i32,
+    position: f64,
+    force_type: ForceType,
+    loads: &Vec<CalculationLoad>,
+    node_results: &NodeResults,
+    calc_settings: &CalculationSettings,
+) -> f64 {
+    if let ForceType::SoilPressure = force_type {
+        return calc_internal_forces(calc_model, loads, node_results, calc_settings)
+            .get(&element_number)
+            .and_then(|f| f.get_force_at(force_type, position))
+            .map_or(0.0, |f| f.value_y);
+    }
+    let Some(element) = calc_model.get_calc_element_at(element_number, position) else {
+        return 0.0;
+    };
+    let x = (position - element.offset_from_model_el).clamp(0.0, element.length);
+    // The soil pressures of the elastic foundations are loads on the calculation elements
+    let soil_pressure_loads = foundation::get_soil_pressure_loads(calc_model, node_results);
+    let all_loads;
+    let loads = if soil_pressure_loads.is_empty() {
+        loads
+    } else {
+        all_loads = [loads.as_slice(), soil_pressure_loads.as_slice()].concat();
+        &all_loads
+    };
+    match force_type {
+        ForceType::Axial => calculate_axial_force_at(x, element, loads, node_results, calc_settings),
+        ForceType::Shear => calculate_shear_at(x, element, loads, node_results, calc_settings),
+        ForceType::Moment => calculate_moment_at(x, element, loads, node_results, calc_settings),
+        ForceType::Deflection => deflection::calculate_at(x, element, loads, calc_settings, node_results),
+        ForceType::SoilPressure => 0.0,
+    }
+}
@@ -5,12 +5,12 @@ use std::collections::BTreeMap;
 use crate::{
     loads::load::{self, CalculationLoad},
     results::{ForceType, InternalForcePoint, InternalForceResults},
-    settings::{self, CalculationSettings},
+    settings::{self, calc_settings::AnalysisType, CalculationSettings},
     structure::CalculationElement,
 };
 
 use crate::results::NodeResults;
-use super::{axial_deformation, deflection, CalcModel};
+use super::{axial_deformation, deflection, foundation, CalcModel};
 
 /// Calculates the internal forces for the elements by support reactions and displacements in node results
 ///
@@ -28,11 +28,21 @@ pub fn calc_internal_forces(
     calc_settings: &CalculationSettings,
 ) -> BTreeMap<i32, InternalForceResults> {
     let mut map: BTreeMap<i32, InternalForceResults> = BTreeMap::new();
+    // The soil pressures of the elastic foundations are loads on the calculation elements
+    let soil_pressure_loads = foundation::get_soil_pressure_loads(calc_model, node_results);
+    let all_loads;
+    let loads = if soil_pressure_loads.is_empty() {
+        loads
+    } else {
+        all_loads = [loads.as_slice(), soil_pressure_loads.as_slice()].concat();
+        &all_loads
+    };
     for structure_element in calc_model.structure_elements {
         let mut moment_forces = vec![];
         let mut shear_forces = vec![];
         let mut axial_forces = vec![];
         let mut deflections = vec![];
+        let mut soil_pressures = vec![];
         for element in calc_model.calc_elements[&structure_element.number].iter() {
             let element_length = element.length;
             let split_interval = match calc_settings.calc_split_interval {
@@ -85,6 +95,19 @@ pub fn calc_internal_forces(
                     element_number: element.model_el_num,
                     load_comb_number: 0,
                 });
+                if element.foundation.is_active() {
+                    let (pressure, axial_pressure) = element
+                        .foundation
+                        .get_pressures(deflection_val, axial_deformation_val);
+                    soil_pressures.push(InternalForcePoint {
+                        force_type: ForceType::SoilPressure,
+                        value_x: axial_pressure,
+                        value_y: pressure,
+                        pos_on_element: x + element.offset_from_model_el,
+                        element_number: element.model_el_num,
+                        load_comb_number: 0,
+                    });
+                }
 
                 x += split_interval;
 
@@ -99,12 +122,31 @@ pub fn calc_internal_forces(
             }
         }
 
+        // The inactive elements (e.g. a compressed tension-only element) do not carry any forces
+        if calc_model.inactive_elements.contains(&structure_element.number) {
+            for point in axial_forces
+                .iter_mut()
+                .chain(shear_forces.iter_mut())
+                .chain(moment_forces.iter_mut())
+            {
+                point.value_y = 0.0;
+            }
+        }
+
+        // The rotations of the released connections at the ends of the model element
+        let calc_elements = &calc_model.calc_elements[&structure_element.number];
+        let end_rotations = [(calc_elements.first(), 2), (calc_elements.last(), 5)].map(|(e, i)| {
+            e.map_or(0.0, |e| get_release_rotation(e, i, loads, node_results, calc_settings))
+        });
+
         let res = InternalForceResults {
             element_number: structure_element.number,
             axial_forces,
             shear_forces,
             moment_forces,
             deflections,
+            soil_pressures,
+            end_rotations,
         };
         map.insert(structure_element.number, res);
     }
@@ -112,6 +154,23 @@ pub fn calc_internal_forces(
     map
 }
 
+/// Gets the rotation of the element end relative to the node at given index (2 = start, 5 = end).
+/// Returns zero if the rotation is not released.
+fn get_release_rotation(
+    element: &CalculationElement,
+    index: usize,
+    loads: &Vec<CalculationLoad>,
+    node_results: &NodeResults,
+    calc_settings: &CalculationSettings,
+) -> f64 {
+    if !element.releases.get_release_value(index).unwrap() {
+        return 0.0;
+    }
+    let end_displacements = node_results.get_elem_local_end_displacements(element, loads, calc_settings);
+    let node_displacements = node_results.get_elem_local_displacements(element);
+    end_displacements[index] - node_displacements[index]
+}
+
 pub fn calculate_moment_at(
     x: f64,
     element: &CalculationElement,
@@ -160,15 +219,35 @@ pub fn calculate_moment_at(
                     moment += moment_triang_rtl(element, load, x)
                 }
             }
-            load::CalculationLoadType::Strain => {}
+            load::CalculationLoadType::Strain | load::CalculationLoadType::Curvature => {}
         };
     }
 
     moment += local_reactions[(1, 0)] * x - local_reactions[(2, 0)];
+    moment += second_order_moment_at(x, element, results, settings);
 
     moment
 }
 
+/// Calculates the moment of the axial force on the displacement of the element (P-Δ) in the
+/// second-order analysis. The end forces of the element include the geometric stiffness, so the
+/// shear force is perpendicular to the undeformed element and the axial force moment is needed for
+/// the equilibrium in the deformed geometry. The displacement is interpolated linearly between
+/// the element ends (the elements are split into segments in the second-order analysis).
+fn second_order_moment_at(
+    x: f64,
+    element: &CalculationElement,
+    results: &NodeResults,
+    settings: &CalculationSettings,
+) -> f64 {
+    if settings.analysis_type != AnalysisType::SecondOrder || element.axial_force == 0.0 {
+        return 0.0;
+    }
+    let local_displacements = results.get_elem_local_displacements(element);
+    let chord_displacement = local_displacements[(4, 0)] - local_displacements[(1, 0)];
+    element.axial_force * chord_displacement * x / element.length
+}
+
 pub fn calculate_shear_at(
     x: f64,
     element: &CalculationElement,
@@ -212,7 +291,7 @@ pub fn calculate_shear_at(
                     shear += handle_linear_force_triang_rtl(load, x, z_dir_factor)
                 }
             }
-            load::CalculationLoadType::Strain => {}
+            load::CalculationLoadType::Strain | load::CalculationLoadType::Curvature => {}
         };
     }
 
@@ -236,8 +315,8 @@ pub fn calculate_axial_force_at(
         if load.element_number != element.calc_el_num {
             continue;
         }
-        // The factor to handle skewed loads
-        let x_dir_factor = -load.rotation.to_radians().cos();
+        // The factor to handle skewed loads (the load component in the direction of the element)
+        let x_dir_factor = -(load.rotation - element.rotation).to_radians().cos();
         match load.load_type {
             load::CalculationLoadType::Point => {
                 if load.offset_start <= x {
@@ -264,7 +343,7 @@ pub fn calculate_axial_force_at(
                     axial_f += handle_linear_force_triang_rtl(load, x, x_dir_factor)
                 }
             }
-            load::CalculationLoadType::Strain => {}
+            load::CalculationLoadType::Strain | load::CalculationLoadType::Curvature => {}
         };
     }
 
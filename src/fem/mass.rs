@@ -0,0 +1,135 @@
+#![allow(non_snake_case)]
+
+//! The mass matrices for the modal analysis. The masses are in tonnes (1000 kg) in the matrices, so
+//! that the eigenvalues of the stiffness matrix (N/mm) and the mass matrix are in 1/s².
+
+use nalgebra::DMatrix;
+
+use super::matrices::{apply_rigid_offsets, get_rotation_matrix};
+use super::sparse::SparseMatrix;
+use super::stiffness::add_element_matrix;
+use super::CalcModel;
+use crate::settings::calc_settings::MassMatrixType;
+use crate::structure::CalculationElement;
+
+/// Gets the mass matrix of the element in the global coordinate system. The rigid end offsets
+/// have no mass, but the mass of the element is transformed to the nodes through them.
+pub fn get_element_global_mass_matrix(
+    e: &CalculationElement,
+    mass_matrix_type: MassMatrixType,
+) -> DMatrix<f64> {
+    let e_mass_matrix = get_element_mass_matrix(e, mass_matrix_type);
+    let e_rotation_matrix = get_rotation_matrix(e.rotation);
+    apply_rigid_offsets(e, e_rotation_matrix.transpose() * e_mass_matrix * e_rotation_matrix)
+}
+
+/// Gets the mass matrix of the element in elements local coordinate system (tonnes). The mass of
+/// the element is calculated from the profile and the material (see
+/// [CalculationElement::get_mass_per_length]). The releases of the element do not affect the mass
+/// matrix.
+pub fn get_element_mass_matrix(
+    element: &CalculationElement,
+    mass_matrix_type: MassMatrixType,
+) -> DMatrix<f64> {
+    let L = element.length;
+    // kg/mm => t/mm
+    let m = element.get_mass_per_length() * 1e-3;
+    match mass_matrix_type {
+        MassMatrixType::Consistent => {
+            let a = m * L / 6.0;
+            let b = m * L / 420.0;
+            DMatrix::from_row_slice(
+                6,
+                6,
+                &[
+                    2.0 * a, 0.0, 0.0, a, 0.0, 0.0,
+                    0.0, 156.0 * b, 22.0 * L * b, 0.0, 54.0 * b, -13.0 * L * b,
+                    0.0, 22.0 * L * b, 4.0 * L * L * b, 0.0, 13.0 * L * b, -3.0 * L * L * b,
+                    a, 0.0, 0.0, 2.0 * a, 0.0, 0.0,
+                    0.0, 54.0 * b, 13.0 * L * b, 0.0, 156.0 * b, -22.0 * L * b,
+                    0.0, -13.0 * L * b, -3.0 * L * L * b, 0.0, -22.0 * L * b, 4.0 * L * L * b,
+                ],
+            )
+        }
+        MassMatrixType::Lumped => {
+            let half = m * L / 2.0;
+            DMatrix::from_diagonal(&nalgebra::DVector::from_vec(vec![
+                half, half, 0.0, half, half, 0.0,
+            ]))
+        }
+    }
+}
+
+/// Creates the joined mass matrix of the calculation model. The masses of the structure nodes and
+/// the additional masses are added to the translations of the nodes.
+/// * `additional_masses` - the additional masses (kg) of the nodes in the order of the node
+///   numbers (e.g. the masses of the loads). Can be empty.
+pub(super) fn create_joined_mass_matrix(
+    calc_model: &CalcModel,
+    mass_matrix_type: MassMatrixType,
+    additional_masses: &[f64],
+) -> SparseMatrix {
+    let supp_count = calc_model.structure_nodes.len() + calc_model.extra_nodes.len();
+    // The degrees of freedom count of single node (tx, tz, ry)
+    let dof = 3;
+    let mut matrix = SparseMatrix::new(supp_count * dof);
+
+    for elem in calc_model.get_all_calc_elements() {
+        let e_glob_mass_matrix = get_element_global_mass_matrix(elem, mass_matrix_type);
+        add_element_matrix(&mut matrix, elem, &e_glob_mass_matrix);
+    }
+    for node in calc_model.structure_nodes.values() {
+        if node.mass != 0.0 && node.number > 0 {
+            let index = (node.number as usize - 1) * dof;
+            // kg => t
+            matrix.add(index, index, node.mass * 1e-3);
+            matrix.add(index + 1, index + 1, node.mass * 1e-3);
+        }
+    }
+    for (i, mass) in additional_masses.iter().enumerate() {
+        if *mass != 0.0 {
+            matrix.add(i * dof, i * dof, mass * 1e-3);
+            matrix.add(i * dof + 1, i * dof + 1, mass * 1e-3);
+        }
+    }
+
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{MaterialData, Steel};
+    use crate::profile::Profile;
+    use crate::settings::CalculationSettings;
+    use crate::structure::{Element, Node};
+    use std::collections::BTreeMap;
+    use vputilslib::geometry2d::VpPoint;
+
+    #[test]
+    fn test_element_mass_matrix() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(3000.0, 4000.0))),
+        ]);
+        let element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        let calc_element = CalculationElement::from(&element, &nodes, 1, &CalculationSettings::default());
+        // 100 mm * 100 mm * 5000 mm * 7850 kg/m³ = 392.5 kg
+        let total_mass = 0.3925;
+        for mass_matrix_type in [MassMatrixType::Consistent, MassMatrixType::Lumped] {
+            let mass_matrix = get_element_global_mass_matrix(&calc_element, mass_matrix_type);
+            // A rigid body translation in any direction has the total mass of the element
+            for (x, z) in [(1.0, 0.0), (0.0, 1.0), (0.6, 0.8)] {
+                let translation = DMatrix::from_vec(6, 1, vec![x, z, 0.0, x, z, 0.0]);
+                let mass = (translation.transpose() * &mass_matrix * &translation)[(0, 0)];
+                assert!((mass - total_mass).abs() < 1e-9);
+            }
+        }
+    }
+}
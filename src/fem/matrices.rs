@@ -1,90 +1,177 @@
 ﻿#![allow(dead_code)]
 
-use crate::fem::stiffness::create_joined_stiffness_matrix;
-use crate::fem::{equivalent_loads, matrices, CalcModel};
-use crate::loads::load::CalculationLoad;
+use crate::fem::sparse::SparseMatrix;
+use crate::fem::stiffness::{create_joined_geometric_stiffness_matrix, create_joined_stiffness_matrix};
+use crate::fem::{constraints, equivalent_loads, mass, matrices, CalcModel};
+use crate::loads::load::{CalculationLoad, PrescribedDisplacement};
+use crate::settings::calc_settings::MassMatrixType;
 use crate::settings::CalculationSettings;
-use crate::structure::{Node};
+use crate::structure::{CalculationElement, Node};
 use nalgebra::DMatrix;
 use std::collections::BTreeMap;
 
 pub struct CalculationMatrix {
-    pub stiffness: DMatrix<f64>,
+    pub stiffness: SparseMatrix,
     pub equivalent_loads: DMatrix<f64>,
 }
 
 pub fn create_global_calculation_matrix(
     calc_model: &CalcModel, calc_settings: &CalculationSettings, calculation_loads: &Vec<CalculationLoad>
 ) -> CalculationMatrix {
+    CalculationMatrix {
+        stiffness: create_global_stiffness_matrix(calc_model, calc_settings),
+        equivalent_loads: create_global_equivalent_loads_matrix(calc_model, calc_settings, calculation_loads),
+    }
+}
+
+/// Creates the global stiffness matrix with the nodal constraints and the support rotations
+/// applied. The stiffness matrix does not depend on the loads, so the same matrix can be used for
+/// all the load combinations.
+pub fn create_global_stiffness_matrix(
+    calc_model: &CalcModel, calc_settings: &CalculationSettings
+) -> SparseMatrix {
     let mut global_stiff_matrix = create_joined_stiffness_matrix(calc_model, calc_settings);
-    // The global equivalent loads matrix
+    constraints::apply_constraints_to_stiffness(calc_model, &mut global_stiff_matrix);
+    apply_support_rotations_to_stiffness(calc_model.structure_nodes, &mut global_stiff_matrix);
+    global_stiff_matrix
+}
+
+/// Creates the global geometric stiffness matrix from the axial forces of the calculation elements
+/// with the support rotations applied
+pub fn create_global_geometric_stiffness_matrix(calc_model: &CalcModel) -> SparseMatrix {
+    let mut global_geom_matrix = create_joined_geometric_stiffness_matrix(calc_model);
+    apply_support_rotations_to_stiffness(calc_model.structure_nodes, &mut global_geom_matrix);
+    global_geom_matrix
+}
+
+/// Creates the global mass matrix (tonnes) with the support rotations applied
+/// * `additional_masses` - the additional masses (kg) of the nodes in the order of the node numbers
+pub fn create_global_mass_matrix(
+    calc_model: &CalcModel, mass_matrix_type: MassMatrixType, additional_masses: &[f64]
+) -> SparseMatrix {
+    let mut global_mass_matrix = mass::create_joined_mass_matrix(calc_model, mass_matrix_type, additional_masses);
+    apply_support_rotations_to_stiffness(calc_model.structure_nodes, &mut global_mass_matrix);
+    global_mass_matrix
+}
+
+/// Creates the global equivalent loads matrix (single column) with the support rotations applied
+pub fn create_global_equivalent_loads_matrix(
+    calc_model: &CalcModel, calc_settings: &CalculationSettings, calculation_loads: &Vec<CalculationLoad>
+) -> DMatrix<f64> {
     let mut global_eq_l_matrix = equivalent_loads::create(calc_model, calculation_loads, calc_settings);
-    apply_support_rotation_values(calc_model.structure_nodes, &mut global_stiff_matrix,
-                                  &mut global_eq_l_matrix
-    );
-    CalculationMatrix {
-        stiffness: global_stiff_matrix,
-        equivalent_loads: global_eq_l_matrix,
+    apply_support_rotations_to_loads(calc_model.structure_nodes, &mut global_eq_l_matrix);
+    global_eq_l_matrix
+}
+
+/// Creates the global prescribed displacements matrix (single column) from the prescribed
+/// displacements of the supports with the support rotations applied. The displacements are set
+/// only to the locked degrees of freedom of the nodes and the other rows are zero.
+/// * `nodes` - the nodes with the support locks (e.g. the one-directional supports that are
+///   released are not displaced)
+pub fn create_global_prescribed_displacements_matrix(
+    calc_model: &CalcModel,
+    nodes: &BTreeMap<i32, Node>,
+    prescribed_displacements: &[PrescribedDisplacement],
+) -> DMatrix<f64> {
+    let dof = 3;
+    let mut matrix = DMatrix::zeros(calc_model.get_node_count() * dof, 1);
+    for displacement in prescribed_displacements {
+        if !calc_model.structure_nodes.contains_key(&displacement.node_number)
+            || displacement.node_number <= 0
+        {
+            continue;
+        }
+        let node_index = (displacement.node_number as usize - 1) * dof;
+        matrix[(node_index, 0)] += displacement.x;
+        matrix[(node_index + 1, 0)] += displacement.z;
+        matrix[(node_index + 2, 0)] += displacement.ry;
     }
+    apply_support_rotations_to_loads(calc_model.structure_nodes, &mut matrix);
+    for node in nodes.values() {
+        if node.number <= 0 {
+            continue;
+        }
+        let node_index = (node.number as usize - 1) * dof;
+        for i in 0..dof {
+            if !node.support.get_support_lock(i) {
+                matrix[(node_index + i, 0)] = 0.0;
+            }
+        }
+    }
+    matrix
 }
 
-/// Applies the rotations from supports to stiffness matrix and equivalent loads
-fn apply_support_rotation_values(
+/// Applies the rotations from supports to stiffness matrix
+fn apply_support_rotations_to_stiffness(
     nodes: &BTreeMap<i32, Node>,
-    global_stiff_matrix: &mut DMatrix<f64>,
-    global_equivalent_loads_matrix: &mut DMatrix<f64>,
+    global_stiff_matrix: &mut SparseMatrix,
 ) {
     let dof = 3;
     for node in nodes.values() {
         if node.support.rotation != 0.0 && node.number > 0 {
-            let node_number = node.number as usize;
+            let node_index = (node.number as usize - 1) * dof;
             let small_rotation_matrix = matrices::get_small_rotation_matrix(node.support.rotation);
-            let rotation_matrix_transposed = small_rotation_matrix.transpose();
-            let mut small_stiff_matrix_col = DMatrix::zeros(nodes.len() * dof, dof);
-            let mut small_stiff_matrix_row: DMatrix<f64> = DMatrix::zeros(dof, nodes.len() * dof);
-            // Gather the columns (matrix size: nodes*dof, dof)
-            for i in 0..nodes.len() * dof {
-                for j in 0..dof {
-                    small_stiff_matrix_col[(i, j)] = global_stiff_matrix
-                        [(i, (node_number - 1) * dof + j)];
+            // The stiffness matrix is symmetric, so the rows connected to the node are the
+            // columns of the node rows
+            let mut connected_rows: Vec<usize> = (node_index..node_index + dof).collect();
+            for i in 0..dof {
+                for (col, _) in global_stiff_matrix.row(node_index + i) {
+                    if !connected_rows.contains(&col) {
+                        connected_rows.push(col);
+                    }
                 }
             }
             // T*K*Ttranspose
-            // K*Ttranspose
-            let stiff_and_transposed = &small_stiff_matrix_col * rotation_matrix_transposed;
-            // Update the global stiffness matrix.
-            for i in 0..nodes.len() * dof {
-                for j in 0..dof {
-                    global_stiff_matrix[(i, (node_number - 1) * dof + j)] =
-                        stiff_and_transposed[(i, j)];
+            // K*Ttranspose (the columns of the node)
+            for row in connected_rows.iter() {
+                let mut rotated = [0.0; 3];
+                for (j, value) in rotated.iter_mut().enumerate() {
+                    for k in 0..dof {
+                        *value += global_stiff_matrix.get(*row, node_index + k)
+                            * small_rotation_matrix[(j, k)];
+                    }
                 }
-            }
-
-            // Gather the columns (matrix size: dof, nodes*dof)
-            for i in 0..dof {
-                for j in 0..nodes.len() * dof {
-                    small_stiff_matrix_row[(i, j)] = global_stiff_matrix
-                        [((node_number - 1) * dof + i, j)];
+                for (j, value) in rotated.iter().enumerate() {
+                    global_stiff_matrix.set(*row, node_index + j, *value);
                 }
             }
-            // T*KTtranspose = TKTtranspose
-            let fully_rotated = &small_rotation_matrix * small_stiff_matrix_row;
-            // Final update of the global stiffness matrix
-            for i in 0..dof {
-                for j in 0..nodes.len() *  dof {
-                    global_stiff_matrix[((node_number - 1) * dof + i, j)] =
-                        fully_rotated[(i, j)];
+            // T*KTtranspose (the rows of the node)
+            for col in connected_rows.iter() {
+                let mut rotated = [0.0; 3];
+                for (i, value) in rotated.iter_mut().enumerate() {
+                    for k in 0..dof {
+                        *value += small_rotation_matrix[(i, k)]
+                            * global_stiff_matrix.get(node_index + k, *col);
+                    }
+                }
+                for (i, value) in rotated.iter().enumerate() {
+                    global_stiff_matrix.set(node_index + i, *col, *value);
                 }
             }
+        }
+    }
+}
+
+/// Applies the rotations from supports to equivalent loads (or any other vector in the global
+/// coordinates, e.g. a rigid body translation)
+pub fn apply_support_rotations_to_loads(
+    nodes: &BTreeMap<i32, Node>,
+    global_equivalent_loads_matrix: &mut DMatrix<f64>,
+) {
+    let dof = 3;
+    for node in nodes.values() {
+        if node.support.rotation != 0.0 && node.number > 0 {
+            let node_index = (node.number as usize - 1) * dof;
+            let small_rotation_matrix = matrices::get_small_rotation_matrix(node.support.rotation);
             let mut small_equivalent_loads_matrix = DMatrix::zeros(dof, 1);
             for i in 0..dof {
                 small_equivalent_loads_matrix[(i, 0)] =
-                    global_equivalent_loads_matrix[((node_number - 1) * dof + i, 0)]
+                    global_equivalent_loads_matrix[(node_index + i, 0)]
             }
             // Rotate the equivalent loads matrix
             let rotated_equivalent_loads_matrix = &small_rotation_matrix * small_equivalent_loads_matrix;
             for i in 0..dof {
-                global_equivalent_loads_matrix[((node_number - 1) * dof + i, 0)] =
+                global_equivalent_loads_matrix[(node_index + i, 0)] =
                     rotated_equivalent_loads_matrix[(i, 0)];
             }
         }
@@ -110,6 +197,48 @@ pub fn get_rotation_matrix(rotation: f64) -> DMatrix<f64> {
     )
 }
 
+/// Gets the transformation matrix of the rigid end offsets of the element. The matrix transforms
+/// the global displacements of the nodes into the global displacements of the ends of the element
+/// (u<sub>e</sub> = T u<sub>n</sub>). The ends move with the rotations of the nodes, so a rotation
+/// θ moves the end by (-θ * z, θ * x), where (x, z) is the offset from the node to the end.
+pub fn get_rigid_offset_matrix(element: &CalculationElement) -> DMatrix<f64> {
+    let (sx, sz) = element.rigid_offset_start;
+    let (ex, ez) = element.rigid_offset_end;
+    DMatrix::from_row_slice(
+        6,
+        6,
+        &[
+            1.0, 0.0, -sz, 0.0, 0.0, 0.0,
+            0.0, 1.0, sx,  0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 1.0, 0.0, -ez,
+            0.0, 0.0, 0.0, 0.0, 1.0, ex,
+            0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ],
+    )
+}
+
+/// Transforms the element matrix (6x6) in the global coordinate system from the ends of the
+/// element to the nodes through the rigid end offsets (T<sup>T</sup> K T). See
+/// [get_rigid_offset_matrix].
+pub fn apply_rigid_offsets(element: &CalculationElement, matrix: DMatrix<f64>) -> DMatrix<f64> {
+    if !element.has_rigid_offsets() {
+        return matrix;
+    }
+    let offset_matrix = get_rigid_offset_matrix(element);
+    offset_matrix.transpose() * matrix * offset_matrix
+}
+
+/// Transforms the element loads (6x1) in the global coordinate system from the ends of the
+/// element to the nodes through the rigid end offsets (T<sup>T</sup> F). See
+/// [get_rigid_offset_matrix].
+pub fn apply_rigid_offsets_to_loads(element: &CalculationElement, loads: DMatrix<f64>) -> DMatrix<f64> {
+    if !element.has_rigid_offsets() {
+        return loads;
+    }
+    get_rigid_offset_matrix(element).transpose() * loads
+}
+
 /// Gets the rotation matrix for the node. This matrix is in elements local coordinate system
 pub fn get_small_rotation_matrix(rotation: f64) -> DMatrix<f64> {
     let angle_radians = rotation.to_radians();
@@ -142,7 +271,10 @@ pub fn get_diagonal_matrix() -> DMatrix<f64> {
     )
 }
 
-pub fn get_unknown_translation_rows(nodes: &BTreeMap<i32, Node>, matrix: &DMatrix<f64>) -> Vec<i32> {
+/// Gets the rows of the matrix that have unknown translations (the degrees of freedom that are not
+/// locked by the supports). The rows of the extra nodes (after the structure nodes) are always
+/// unknown.
+pub fn get_unknown_translation_rows(nodes: &BTreeMap<i32, Node>, matrix: &SparseMatrix) -> Vec<i32> {
     let node_count = nodes.len();
     let mut result: Vec<i32> = Vec::new();
     let dof: usize = 3;
@@ -154,7 +286,7 @@ pub fn get_unknown_translation_rows(nodes: &BTreeMap<i32, Node>, matrix: &DMatri
             }
         }
     }
-    // Gather the rows of element releases
+    // Gather the rows of the extra nodes
     for i in (node_count * dof)..matrix.nrows() {
         result.push(i as i32);
     }
@@ -165,31 +297,6 @@ pub fn get_unknown_translation_rows(nodes: &BTreeMap<i32, Node>, matrix: &DMatri
     result
 }
 
-pub fn get_unknown_translation_stiffness_rows(
-    unknown_translation_rows: &Vec<i32>,
-    matrix: &DMatrix<f64>,
-) -> DMatrix<f64> {
-    let mut return_matrix = DMatrix::zeros(
-        unknown_translation_rows.len(),
-        unknown_translation_rows.len(),
-    );
-    let mut cur_row = 0;
-    // Iterate through the unknown translation rows and columns and add them to the return matrix
-    for row in 0..unknown_translation_rows.len() {
-        let mut cur_col = 0;
-        for col in 0..unknown_translation_rows.len() {
-            return_matrix[(cur_row, cur_col)] = matrix[(
-                unknown_translation_rows[row] as usize,
-                unknown_translation_rows[col] as usize,
-            )];
-            cur_col += 1;
-        }
-        cur_row += 1;
-    }
-
-    return_matrix
-}
-
 pub fn get_unknown_translation_eq_loads_rows(
     unknown_translation_rows: &Vec<i32>,
     matrix: &DMatrix<f64>,
@@ -0,0 +1,109 @@
+//! The modal analysis. The natural angular frequencies ω and the mode shapes φ are solved from the
+//! generalized eigenproblem K φ = ω² M φ, where M is the mass matrix (see [super::mass]). The
+//! lowest modes are solved with the subspace iteration (see [super::eigen]).
+
+use std::f64::consts::PI;
+
+use nalgebra::DMatrix;
+
+use super::fem_handler::StiffnessFactorization;
+use super::sparse::SparseMatrix;
+use super::{eigen, equivalent_loads, matrices, CalcModel};
+use crate::loads::load::{CalculationLoad, CalculationLoadType};
+use crate::loads::utils::GRAVITY;
+use crate::results::VibrationMode;
+use crate::settings::calc_settings::ModalSettings;
+use crate::settings::CalculationSettings;
+
+/// Solves the lowest vibration modes. Returns the modes in the ascending order of the natural
+/// frequencies and whether the frequencies converged.
+/// * `factorization` - the factorized stiffness matrix
+/// * `mass_matrix` - the global mass matrix (tonnes)
+/// * `calc_model` - the calculation model (for the rigid body translations of the nodes)
+/// * `settings` - the modal settings
+pub fn solve_modes(
+    factorization: &StiffnessFactorization,
+    mass_matrix: &SparseMatrix,
+    calc_model: &CalcModel,
+    settings: &ModalSettings,
+) -> (Vec<VibrationMode>, bool) {
+    let (pairs, converged) = eigen::solve_lowest(
+        factorization,
+        mass_matrix,
+        settings.mode_count,
+        settings.max_iterations,
+        settings.tolerance,
+    );
+    let (translation_x, translation_z) = get_rigid_body_translations(calc_model);
+    let inertia_x = mass_matrix * &translation_x;
+    let inertia_z = mass_matrix * &translation_z;
+    let modes = pairs
+        .into_iter()
+        .map(|pair| {
+            // The eigenvectors are mass normalized (φᵀ M φ = 1), so the participation factors are
+            // φᵀ M r and the effective masses are their squares
+            let factor_x: f64 = pair.vector.iter().zip(inertia_x.iter()).map(|(p, m)| p * m).sum();
+            let factor_z: f64 = pair.vector.iter().zip(inertia_z.iter()).map(|(p, m)| p * m).sum();
+            // The participation factors of the normalized mode shape φ / c are c φᵀ M r
+            let scale = eigen::get_largest_translation(&pair.vector);
+            let angular_frequency = pair.eigenvalue.sqrt();
+            VibrationMode {
+                frequency: angular_frequency / (2.0 * PI),
+                angular_frequency,
+                mode_shape: eigen::normalize_to_largest_translation(pair.vector),
+                participation_factor_x: factor_x * scale,
+                participation_factor_z: factor_z * scale,
+                // t => kg
+                effective_mass_x: factor_x.powi(2) * 1e3,
+                effective_mass_z: factor_z.powi(2) * 1e3,
+            }
+        })
+        .collect();
+    (modes, converged)
+}
+
+/// Gets the total mass (kg) of the mass matrix. The masses at the supports are included.
+pub fn get_total_mass(mass_matrix: &SparseMatrix, calc_model: &CalcModel) -> f64 {
+    let (translation_x, _) = get_rigid_body_translations(calc_model);
+    // t => kg
+    (translation_x.transpose() * (mass_matrix * &translation_x))[(0, 0)] * 1e3
+}
+
+/// Gets the masses (kg) of the loads at the nodes in the order of the node numbers. The downward
+/// component of the equivalent nodal loads is divided by the gravity. Only the force loads (point
+/// and distributed loads) are converted into the masses.
+pub fn get_load_masses(
+    calc_model: &CalcModel,
+    calculation_loads: &[CalculationLoad],
+    calc_settings: &CalculationSettings,
+) -> Vec<f64> {
+    let force_loads: Vec<CalculationLoad> = calculation_loads
+        .iter()
+        .filter(|l| {
+            matches!(
+                l.load_type,
+                CalculationLoadType::Point | CalculationLoadType::Line | CalculationLoadType::Triangular
+            )
+        })
+        .cloned()
+        .collect();
+    let equivalent_loads = equivalent_loads::create(calc_model, &force_loads, calc_settings);
+    (0..calc_model.get_node_count())
+        .map(|i| (-equivalent_loads[(i * 3 + 1, 0)]).max(0.0) / GRAVITY)
+        .collect()
+}
+
+/// Gets the rigid body translations of all the nodes in the global X- and Z-directions with the
+/// support rotations applied
+fn get_rigid_body_translations(calc_model: &CalcModel) -> (DMatrix<f64>, DMatrix<f64>) {
+    let node_count = calc_model.get_node_count();
+    let mut translation_x = DMatrix::zeros(node_count * 3, 1);
+    let mut translation_z = DMatrix::zeros(node_count * 3, 1);
+    for i in 0..node_count {
+        translation_x[(i * 3, 0)] = 1.0;
+        translation_z[(i * 3 + 1, 0)] = 1.0;
+    }
+    matrices::apply_support_rotations_to_loads(calc_model.structure_nodes, &mut translation_x);
+    matrices::apply_support_rotations_to_loads(calc_model.structure_nodes, &mut translation_z);
+    (translation_x, translation_z)
+}
@@ -0,0 +1,50 @@
+//! The second-order (P-Δ) analysis. The geometric stiffnesses of the calculation elements are
+//! calculated from the axial forces of the previous solution and the displacements are iterated
+//! until they converge. The elements are split into segments, so that the deformations inside
+//! the elements (P-δ) are taken into account too.
+
+use std::collections::BTreeMap;
+
+use crate::loads::load::CalculationLoad;
+use crate::results::{InternalForceResults, NodeResults};
+use crate::settings::CalculationSettings;
+use crate::structure::CalculationElement;
+
+use super::internal_forces::calculate_axial_force_at;
+
+/// Updates the axial forces of the calculation elements by the axial forces at the middle of the
+/// calculation elements.
+pub fn update_axial_forces(
+    calc_elements: &mut BTreeMap<i32, Vec<CalculationElement>>,
+    loads: &Vec<CalculationLoad>,
+    node_results: &NodeResults,
+    calc_settings: &CalculationSettings,
+) {
+    for element in calc_elements.values_mut().flatten() {
+        element.axial_force =
+            calculate_axial_force_at(element.length / 2.0, element, loads, node_results, calc_settings);
+    }
+}
+
+/// Gets the moment amplification factors of the elements, i.e. the ratio of the largest absolute
+/// second-order moment to the largest absolute first-order moment. The factor is 1.0 for the
+/// elements without first-order moments.
+pub fn get_moment_amplifications(
+    first_order: &BTreeMap<i32, InternalForceResults>,
+    second_order: &BTreeMap<i32, InternalForceResults>,
+) -> BTreeMap<i32, f64> {
+    let max_moment = |forces: &InternalForceResults| {
+        forces.moment_forces.iter().fold(0.0f64, |acc, m| acc.max(m.value_y.abs()))
+    };
+    let mut result = BTreeMap::new();
+    for (el_number, forces) in second_order {
+        let first_order_moment = first_order.get(el_number).map_or(0.0, max_moment);
+        let amplification = if first_order_moment > 0.0 {
+            max_moment(forces) / first_order_moment
+        } else {
+            1.0
+        };
+        result.insert(*el_number, amplification);
+    }
+    result
+}
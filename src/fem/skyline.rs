@@ -0,0 +1,257 @@
+//! The skyline (profile) storage of the symmetric matrices and the LDLᵀ factorization. Only the
+//! upper triangle of each column is stored from the first non-zero row to the diagonal, so the
+//! storage and the work of the factorization depend on the profile of the matrix. Renumber the
+//! rows with [crate::fem::sparse::get_renumbered_rows] to keep the profile small.
+
+use super::sparse::SparseMatrix;
+
+/// The pivots of the factorization that are smaller than this times the original diagonal value
+/// are considered to be zero (the matrix is singular, e.g. the structure is a mechanism)
+const PIVOT_TOLERANCE: f64 = 1e-13;
+
+/// A symmetric matrix in the skyline storage
+#[derive(Debug, Clone)]
+pub struct SkylineMatrix {
+    /// The first stored row of each column
+    first_rows: Vec<usize>,
+    /// The index of the first stored value of each column in the values vector. The last item is
+    /// the length of the values vector.
+    offsets: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl SkylineMatrix {
+    /// Creates the skyline matrix from the rows and the columns of the sparse matrix. The sparse
+    /// matrix is assumed to be symmetric.
+    /// * `matrix` - the sparse matrix
+    /// * `order` - the rows of the sparse matrix in the order of the skyline matrix (new index -> row).
+    ///   The rows that are not in the order are left out.
+    pub fn from_sparse(matrix: &SparseMatrix, order: &[usize]) -> Self {
+        let mut new_indices: Vec<Option<usize>> = vec![None; matrix.nrows()];
+        for (i, row) in order.iter().enumerate() {
+            new_indices[*row] = Some(i);
+        }
+        let size = order.len();
+        let mut first_rows: Vec<usize> = (0..size).collect();
+        for (j, row) in order.iter().enumerate() {
+            for (col, value) in matrix.row(*row) {
+                if value == 0.0 {
+                    continue;
+                }
+                if let Some(i) = new_indices[col] {
+                    first_rows[j] = first_rows[j].min(i);
+                }
+            }
+        }
+        let mut offsets = Vec::with_capacity(size + 1);
+        let mut length = 0;
+        for (j, first) in first_rows.iter().enumerate() {
+            offsets.push(length);
+            length += j - first + 1;
+        }
+        offsets.push(length);
+        let mut result = Self {
+            first_rows,
+            offsets,
+            values: vec![0.0; length],
+        };
+        for (j, row) in order.iter().enumerate() {
+            for (col, value) in matrix.row(*row) {
+                // The zero values may be outside the profile
+                if value == 0.0 {
+                    continue;
+                }
+                if let Some(i) = new_indices[col] {
+                    if i <= j {
+                        let index = result.index_of(i, j);
+                        result.values[index] = value;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Gets the number of rows and columns
+    pub fn size(&self) -> usize {
+        self.first_rows.len()
+    }
+
+    /// Gets the number of the stored values (the profile of the matrix)
+    pub fn profile_size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Gets the value at the row i and the column j (i <= j). Returns zero for the values outside
+    /// the profile.
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        let (i, j) = if i <= j { (i, j) } else { (j, i) };
+        if i < self.first_rows[j] {
+            return 0.0;
+        }
+        self.values[self.index_of(i, j)]
+    }
+
+    /// The index of the value in the values vector (i >= first row of the column j and i <= j)
+    fn index_of(&self, i: usize, j: usize) -> usize {
+        self.offsets[j] + i - self.first_rows[j]
+    }
+
+    /// Factorizes the matrix into K = L D Lᵀ. The factors are stored in place of the matrix, so
+    /// the profile does not grow. Returns None if the matrix is singular.
+    pub fn factorize(mut self) -> Option<LdlFactorization> {
+        let size = self.size();
+        let original_diagonal: Vec<f64> = (0..size).map(|j| self.get(j, j)).collect();
+        for (j, original_d_j) in original_diagonal.iter().enumerate() {
+            let first_j = self.first_rows[j];
+            // g_ij = k_ij - sum(l_ri * g_rj), the values of the column j are replaced with g_ij
+            for i in first_j..j {
+                let start = self.first_rows[i].max(first_j);
+                let mut sum = 0.0;
+                for r in start..i {
+                    sum += self.values[self.index_of(r, i)] * self.values[self.index_of(r, j)];
+                }
+                let index = self.index_of(i, j);
+                self.values[index] -= sum;
+            }
+            // l_ij = g_ij / d_i and d_j = k_jj - sum(l_ij * g_ij)
+            let mut d_j = self.values[self.index_of(j, j)];
+            for i in first_j..j {
+                let index = self.index_of(i, j);
+                let g_ij = self.values[index];
+                let l_ij = g_ij / self.values[self.index_of(i, i)];
+                d_j -= l_ij * g_ij;
+                self.values[index] = l_ij;
+            }
+            if d_j.abs() <= PIVOT_TOLERANCE * original_d_j.abs() || d_j == 0.0 {
+                return None;
+            }
+            let index = self.index_of(j, j);
+            self.values[index] = d_j;
+        }
+        Some(LdlFactorization { factors: self })
+    }
+}
+
+/// The LDLᵀ factorization of a symmetric matrix. The factorization can be used to solve the
+/// system of equations with multiple right hand sides.
+#[derive(Debug, Clone)]
+pub struct LdlFactorization {
+    /// The unit lower triangular matrix L (transposed, above the diagonal) and the diagonal D
+    factors: SkylineMatrix,
+}
+
+impl LdlFactorization {
+    /// Gets the number of rows and columns of the factorized matrix
+    pub fn size(&self) -> usize {
+        self.factors.size()
+    }
+
+    /// Gets the number of the negative pivots (the negative values of D). By Sylvester's law of
+    /// inertia this is the number of the negative eigenvalues of the factorized matrix, so a
+    /// stiffness matrix with negative pivots is not positive definite (the structure is unstable).
+    pub fn negative_pivot_count(&self) -> usize {
+        let f = &self.factors;
+        (0..f.size()).filter(|j| f.values[f.index_of(*j, *j)] < 0.0).count()
+    }
+
+    /// Solves the system of equations K x = b
+    /// * `rhs` - the right hand side (b) in the order of the factorized matrix
+    pub fn solve(&self, rhs: &[f64]) -> Vec<f64> {
+        let f = &self.factors;
+        let mut x = rhs.to_vec();
+        // Forward substitution L y = b
+        for j in 0..f.size() {
+            let first_j = f.first_rows[j];
+            let sum: f64 = (first_j..j)
+                .zip(&x[first_j..j])
+                .map(|(r, x_r)| f.values[f.index_of(r, j)] * x_r)
+                .sum();
+            x[j] -= sum;
+        }
+        // D z = y
+        for (j, value) in x.iter_mut().enumerate() {
+            *value /= f.values[f.index_of(j, j)];
+        }
+        // Backward substitution Lᵀ x = z
+        for j in (0..f.size()).rev() {
+            let x_j = x[j];
+            let first_j = f.first_rows[j];
+            for (r, x_r) in (first_j..j).zip(&mut x[first_j..j]) {
+                *x_r -= f.values[f.index_of(r, j)] * x_j;
+            }
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::DMatrix;
+
+    use super::*;
+
+    #[test]
+    fn test_ldl_solve() {
+        #[rustfmt::skip]
+        let dense = DMatrix::from_row_slice(4, 4, &[
+            4.0, -1.0, 0.0, 0.0,
+            -1.0, 4.0, 0.0, -1.0,
+            0.0, 0.0, 3.0, 1.0,
+            0.0, -1.0, 1.0, 5.0,
+        ]);
+        let sparse = SparseMatrix::from_dense(&dense);
+        // The rows in a shuffled order
+        let order = vec![2, 0, 3, 1];
+        let skyline = SkylineMatrix::from_sparse(&sparse, &order);
+        assert_eq!(skyline.get(0, 2), 1.0);
+        assert_eq!(skyline.get(0, 1), 0.0);
+        let factorization = skyline.factorize().unwrap();
+        let b = vec![1.0, 2.0, 3.0, 4.0];
+        let rhs: Vec<f64> = order.iter().map(|r| b[*r]).collect();
+        let x = factorization.solve(&rhs);
+        let mut x_full = DMatrix::zeros(4, 1);
+        for (i, r) in order.iter().enumerate() {
+            x_full[(*r, 0)] = x[i];
+        }
+        let expected = dense.try_inverse().unwrap() * DMatrix::from_vec(4, 1, b);
+        assert!((x_full - expected).abs().max() < 1e-12);
+        assert_eq!(factorization.negative_pivot_count(), 0);
+    }
+
+    #[test]
+    fn test_indefinite_matrix() {
+        // The eigenvalues are 3 and -1
+        let dense = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0]);
+        let skyline = SkylineMatrix::from_sparse(&SparseMatrix::from_dense(&dense), &[0, 1]);
+        let factorization = skyline.factorize().unwrap();
+        assert_eq!(factorization.negative_pivot_count(), 1);
+        let x = factorization.solve(&[3.0, 3.0]);
+        assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_singular_matrix() {
+        let dense = DMatrix::from_row_slice(2, 2, &[1.0, -1.0, -1.0, 1.0]);
+        let skyline = SkylineMatrix::from_sparse(&SparseMatrix::from_dense(&dense), &[0, 1]);
+        assert!(skyline.factorize().is_none());
+    }
+
+    #[test]
+    fn test_stored_zeros() {
+        // The explicitly stored zero values outside the profile are ignored
+        let mut sparse = SparseMatrix::new(3);
+        sparse.set(0, 0, 2.0);
+        sparse.set(1, 1, 3.0);
+        sparse.set(2, 2, 4.0);
+        sparse.set(1, 2, 1.0);
+        sparse.set(2, 1, 1.0);
+        sparse.set(0, 2, 0.0);
+        sparse.set(2, 0, 0.0);
+        let skyline = SkylineMatrix::from_sparse(&sparse, &[0, 1, 2]);
+        assert_eq!(skyline.profile_size(), 4);
+        assert_eq!(skyline.get(0, 0), 2.0);
+        assert_eq!(skyline.get(1, 1), 3.0);
+        assert_eq!(skyline.get(1, 2), 1.0);
+    }
+}
@@ -0,0 +1,228 @@
+//! The sparse storage of the global matrices and the renumbering of the nodes to reduce the
+//! bandwidth of the matrices.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ops::{Index, Mul};
+
+use nalgebra::DMatrix;
+
+/// The value returned by the index operator for the entries that are not stored
+const ZERO: f64 = 0.0;
+
+/// A square sparse matrix where only the non-zero entries are stored. The rows are stored as maps
+/// of column indices and values, so the matrix can be assembled in any order. Both triangles of
+/// the symmetric matrices (e.g. the stiffness matrix) are stored.
+#[derive(Debug, Clone, Default)]
+pub struct SparseMatrix {
+    size: usize,
+    rows: Vec<BTreeMap<usize, f64>>,
+}
+
+impl SparseMatrix {
+    /// Creates a new empty (zero) square matrix
+    /// * `size` - the number of rows and columns
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            rows: vec![BTreeMap::new(); size],
+        }
+    }
+
+    /// Creates a sparse matrix from the dense matrix. The zero entries are not stored.
+    pub fn from_dense(matrix: &DMatrix<f64>) -> Self {
+        let mut result = Self::new(matrix.nrows());
+        for row in 0..matrix.nrows() {
+            for col in 0..matrix.ncols() {
+                if matrix[(row, col)] != 0.0 {
+                    result.set(row, col, matrix[(row, col)]);
+                }
+            }
+        }
+        result
+    }
+
+    /// Gets the number of rows
+    pub fn nrows(&self) -> usize {
+        self.size
+    }
+
+    /// Gets the number of columns
+    pub fn ncols(&self) -> usize {
+        self.size
+    }
+
+    /// Gets the value at given row and column. Returns zero for the entries that are not stored.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        *self.rows[row].get(&col).unwrap_or(&ZERO)
+    }
+
+    /// Sets the value at given row and column
+    pub fn set(&mut self, row: usize, col: usize, value: f64) {
+        self.rows[row].insert(col, value);
+    }
+
+    /// Adds the value to the entry at given row and column
+    pub fn add(&mut self, row: usize, col: usize, value: f64) {
+        *self.rows[row].entry(col).or_insert(0.0) += value;
+    }
+
+    /// Multiplies all the entries by the factor
+    pub fn scale(&mut self, factor: f64) {
+        for value in self.rows.iter_mut().flat_map(|r| r.values_mut()) {
+            *value *= factor;
+        }
+    }
+
+    /// Gets the stored entries (column index, value) of the row in the order of the columns
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        self.rows[row].iter().map(|(c, v)| (*c, *v))
+    }
+
+    /// Gets the number of the stored entries
+    pub fn stored_count(&self) -> usize {
+        self.rows.iter().map(|r| r.len()).sum()
+    }
+
+    /// Converts the matrix into a dense matrix
+    pub fn to_dense(&self) -> DMatrix<f64> {
+        let mut result = DMatrix::zeros(self.size, self.size);
+        for (row, entries) in self.rows.iter().enumerate() {
+            for (col, value) in entries {
+                result[(row, *col)] = *value;
+            }
+        }
+        result
+    }
+
+    /// Gets the adjacency lists of the blocks of the matrix. The rows and columns are grouped into
+    /// blocks of given size (e.g. the degrees of freedom of a node) and two blocks are adjacent if
+    /// there is a non-zero entry between them.
+    pub fn get_block_adjacency(&self, block_size: usize) -> Vec<Vec<usize>> {
+        let block_count = self.size.div_ceil(block_size);
+        let mut adjacency: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); block_count];
+        for (row, entries) in self.rows.iter().enumerate() {
+            for (col, value) in entries {
+                let (a, b) = (row / block_size, col / block_size);
+                if a != b && *value != 0.0 {
+                    adjacency[a].insert(b);
+                    adjacency[b].insert(a);
+                }
+            }
+        }
+        adjacency.into_iter().map(|s| s.into_iter().collect()).collect()
+    }
+}
+
+impl Index<(usize, usize)> for SparseMatrix {
+    type Output = f64;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.rows[row].get(&col).unwrap_or(&ZERO)
+    }
+}
+
+impl Mul<&DMatrix<f64>> for &SparseMatrix {
+    type Output = DMatrix<f64>;
+
+    fn mul(self, rhs: &DMatrix<f64>) -> Self::Output {
+        let mut result = DMatrix::zeros(self.size, rhs.ncols());
+        for (row, entries) in self.rows.iter().enumerate() {
+            for (col, value) in entries {
+                for k in 0..rhs.ncols() {
+                    result[(row, k)] += value * rhs[(*col, k)];
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Gets the reverse Cuthill-McKee ordering for the graph given as adjacency lists. The ordering
+/// reduces the bandwidth and the profile of the matrix of the graph. Each connected part of the
+/// graph is started from a node with the smallest degree.
+///
+/// Returns the indices of the graph nodes in the new order (new index -> old index).
+pub fn reverse_cuthill_mckee(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let count = adjacency.len();
+    let degree = |i: usize| adjacency[i].len();
+    let mut visited = vec![false; count];
+    let mut order = Vec::with_capacity(count);
+    // The start nodes in the order of their degrees
+    let mut start_nodes: Vec<usize> = (0..count).collect();
+    start_nodes.sort_by_key(|i| degree(*i));
+    for start in start_nodes {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            let mut neighbours: Vec<usize> =
+                adjacency[node].iter().copied().filter(|n| !visited[*n]).collect();
+            neighbours.sort_by_key(|n| degree(*n));
+            for n in neighbours {
+                visited[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+    order.reverse();
+    order
+}
+
+/// Gets the unknown rows of the matrix in the order that reduces the bandwidth of the matrix. The
+/// rows are grouped into blocks of given size (the degrees of freedom of a node) and the blocks
+/// are renumbered with the reverse Cuthill-McKee ordering.
+/// * `matrix` - the (stiffness) matrix
+/// * `unknown_rows` - the rows that are included in the result
+/// * `block_size` - the size of the row blocks (the degrees of freedom of a node)
+pub fn get_renumbered_rows(matrix: &SparseMatrix, unknown_rows: &[usize], block_size: usize) -> Vec<usize> {
+    let mut is_unknown = vec![false; matrix.nrows()];
+    for r in unknown_rows {
+        is_unknown[*r] = true;
+    }
+    let mut result = Vec::with_capacity(unknown_rows.len());
+    for block in reverse_cuthill_mckee(&matrix.get_block_adjacency(block_size)) {
+        let rows = block * block_size..((block + 1) * block_size).min(matrix.nrows());
+        result.extend(rows.filter(|row| is_unknown[*row]));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_matrix() {
+        let mut matrix = SparseMatrix::new(3);
+        matrix.add(0, 0, 2.0);
+        matrix.add(0, 0, 3.0);
+        matrix.set(1, 2, -1.0);
+        assert_eq!(matrix[(0, 0)], 5.0);
+        assert_eq!(matrix.get(1, 2), -1.0);
+        assert_eq!(matrix[(2, 1)], 0.0);
+        assert_eq!(matrix.stored_count(), 2);
+        let vector = DMatrix::from_vec(3, 1, vec![1.0, 2.0, 3.0]);
+        let result = &matrix * &vector;
+        assert_eq!(result, DMatrix::from_vec(3, 1, vec![5.0, -3.0, 0.0]));
+        assert_eq!(SparseMatrix::from_dense(&matrix.to_dense()).to_dense(), matrix.to_dense());
+        matrix.scale(-2.0);
+        assert_eq!(matrix[(0, 0)], -10.0);
+        assert_eq!(matrix[(1, 2)], 2.0);
+    }
+
+    #[test]
+    fn test_reverse_cuthill_mckee() {
+        // A chain numbered in a bad order: 0 - 4 - 1 - 3 - 2 and a separate node 5
+        let adjacency = vec![vec![4], vec![3, 4], vec![3], vec![1, 2], vec![0, 1], vec![]];
+        let order = reverse_cuthill_mckee(&adjacency);
+        assert_eq!(order.len(), 6);
+        // The neighbours of the chain are next to each other in the new order
+        let chain: Vec<usize> = order.iter().copied().filter(|n| *n != 5).collect();
+        for pair in chain.windows(2) {
+            assert!(adjacency[pair[0]].contains(&pair[1]));
+        }
+    }
+}
@@ -1,13 +1,15 @@
 ﻿#![allow(non_snake_case)]
 
-use crate::fem::matrices::get_rotation_matrix;
-use crate::material::MaterialData;
+use crate::fem::matrices::{apply_rigid_offsets, get_rotation_matrix};
 use crate::settings::CalculationSettings;
 use crate::structure::CalculationElement;
 use nalgebra::DMatrix;
+use super::sparse::SparseMatrix;
 use super::CalcModel;
 
-/// Gets the elements stiffness matrix in the global coordinate system.
+/// Gets the elements stiffness matrix in the global coordinate system. The matrix is transformed
+/// to the nodes through the rigid end offsets of the element (see
+/// [crate::fem::matrices::get_rigid_offset_matrix]).
 pub fn get_element_global_stiffness_matrix(
     e: &CalculationElement,
     settings: &CalculationSettings
@@ -16,7 +18,74 @@ pub fn get_element_global_stiffness_matrix(
     let e_rotation_matrix = get_rotation_matrix(e.rotation);
     let e_rot_matrix_t = e_rotation_matrix.transpose();
     let e_glob_stiff_matrix = e_rot_matrix_t * e_stiff_matrix * e_rotation_matrix;
-    e_glob_stiff_matrix
+    apply_rigid_offsets(e, e_glob_stiff_matrix)
+}
+
+/// Gets the elements geometric stiffness matrix in the global coordinate system. See
+/// [get_element_geometric_stiffness_matrix].
+pub fn get_element_global_geometric_stiffness_matrix(e: &CalculationElement) -> DMatrix<f64> {
+    let e_geom_stiff_matrix = get_element_geometric_stiffness_matrix(e);
+    let e_rotation_matrix = get_rotation_matrix(e.rotation);
+    apply_rigid_offsets(e, e_rotation_matrix.transpose() * e_geom_stiff_matrix * e_rotation_matrix)
+}
+
+/// Gets the geometric stiffness matrix (P-Δ) of the element in elements local coordinate system.
+/// The matrix is calculated from the axial force of the element (tension is positive), so a
+/// compressed element has a negative geometric stiffness. Only the translations perpendicular to
+/// the element are affected, so the releases of the rotations do not change the matrix.
+pub fn get_element_geometric_stiffness_matrix(element: &CalculationElement) -> DMatrix<f64> {
+    let k_g = element.axial_force / element.length;
+    DMatrix::from_row_slice(
+        6,
+        6,
+        &[
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, k_g, 0.0, 0.0, -k_g, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, -k_g, 0.0, 0.0, k_g, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ],
+    )
+}
+
+/// Gets the stiffness matrix of the elastic foundation of the element in the global coordinate
+/// system. See [get_element_foundation_stiffness_matrix].
+pub fn get_element_global_foundation_stiffness_matrix(e: &CalculationElement) -> DMatrix<f64> {
+    let e_found_matrix = get_element_foundation_stiffness_matrix(e);
+    let e_rotation_matrix = get_rotation_matrix(e.rotation);
+    apply_rigid_offsets(e, e_rotation_matrix.transpose() * e_found_matrix * e_rotation_matrix)
+}
+
+/// Gets the stiffness matrix of the elastic (Winkler) foundation of the element in elements local
+/// coordinate system. The matrix is consistent with the displacement shape functions of the beam
+/// (it has the same form as the consistent mass matrix). The rows and columns of the released
+/// degrees of freedom are zero, so the foundation is connected only to the node displacements that
+/// the element follows.
+pub fn get_element_foundation_stiffness_matrix(element: &CalculationElement) -> DMatrix<f64> {
+    let L = element.length;
+    let (k_z, k_x) = element.foundation.get_stiffness();
+    let a = k_x * L / 6.0;
+    let b = k_z * L / 420.0;
+    let mut found_matrix = DMatrix::from_row_slice(
+        6,
+        6,
+        &[
+            2.0 * a, 0.0, 0.0, a, 0.0, 0.0,
+            0.0, 156.0 * b, 22.0 * L * b, 0.0, 54.0 * b, -13.0 * L * b,
+            0.0, 22.0 * L * b, 4.0 * L * L * b, 0.0, 13.0 * L * b, -3.0 * L * L * b,
+            a, 0.0, 0.0, 2.0 * a, 0.0, 0.0,
+            0.0, 54.0 * b, 13.0 * L * b, 0.0, 156.0 * b, -22.0 * L * b,
+            0.0, -13.0 * L * b, -3.0 * L * L * b, 0.0, -22.0 * L * b, 4.0 * L * L * b,
+        ],
+    );
+    for i in 0..6 {
+        if element.releases.get_release_value(i).unwrap() {
+            found_matrix.row_mut(i).fill(0.0);
+            found_matrix.column_mut(i).fill(0.0);
+        }
+    }
+    found_matrix
 }
 
 /// Gets the stiffness matrix of the element in elements local coordinate system.
@@ -24,59 +93,29 @@ pub fn get_element_global_stiffness_matrix(
 pub fn get_element_stiffness_matrix(element: &CalculationElement, 
     settings: &CalculationSettings, ignore_releases: bool
 ) -> DMatrix<f64> {
-    let E = match &element.material {
-        MaterialData::Concrete(c) => c.elastic_modulus,
-        MaterialData::Steel(s) => s.elastic_modulus,
-        MaterialData::Timber(_) => {
-            println!("Timber is not yet implemented!");
-            0.0
-        }
-    };
+    let E = element.elastic_modulus;
     let L = element.length;
-    let A = element.profile.get_area(&element.material, settings);
-    let I = element.profile.get_major_second_mom_of_area(&element.material, settings);
+    let A = element.profile_area;
+    let I = element.major_smoa;
     let EA = E * A;
     let EI = E * I;
+    // The shear deformation factor Φ = 12EI / (G*As*L²) for Timoshenko beams (zero for Euler-Bernoulli beams)
+    let GAs = element.get_shear_stiffness(settings);
+    let phi = if GAs > 0.0 { 12.0 * EI / (GAs * L.powi(2)) } else { 0.0 };
+    let k_v = 12.0 * EI / ((1.0 + phi) * L.powi(3));
+    let k_vr = 6.0 * EI / ((1.0 + phi) * L.powi(2));
+    let k_r = (4.0 + phi) * EI / ((1.0 + phi) * L);
+    let k_r2 = (2.0 - phi) * EI / ((1.0 + phi) * L);
     let mut stiff_matrix = DMatrix::from_row_slice(
         6,
         6,
         &[
-            EA / L,
-            0.,
-            0.,
-            -EA / L,
-            0.,
-            0.,
-            0.0,
-            12.0 * EI / L.powi(3),
-            6.0 * EI / L.powi(2),
-            0.0,
-            -12.0 * EI / L.powi(3),
-            6.0 * EI / L.powi(2),
-            0.0,
-            6.0 * EI / L.powi(2),
-            4.0 * EI / L,
-            0.0,
-            -6.0 * EI / L.powi(2),
-            2.0 * EI / L,
-            -EA / L,
-            0.0,
-            0.0,
-            EA / L,
-            0.0,
-            0.0,
-            0.0,
-            -12.0 * EI / L.powi(3),
-            -6.0 * EI / L.powi(2),
-            0.0,
-            12.0 * EI / L.powi(3),
-            -6.0 * EI / L.powi(2),
-            0.0,
-            6.0 * EI / L.powi(2),
-            2.0 * EI / L,
-            0.0,
-            -6.0 * EI / L.powi(2),
-            4.0 * EI / L,
+            EA / L, 0.0, 0.0, -EA / L, 0.0, 0.0,
+            0.0, k_v, k_vr, 0.0, -k_v, k_vr,
+            0.0, k_vr, k_r, 0.0, -k_vr, k_r2,
+            -EA / L, 0.0, 0.0, EA / L, 0.0, 0.0,
+            0.0, -k_v, -k_vr, 0.0, k_v, -k_vr,
+            0.0, k_vr, k_r2, 0.0, -k_vr, k_r,
         ],
     );
     if !ignore_releases {
@@ -104,6 +143,15 @@ pub fn get_element_stiffness_matrix(element: &CalculationElement,
 /// - K<sub>pf</sub> would be K\[5, 0..4] (row 6 and columns 1 to 5)
 /// - K<sub>pp</sub> would be K\[0..4, 0..4] (all cells but rows and columns 6)
 /// - K<sub>ff</sub> would be the cells of K\[5,5] (row 6, column 6)
+///
+/// The released directions can have springs S between the element end and the node (semi-rigid
+/// releases). The springs are included in K<sub>ff</sub> (see [get_stiffness_matrix_release_parts])
+/// and the released rows and columns get the stiffness of the springs:
+/// - K<sub>pf</sub>\*K<sub>ff</sub><sup>-1</sup>\*S in the released columns (and its transpose in the
+///   released rows)
+/// - S - S\*K<sub>ff</sub><sup>-1</sup>\*S in the intersections of the released rows and columns
+///
+/// Without the springs the released rows and columns are zero.
 fn handle_releases(elem: &CalculationElement, stiff_matrix: &DMatrix<f64>) -> DMatrix<f64> {
     let dof = 3;
     let release_count = elem.releases.start_release_count() + elem.releases.end_release_count();
@@ -113,8 +161,6 @@ fn handle_releases(elem: &CalculationElement, stiff_matrix: &DMatrix<f64>) -> DM
 
     let parts = get_stiffness_matrix_release_parts(elem, release_count, stiff_matrix);
 
-    // println!("Kff: {}", released);
-
     let kff_inversed = match parts.kff.try_inverse() {
         Some(m) => m,
         None => {
@@ -122,49 +168,87 @@ fn handle_releases(elem: &CalculationElement, stiff_matrix: &DMatrix<f64>) -> DM
         }
     };
 
-    let kpf_m_kff_inv = parts.kpf * kff_inversed;
+    let kpf_m_kff_inv = &parts.kpf * &kff_inversed;
 
-    let subtraction = kpf_m_kff_inv * parts.kfp;
-    let subtracted = parts.kpp - subtraction;
-
-    // println!("Subtracted: {}", subtracted);
+    let subtraction = &kpf_m_kff_inv * &parts.kfp;
+    let subtracted = &parts.kpp - subtraction;
+    // The forces of the release springs (zero without the springs)
+    let springs_pf = &kpf_m_kff_inv * &parts.springs;
+    let springs_ff = &parts.springs - &parts.springs * &kff_inversed * &parts.springs;
 
     let mut result: DMatrix<f64> = DMatrix::zeros(dof*2, dof*2);
 
-    let mut result_row_cur = 0;
-    let mut result_col_cur = 0;
-    let mut increm_result_row_cur = false;
-    for i in 0..dof*2 {
-        for j in 0..dof*2 {
-            let rel_row = elem.releases.get_release_value(i).unwrap();
-            let rel_col = elem.releases.get_release_value(j).unwrap();
-            if rel_row || rel_col {
-                // Move the intersected release values into Kff
-                result[(i, j)] = 0.0;
-            } else {
-                result[(i, j)] = subtracted[(result_row_cur, result_col_cur)];
-                result_col_cur += 1;
-                increm_result_row_cur = true;
-            }
+    let (preserved, released) = get_release_indices(elem);
+    for (row, i) in preserved.iter().enumerate() {
+        for (col, j) in preserved.iter().enumerate() {
+            result[(*i, *j)] = subtracted[(row, col)];
         }
-        if increm_result_row_cur {
-            result_row_cur += 1;
-            result_col_cur = 0;
-            increm_result_row_cur = false;
+        for (col, j) in released.iter().enumerate() {
+            result[(*i, *j)] = springs_pf[(row, col)];
+            result[(*j, *i)] = springs_pf[(row, col)];
+        }
+    }
+    for (row, i) in released.iter().enumerate() {
+        for (col, j) in released.iter().enumerate() {
+            result[(*i, *j)] = springs_ff[(row, col)];
         }
     }
 
     result
 }
 
+/// Gets the displacements of the element ends in elements local coordinate system. The
+/// displacements of the released directions are solved from the equilibrium of the element end
+/// ((K<sub>ff</sub> + S) * u<sub>f</sub> = S * d<sub>f</sub> - K<sub>fp</sub> * d<sub>p</sub> + F<sub>f</sub>,
+/// where d are the displacements of the nodes and F the fixed end loads). The other displacements
+/// are the displacements of the nodes.
+/// * `local_displacements` - the local displacements of the nodes (6x1)
+/// * `local_eq_loads` - the local equivalent loads of the element without the releases (6x1)
+pub fn get_element_end_displacements(
+    element: &CalculationElement,
+    settings: &CalculationSettings,
+    local_displacements: &DMatrix<f64>,
+    local_eq_loads: &DMatrix<f64>,
+) -> DMatrix<f64> {
+    let release_count = element.releases.start_release_count() + element.releases.end_release_count();
+    if release_count == 0 {
+        return local_displacements.clone();
+    }
+    let stiff_matrix = get_element_stiffness_matrix(element, settings, true);
+    let parts = get_stiffness_matrix_release_parts(element, release_count, &stiff_matrix);
+    let Some(kff_inversed) = parts.kff.try_inverse() else {
+        return local_displacements.clone();
+    };
+    let (preserved, released) = get_release_indices(element);
+    let d_p = DMatrix::from_fn(preserved.len(), 1, |r, _| local_displacements[preserved[r]]);
+    let d_f = DMatrix::from_fn(released.len(), 1, |r, _| local_displacements[released[r]]);
+    let f_f = DMatrix::from_fn(released.len(), 1, |r, _| local_eq_loads[released[r]]);
+    let u_f = kff_inversed * (&parts.springs * d_f - &parts.kfp * d_p + f_f);
+
+    let mut end_displacements = local_displacements.clone();
+    for (k, i) in released.iter().enumerate() {
+        end_displacements[*i] = u_f[k];
+    }
+    end_displacements
+}
+
+/// Gets the indices (0..=5) of the preserved and the released degrees of freedom of the element
+pub fn get_release_indices(elem: &CalculationElement) -> (Vec<usize>, Vec<usize>) {
+    (0..6).partition(|i| !elem.releases.get_release_value(*i).unwrap())
+}
+
 pub struct StiffnessMatrixParts {
     pub kpp: DMatrix<f64>,
     pub kpf: DMatrix<f64>,
     pub kfp: DMatrix<f64>,
+    /// The released cells with the release springs (K<sub>ff</sub> + S)
     pub kff: DMatrix<f64>,
+    /// The diagonal matrix of the release springs (S) of the released directions
+    pub springs: DMatrix<f64>,
 }
 
-/// Gets the Kpf, Kfp, Kff and Kpp matrices from the element stiffness matrix
+/// Gets the Kpf, Kfp, Kff and Kpp matrices from the element stiffness matrix. The stiffnesses of
+/// the release springs are added to the diagonal of Kff.
 pub fn get_stiffness_matrix_release_parts(
     elem: &CalculationElement,
     rel_count: usize,
@@ -235,79 +319,94 @@ pub fn get_stiffness_matrix_release_parts(
         }
     }
 
+    // The release springs (semi-rigid releases) between the element ends and the nodes
+    let mut springs: DMatrix<f64> = DMatrix::zeros(rel_count, rel_count);
+    for (k, i) in get_release_indices(elem).1.iter().enumerate() {
+        springs[(k, k)] = elem.releases.get_spring_stiffness(*i);
+    }
+    released += &springs;
+
     StiffnessMatrixParts {
         kpp: preserved,
         kpf: modifiers_cols,
         kfp: modifiers_rows,
         kff: released,
+        springs,
     }
 }
 
+/// Creates the global stiffness matrix of the calculation model. The matrix is assembled into a
+/// sparse matrix, because most of the entries are zero (each node is connected only to the nodes
+/// of its elements).
 pub(super) fn create_joined_stiffness_matrix(
     calc_model: &CalcModel,
     settings: &CalculationSettings
-) -> DMatrix<f64> {
-    let supp_count = calc_model.structure_nodes.len() + calc_model.extra_nodes.len();    
-    // Increase the joined stiffness matrix size by release count. Releases are set into their
-    // own rows and columns at the end of the joined matrix
-    // let release_count = crate::structure::utils::get_element_release_count(&calc_model.structure_elements);
+) -> SparseMatrix {
+    let supp_count = calc_model.structure_nodes.len() + calc_model.extra_nodes.len();
     // The degrees of freedom count of single node (tx, tz, ry)
     let dof = 3;
     let row_width = supp_count * dof;
 
-    let mut matrix_vector = vec![0.0; row_width * row_width];
+    let mut matrix = SparseMatrix::new(row_width);
 
-    let mut supp_index1: usize;
-    let mut supp_index2: usize;
-    let mut i_normalized: usize;
-    let mut j_normalized: usize;
+    for elem in calc_model.get_all_calc_elements() {
+        if calc_model.inactive_elements.contains(&elem.model_el_num) {
+            continue;
+        }
+        let mut e_glob_stiff_matrix = get_element_global_stiffness_matrix(&elem, settings);
+        // The geometric stiffness of the second-order analysis
+        if elem.axial_force != 0.0 {
+            e_glob_stiff_matrix += get_element_global_geometric_stiffness_matrix(elem);
+        }
+        if elem.foundation.is_active() {
+            e_glob_stiff_matrix += get_element_global_foundation_stiffness_matrix(elem);
+        }
+        add_element_matrix(&mut matrix, elem, &e_glob_stiff_matrix);
+    }
+
+    matrix
+}
+
+/// Creates the joined geometric stiffness matrix of the calculation elements from the axial
+/// forces of the elements (see [get_element_geometric_stiffness_matrix])
+pub(super) fn create_joined_geometric_stiffness_matrix(calc_model: &CalcModel) -> SparseMatrix {
+    let supp_count = calc_model.structure_nodes.len() + calc_model.extra_nodes.len();
+    let mut matrix = SparseMatrix::new(supp_count * 3);
 
     for elem in calc_model.get_all_calc_elements() {
-        let e_glob_stiff_matrix = get_element_global_stiffness_matrix(&elem, settings);
-        // The index of the start node
-        let s = (elem.node_start - 1) as usize;
-        // The index of the end node
-        let e = (elem.node_end - 1) as usize;
-        for i in 0..dof * 2 {
-            for j in 0..dof * 2 {
-                if i < dof {
-                    supp_index1 = s;
-                    i_normalized = i;
-                    if j < dof {
-                        // the top left triple (start element, start node)
-                        supp_index2 = s;
-                        j_normalized = j;
-                    } else {
-                        // The bottom left triple (start element, end node)
-                        supp_index2 = e;
-                        j_normalized = j - dof;
-                    }
-                } else {
-                    supp_index1 = e;
-                    i_normalized = i - dof;
-                    if j < dof {
-                        // the top right triple (end element, start node)
-                        supp_index2 = s;
-                        j_normalized = j;
-                    } else {
-                        // the top right triple (end element, end node)
-                        supp_index2 = e;
-                        j_normalized = j - dof;
-                    }
-                }
-                // (supp_index1 * dof) * row_width       offset the rows by the support node number
-                // supp_index2 * dof                     offset the columns by the support number
-                // j_normalized                          offset the columns by j
-                // i_normalized * row_width              offset the rows by i
-                matrix_vector[(supp_index1 * dof) * row_width
-                    + i_normalized * row_width
-                    + supp_index2 * dof
-                    + j_normalized] += e_glob_stiff_matrix[(i, j)];
-            }
+        if elem.axial_force != 0.0 && !calc_model.inactive_elements.contains(&elem.model_el_num) {
+            let e_glob_geom_matrix = get_element_global_geometric_stiffness_matrix(elem);
+            add_element_matrix(&mut matrix, elem, &e_glob_geom_matrix);
         }
     }
 
-    DMatrix::from_vec(row_width, row_width, matrix_vector)
+    matrix
+}
+
+/// Adds the global element matrix (6x6) into the joined matrix at the rows and columns of the
+/// element nodes
+pub(super) fn add_element_matrix(
+    matrix: &mut SparseMatrix,
+    elem: &CalculationElement,
+    e_glob_matrix: &DMatrix<f64>,
+) {
+    // The degrees of freedom count of single node (tx, tz, ry)
+    let dof = 3;
+    // The index of the start node
+    let s = (elem.node_start - 1) as usize;
+    // The index of the end node
+    let e = (elem.node_end - 1) as usize;
+    for i in 0..dof * 2 {
+        // The first half of the element matrix rows belongs to the start node and the second
+        // half to the end node (same for the columns)
+        let row = if i < dof { s * dof + i } else { e * dof + i - dof };
+        for j in 0..dof * 2 {
+            let col = if j < dof { s * dof + j } else { e * dof + j - dof };
+            if e_glob_matrix[(i, j)] != 0.0 {
+                matrix.add(row, col, e_glob_matrix[(i, j)]);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -316,7 +415,9 @@ mod tests {
     use crate::material::{MaterialData, Steel};
     use crate::profile::Profile;
     use crate::settings::CalculationSettings;
-    use crate::structure::{CalculationElement, Element};
+    use crate::structure::{CalculationElement, Element, Node};
+    use std::collections::BTreeMap;
+    use vputilslib::geometry2d::VpPoint;
 
     #[test]
     fn test_release_handling() {
@@ -335,10 +436,42 @@ mod tests {
             profile_area: 10000.0,
             elastic_modulus: 210000.0,
             major_smoa: 8333333.0,
+            shear_modulus: 81000.0,
+            shear_area: 10000.0 * 5.0 / 6.0,
             offset_from_model_el: 0.0,
+            axial_force: 0.0,
+            foundation: Default::default(),
+            rigid_offset_start: (0.0, 0.0),
+            rigid_offset_end: (0.0, 0.0),
         };
         calc_elem.releases.e_tx = true;
         calc_elem.releases.e_ry = true;
         &get_element_stiffness_matrix(&calc_elem, &CalculationSettings::default(), false);
     }
+
+    #[test]
+    fn test_release_springs() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let settings = CalculationSettings::default();
+        let mut elem = Element::new(1, 1, 2, Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0), MaterialData::Steel(Steel::new(210e3)));
+        elem.releases.e_ry = true;
+        elem.releases.e_r_spring = 500.0;
+        let calc_elem = CalculationElement::from(&elem, &nodes, 1, &settings);
+        let stiff_matrix = get_element_stiffness_matrix(&calc_elem, &settings, false);
+        // The rotational stiffness of the end is the spring and the element (4EI/L) in series
+        let k_r = 4.0 * calc_elem.elastic_modulus * calc_elem.major_smoa / 4000.0;
+        let k_s = 500.0 * 1e6;
+        assert!((stiff_matrix[(5, 5)] / (k_r * k_s / (k_r + k_s)) - 1.0).abs() < 1e-9);
+
+        // A very stiff spring is the same as no release
+        elem.releases.e_r_spring = 1e10;
+        let calc_elem = CalculationElement::from(&elem, &nodes, 1, &settings);
+        let stiff_matrix = get_element_stiffness_matrix(&calc_elem, &settings, false);
+        let fixed_matrix = get_element_stiffness_matrix(&calc_elem, &settings, true);
+        let tolerance = fixed_matrix.amax() * 1e-6;
+        assert!((stiff_matrix - fixed_matrix).amax() < tolerance);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,204 @@
+//! The superposition of the load cases. Each distinct load name is solved once with the factor 1.0
+//! and the results of the load combinations are the factored sums of the load case results. This
+//! is valid only for the linear analysis.
+
+use std::collections::BTreeMap;
+
+use vputilslib::equation_handler::EquationHandler;
+
+use super::CalcModel;
+use crate::loads::load::{CalculationLoad, PrescribedDisplacement};
+use crate::loads::{self, CalcLoadCombination, Load};
+use crate::results::{InternalForcePoint, InternalForceResults, NodeResults};
+use crate::structure::Node;
+
+/// A load case that is solved once and combined into the load combinations. The loads with the
+/// same name are in the same load case, except the moving loads, which are split into separate
+/// load cases by the model elements so that the moving load patterns can be combined.
+#[derive(Debug, Clone)]
+pub struct LoadCase {
+    /// The name of the loads in the load case
+    pub name: String,
+    /// The model element number for the load case of a moving load. None for the other loads.
+    pub moving_load_element: Option<i32>,
+    /// The percentage of the moving load that is set on the elements that are not in the moving
+    /// load pattern
+    pub moving_percent: f64,
+    /// The calculation loads of the load case (with the factor 1.0)
+    pub loads: Vec<CalculationLoad>,
+    /// The prescribed displacements of the supports of the load case (with the factor 1.0)
+    pub prescribed_displacements: Vec<PrescribedDisplacement>,
+}
+
+impl LoadCase {
+    /// Gets the factor of the load case in the load combination. Returns zero if the loads of the
+    /// load case are not included in the load combination.
+    pub fn get_factor(&self, lc: &CalcLoadCombination) -> f64 {
+        let Some(factor) = loads::lc_utils::get_load_factor(lc, &self.name) else {
+            return 0.0;
+        };
+        match (&lc.moving_load_elements, self.moving_load_element) {
+            (Some(pattern), Some(element)) if !pattern.contains(&element) => {
+                factor * self.moving_percent / 100.0
+            }
+            _ => factor,
+        }
+    }
+}
+
+/// Gets the load cases for the loads. The load cases are in the order of the first loads with
+/// each name.
+pub fn get_load_cases(
+    calc_model: &CalcModel,
+    loads: &Vec<Load>,
+    eq_handler: &EquationHandler,
+) -> Vec<LoadCase> {
+    // The model element numbers of the calculation elements
+    let model_elements: BTreeMap<i32, i32> = calc_model
+        .get_all_calc_elements()
+        .iter()
+        .map(|e| (e.calc_el_num, e.model_el_num))
+        .collect();
+    let mut names: Vec<&String> = Vec::new();
+    for load in loads {
+        if !names.contains(&&load.name) {
+            names.push(&load.name);
+        }
+    }
+    let mut result = Vec::new();
+    for name in names {
+        let mut lc = CalcLoadCombination::default();
+        lc.loads_n_factors.insert(name.clone(), 1.0);
+        let (moving_loads, other_loads): (Vec<Load>, Vec<Load>) = loads
+            .iter()
+            .filter(|l| &l.name == name)
+            .cloned()
+            .partition(|l| l.is_moving_load && !l.is_support_displacement());
+        if !other_loads.is_empty() {
+            result.push(LoadCase {
+                name: name.clone(),
+                moving_load_element: None,
+                moving_percent: 100.0,
+                loads: loads::utils::extract_calculation_loads(calc_model, &other_loads, &lc, eq_handler),
+                prescribed_displacements: loads::utils::extract_prescribed_displacements(
+                    calc_model,
+                    &other_loads,
+                    &lc,
+                    eq_handler,
+                ),
+            });
+        }
+        for moving_load in moving_loads {
+            let moving_percent = moving_load.moving_percent;
+            let calc_loads =
+                loads::utils::extract_calculation_loads(calc_model, &vec![moving_load], &lc, eq_handler);
+            // Split the loads by the model elements
+            let mut element_loads: BTreeMap<i32, Vec<CalculationLoad>> = BTreeMap::new();
+            for calc_load in calc_loads {
+                let Some(model_element) = model_elements.get(&calc_load.element_number) else {
+                    println!(
+                        "Calculation element {} of the load '{}' not found, the load is skipped",
+                        calc_load.element_number, calc_load.name
+                    );
+                    continue;
+                };
+                element_loads.entry(*model_element).or_default().push(calc_load);
+            }
+            for (model_element, loads) in element_loads {
+                result.push(LoadCase {
+                    name: name.clone(),
+                    moving_load_element: Some(model_element),
+                    moving_percent,
+                    loads,
+                    prescribed_displacements: vec![],
+                });
+            }
+        }
+    }
+    result
+}
+
+/// Combines the node results of the load cases with the factors
+/// * `results` - the node results of the load cases
+/// * `factors` - the factors of the load cases (in the same order as the results)
+/// * `equation_handler` - the equation handler copied to the combined results
+/// * `nodes` - the structure nodes
+pub fn combine_node_results(
+    results: &[NodeResults],
+    factors: &[f64],
+    equation_handler: &EquationHandler,
+    nodes: &BTreeMap<i32, Node>,
+) -> NodeResults {
+    let col_height = results.first().map_or(0, |r| r.displacements.len());
+    let mut displacements = vec![0.0; col_height];
+    let mut support_reactions = vec![0.0; col_height];
+    let mut constraint_forces = vec![0.0; results.first().map_or(0, |r| r.constraint_forces.len())];
+    for (result, factor) in results.iter().zip(factors) {
+        if *factor == 0.0 {
+            continue;
+        }
+        for (d, value) in displacements.iter_mut().zip(&result.displacements) {
+            *d += factor * value;
+        }
+        for (r, value) in support_reactions.iter_mut().zip(&result.support_reactions) {
+            *r += factor * value;
+        }
+        for (c, value) in constraint_forces.iter_mut().zip(&result.constraint_forces) {
+            *c += factor * value;
+        }
+    }
+    let mut result = NodeResults::new(displacements, support_reactions, nodes.len(), equation_handler, nodes);
+    result.constraint_forces = constraint_forces;
+    result
+}
+
+/// Combines the internal forces of the load cases with the factors. The internal forces of all
+/// the load cases are calculated at the same positions (the positions depend only on the
+/// calculation model), so the points are combined by their indices.
+/// * `results` - the internal force results of the load cases
+/// * `factors` - the factors of the load cases (in the same order as the results)
+pub fn combine_internal_forces(
+    results: &[BTreeMap<i32, InternalForceResults>],
+    factors: &[f64],
+) -> BTreeMap<i32, InternalForceResults> {
+    let mut combined = BTreeMap::new();
+    let Some(first) = results.first() else {
+        return combined;
+    };
+    for element_number in first.keys() {
+        let element_results: Vec<&InternalForceResults> =
+            results.iter().map(|r| &r[element_number]).collect();
+        let combine = |points: fn(&InternalForceResults) -> &Vec<InternalForcePoint>| {
+            let mut combined_points = points(element_results[0]).clone();
+            for p in combined_points.iter_mut() {
+                p.value_x = 0.0;
+                p.value_y = 0.0;
+            }
+            for (result, factor) in element_results.iter().zip(factors) {
+                if *factor == 0.0 {
+                    continue;
+                }
+                for (c, p) in combined_points.iter_mut().zip(points(result)) {
+                    c.value_x += factor * p.value_x;
+                    c.value_y += factor * p.value_y;
+                }
+            }
+            combined_points
+        };
+        combined.insert(
+            *element_number,
+            InternalForceResults {
+                element_number: *element_number,
+                axial_forces: combine(|r| &r.axial_forces),
+                shear_forces: combine(|r| &r.shear_forces),
+                moment_forces: combine(|r| &r.moment_forces),
+                deflections: combine(|r| &r.deflections),
+                soil_pressures: combine(|r| &r.soil_pressures),
+                end_rotations: [0, 1].map(|i| {
+                    element_results.iter().zip(factors).map(|(r, f)| f * r.end_rotations[i]).sum()
+                }),
+            },
+        );
+    }
+    combined
+}
@@ -1,12 +1,24 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use vputilslib::equation_handler::EquationHandler;
+use crate::settings::{calc_settings::AxleTrainSettings, partial_factors::UlsExpression, PartialFactors};
+use crate::structure::{utils as structure_utils, Element, NodeCollection};
 use super::{
-    load::Load, load_combination::CalcLoadCombination, load_group::GroupType, LoadCombination,
-    LoadGroup,
+    load::{Load, LoadType}, load_combination::{AxleTrainPosition, CalcLoadCombination, LoadCombinationType},
+    load_group::GroupType, LoadCombination, LoadGroup,
 };
 
+/// Creates the calculation load combinations from the given model load combination. If the load
+/// combination is automatic, the combinations are created by the load groups and the combinations
+/// that contain moving loads are 'exploded' with the moving load patterns (see [`get_moving_load_patterns`]).
+/// * `lc` - The model load combination
+/// * `loads` - List of loads
+/// * `elements` - List of the model elements (used to resolve the moving loads linked to all elements)
+/// * `partial_factors` - The partial factors used for the automatic ultimate limit state combinations
 pub fn get_calc_load_combinations(
     lc: &LoadCombination,
     loads: &Vec<Load>,
+    elements: &[Element],
+    partial_factors: &PartialFactors,
 ) -> Vec<CalcLoadCombination> {
     let mut result: Vec<CalcLoadCombination> = Vec::new();
         
@@ -30,6 +42,38 @@ pub fn get_calc_load_combinations(
     
     let loads_map = crate::loads::utils::get_load_map(&loads);
 
+    // Quasi-permanent combination has no leading variable load, so only one combination is
+    // needed (Gk + sum(ψ2,i * Qk,i))
+    if matches!(lc.combination_type, LoadCombinationType::SLSqp { .. }) {
+        let mut calc_lc = CalcLoadCombination::new(
+            lc.number,
+            lc.name.clone(),
+            lc.number*1000+1,
+            "_QP".to_string(),
+            lc.combination_type,
+        );
+        for load_name in loads_map.keys() {
+            if !load_is_included(lc, load_name) {
+                continue;
+            }
+            for load in loads_map.get(load_name).unwrap() {
+                // The permanent loads are the permanent part of the combination
+                // (see get_model_load_factor)
+                let is_permanent = matches!(
+                    load.load_group.group_type,
+                    GroupType::Permanent | GroupType::PermanentFav
+                );
+                let factor = get_model_load_factor(lc, load_name, is_permanent);
+                calc_lc.loads_n_factors.insert(
+                    load.name.clone(),
+                    factor * get_group_factor(lc.combination_type, &load.load_group, false, partial_factors),
+                );
+            }
+        }
+        let result = if calc_lc.loads_n_factors.is_empty() { vec![] } else { vec![calc_lc] };
+        return add_moving_load_patterns(lc, result, loads, elements);
+    }
+
     let mut permanents_only = CalcLoadCombination::new(
         lc.number,
         lc.name.clone(),
@@ -44,25 +88,49 @@ pub fn get_calc_load_combinations(
         
         let loads = loads_map.get(load_name).unwrap();
         for load in loads {
-            let factor = if lc.loads_n_factors.contains_key("ALL") {
-                lc.loads_n_factors["ALL"]
-            } else if lc.loads_n_factors.contains_key(load_name) {
-                lc.loads_n_factors[load_name]
-            } else {
-                1.0
-            };
+            let factor = get_model_load_factor(lc, load_name, true);
             match load.load_group.group_type {
                 GroupType::Permanent => {
+                    let perm_factor = match lc.combination_type {
+                        LoadCombinationType::ULS { .. } => {
+                            get_uls_factor(&load.load_group, partial_factors.get_permanent_factor(), partial_factors)
+                        }
+                        _ => 1.0,
+                    };
                     permanents_only
                         .loads_n_factors
-                        .insert(load.name.clone(), factor * 1.35);
+                        .insert(load.name.clone(), factor * perm_factor);
+                }
+                GroupType::PermanentFav => {
+                    permanents_only.loads_n_factors.insert(
+                        load.name.clone(),
+                        factor * get_group_factor(lc.combination_type, &load.load_group, false, partial_factors),
+                    );
+                }
+                GroupType::LiveLoad => {
+                    // Expression 6.10a can include the variable loads with their combination values
+                    if matches!(lc.combination_type, LoadCombinationType::ULS { .. })
+                        && partial_factors.expression == UlsExpression::Eq6_10ab
+                        && partial_factors.variable_loads_in_6_10a
+                    {
+                        permanents_only.loads_n_factors.insert(
+                            load.name.clone(),
+                            factor
+                                * get_uls_factor(&load.load_group, partial_factors.get_variable_factor(), partial_factors)
+                                * load.load_group.psii0,
+                        );
+                    }
                 }
-                _ => (),
             }
         }
     }
 
-    result.push(permanents_only);
+    // A calculation load combination without any loads would include all the loads with the factor
+    // 1.0 (see crate::loads::utils::extract_calculation_loads), so the combination is not created if
+    // there are no permanent loads
+    if !permanents_only.loads_n_factors.is_empty() {
+        result.push(permanents_only);
+    }
 
     // Create a map of groups from all loads that are included in the load combination
     let mut loads_mapped_by_group: BTreeMap<&LoadGroup, Vec<&Load>> = BTreeMap::new();
@@ -81,7 +149,9 @@ pub fn get_calc_load_combinations(
 
     let mut combination_number = lc.number*1000+2;
     // Go through the groups and create load combinations with one group as the 'main' group
-    // and others the 'secondary' groups (1.15 * Gk + 1.5 * Qk,1 + sum(1,5 * ψ0,i * Qk,i))
+    // and others the 'secondary' groups. E.g. for ULS (1.15 * Gk + 1.5 * Qk,1 + sum(1,5 * ψ0,i * Qk,i)),
+    // for characteristic SLS (Gk + Qk,1 + sum(ψ0,i * Qk,i)) and for frequent SLS
+    // (Gk + ψ1,1 * Qk,1 + sum(ψ2,i * Qk,i))
     for group in loads_mapped_by_group.keys() {
         if matches!(group.group_type, GroupType::Permanent) || matches!(group.group_type, GroupType::PermanentFav) {
             continue;
@@ -101,46 +171,17 @@ pub fn get_calc_load_combinations(
             if !load_is_included(lc, load_name) {
                 continue;
             }
-            let factor = if lc.loads_n_factors.contains_key(load_name) {
-                lc.loads_n_factors[load_name]
-            } else {
-                1.0
-            };
+            let factor = get_model_load_factor(lc, load_name, false);
             // Get the loads with the current load name
             let loads = loads_map.get(load_name).unwrap();
             for load in loads {
-                match load.load_group.group_type {
-                    GroupType::Permanent => {
-                        calc_lc.loads_n_factors.insert(
-                            load.name.clone(),
-                            factor * load.load_group.uls_factor,
-                        );
-                    }
-                    GroupType::PermanentFav => {
-                        calc_lc.loads_n_factors.insert(
-                            load.name.clone(),
-                            factor * load.load_group.uls_factor,
-                        );
-                    }
-                    GroupType::LiveLoad => {
-                        // If the current load is in the group that is currently the 'main' group,
-                        // add it with the unmodified uls factor. Otherwise modify the uls factor with ψ0
-                        // of the loads group
-                        if *group == &load.load_group {
-                            calc_lc.loads_n_factors.insert(
-                                load.name.clone(),
-                                factor * load.load_group.uls_factor,
-                            );
-                        } else {
-                            calc_lc.loads_n_factors.insert(
-                                load.name.clone(),
-                                factor
-                                    * load.load_group.uls_factor
-                                    * load.load_group.psii0,
-                            );
-                        }
-                    }
-                }
+                // If the current load is in the group that is currently the 'main' group,
+                // it is added as the leading variable load
+                let is_leading = *group == &load.load_group;
+                calc_lc.loads_n_factors.insert(
+                    load.name.clone(),
+                    factor * get_group_factor(lc.combination_type, &load.load_group, is_leading, partial_factors),
+                );
             }
         }
         if !calc_lc.loads_n_factors.is_empty() {
@@ -148,9 +189,315 @@ pub fn get_calc_load_combinations(
         }
     }
 
+    add_moving_load_patterns(lc, result, loads, elements)
+}
+
+/// Gets the factor of the load from the model load combination. The factor for 'ALL' is used only
+/// for the permanent part of the automatic combinations (the combination with only the permanent
+/// loads and the permanent loads of the quasi-permanent combination) and it overrides the factor
+/// of the load name. If neither is found, returns 1.0.
+/// * `lc` - The model load combination
+/// * `load_name` - The name of the load
+/// * `is_permanent_part` - Is the load in the permanent part of the combination
+fn get_model_load_factor(lc: &LoadCombination, load_name: &str, is_permanent_part: bool) -> f64 {
+    if let (true, Some(factor)) = (is_permanent_part, lc.loads_n_factors.get("ALL")) {
+        *factor
+    } else if let Some(factor) = lc.loads_n_factors.get(load_name) {
+        *factor
+    } else {
+        1.0
+    }
+}
+
+/// Gets the factor for the loads of the given group in the combination with a leading variable load
+/// (EN 1990 6.4.3.2 and 6.5.3).
+/// * `combination_type` - The type of the load combination
+/// * `group` - The load group of the load
+/// * `is_leading` - Is the group the leading variable load group of the combination
+/// * `partial_factors` - The partial factors for the ultimate limit state
+pub fn get_group_factor(
+    combination_type: LoadCombinationType,
+    group: &LoadGroup,
+    is_leading: bool,
+    partial_factors: &PartialFactors,
+) -> f64 {
+    match group.group_type {
+        GroupType::Permanent => match combination_type {
+            LoadCombinationType::ULS { .. } => {
+                get_uls_factor(group, partial_factors.get_permanent_factor_with_leading(), partial_factors)
+            }
+            _ => 1.0,
+        },
+        GroupType::PermanentFav => match combination_type {
+            LoadCombinationType::ULS { .. } => get_uls_factor(group, partial_factors.gamma_g_inf, partial_factors),
+            _ => 1.0,
+        },
+        GroupType::LiveLoad => match combination_type {
+            LoadCombinationType::ULS { .. } | LoadCombinationType::None => {
+                let variable_factor = get_uls_factor(group, partial_factors.get_variable_factor(), partial_factors);
+                if is_leading {
+                    variable_factor
+                } else {
+                    variable_factor * group.psii0
+                }
+            }
+            LoadCombinationType::SLSc { .. } => {
+                if is_leading {
+                    1.0
+                } else {
+                    group.psii0
+                }
+            }
+            LoadCombinationType::SLSf { .. } => {
+                if is_leading {
+                    group.psii1
+                } else {
+                    group.psii2
+                }
+            }
+            LoadCombinationType::SLSqp { .. } => group.psii2,
+        },
+    }
+}
+
+/// Gets the partial factor of the group in the ultimate limit state. The partial factor of a user
+/// defined group replaces the base factor of the partial factor set (γG,sup, γG,inf or γQ), so the
+/// reduction factor ξ of the expression 6.10b and the consequence class factor KFI are applied to
+/// the user defined groups the same way as to the predefined groups.
+/// * `set_factor` - The factor from the partial factor set
+fn get_uls_factor(group: &LoadGroup, set_factor: f64, partial_factors: &PartialFactors) -> f64 {
+    if !group.is_user_group() {
+        return set_factor;
+    }
+    let base_factor = match group.group_type {
+        GroupType::Permanent => partial_factors.gamma_g_sup,
+        GroupType::PermanentFav => partial_factors.gamma_g_inf,
+        GroupType::LiveLoad => partial_factors.gamma_q,
+    };
+    set_factor * group.uls_factor / base_factor
+}
+
+/// Adds the moving load pattern combinations after each calculation load combination that contains
+/// moving loads. The combinations are renumbered so that the sub numbers stay consecutive.
+fn add_moving_load_patterns(
+    lc: &LoadCombination,
+    calc_lcs: Vec<CalcLoadCombination>,
+    loads: &[Load],
+    elements: &[Element],
+) -> Vec<CalcLoadCombination> {
+    if !loads.iter().any(|l| l.is_moving_load) {
+        return calc_lcs;
+    }
+    let mut result: Vec<CalcLoadCombination> = Vec::new();
+    for calc_lc in calc_lcs {
+        // Collect the elements that the moving loads of this combination are linked to
+        let mut moving_elements: BTreeSet<i32> = BTreeSet::new();
+        for load in loads.iter() {
+            if !load.is_moving_load
+                || load.is_support_displacement()
+                || !calc_load_is_included(&calc_lc, &load.name)
+            {
+                continue;
+            }
+            let linked = crate::loads::utils::get_linked_element_numbers(load);
+            for e in elements.iter() {
+                if crate::loads::utils::load_is_linked(e.number, &linked) {
+                    moving_elements.insert(e.number);
+                }
+            }
+        }
+        let moving_elements: Vec<&Element> =
+            elements.iter().filter(|e| moving_elements.contains(&e.number)).collect();
+        let patterns = get_moving_load_patterns(&moving_elements);
+        let base = calc_lc.clone();
+        result.push(calc_lc);
+        for pattern in patterns {
+            let mut pattern_lc = base.clone();
+            pattern_lc.sub_name = format!(
+                "{}_MOV({})",
+                base.sub_name,
+                pattern.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(",")
+            );
+            pattern_lc.moving_load_elements = Some(pattern);
+            result.push(pattern_lc);
+        }
+    }
+    for (i, calc_lc) in result.iter_mut().enumerate() {
+        calc_lc.sub_number = lc.number * 1000 + 1 + i;
+    }
+    result
+}
+
+/// Replaces each calculation load combination that contains axle train loads with the
+/// combinations of the train positions (see [AxleTrainSettings]). The combinations are renumbered
+/// so that the sub numbers stay consecutive. There can be more than 999 train positions, so the
+/// sub numbers have to be made unique with [set_unique_sub_numbers] afterwards.
+/// * `lc` - The model load combination
+/// * `calc_lcs` - The calculation load combinations of the model load combination
+/// * `nodes` - The structure nodes (used to get the lengths of the paths)
+pub fn add_axle_train_positions(
+    lc: &LoadCombination,
+    calc_lcs: Vec<CalcLoadCombination>,
+    loads: &[Load],
+    elements: &[Element],
+    nodes: &NodeCollection,
+    settings: &AxleTrainSettings,
+    eq_handler: &EquationHandler,
+) -> Vec<CalcLoadCombination> {
+    if !loads.iter().any(|l| l.load_type == LoadType::AxleTrain) {
+        return calc_lcs;
+    }
+    let mut result: Vec<CalcLoadCombination> = Vec::new();
+    for calc_lc in calc_lcs {
+        // The longest path and train of the axle trains in this combination
+        let mut path_length: f64 = 0.0;
+        let mut train_length: f64 = 0.0;
+        let mut has_trains = false;
+        for load in loads.iter() {
+            if load.load_type != LoadType::AxleTrain || !calc_load_is_included(&calc_lc, &load.name) {
+                continue;
+            }
+            let offsets = match load.get_axle_offsets(eq_handler) {
+                Ok(offsets) => offsets,
+                Err(message) => {
+                    println!("{} The load is skipped.", message);
+                    continue;
+                }
+            };
+            has_trains = true;
+            let linked = crate::loads::utils::get_linked_element_numbers(load);
+            let path = structure_utils::get_element_path(elements, nodes, &linked);
+            path_length = path_length.max(path.iter().map(|e| e.length).sum());
+            train_length = train_length.max(offsets.last().copied().unwrap_or(0.0));
+        }
+        if !has_trains {
+            result.push(calc_lc);
+            continue;
+        }
+        let travel = path_length + train_length;
+        let step_count = (travel / settings.step.max(1.0)).ceil().max(1.0) as usize;
+        let directions: &[bool] = if settings.both_directions { &[false, true] } else { &[false] };
+        for reversed in directions {
+            for step in 0..=step_count {
+                let distance = travel * step as f64 / step_count as f64;
+                // The first axle enters the path at the start (or at the end if reversed)
+                let path_position = if *reversed { path_length - distance } else { distance };
+                let mut position_lc = calc_lc.clone();
+                position_lc.sub_name = format!(
+                    "{}_AXLE{}({:.0})",
+                    calc_lc.sub_name,
+                    if *reversed { "_REV" } else { "" },
+                    path_position
+                );
+                position_lc.axle_train_position = Some(AxleTrainPosition { path_position, reversed: *reversed });
+                result.push(position_lc);
+            }
+        }
+    }
+    for (i, calc_lc) in result.iter_mut().enumerate() {
+        calc_lc.sub_number = lc.number * 1000 + 1 + i;
+    }
     result
 }
 
+/// Makes the sub numbers of the calculation load combinations of all the model load combinations
+/// unique. The sub numbers are `lc.number * 1000 + i`, so a model load combination with more than
+/// 999 sub combinations (e.g. the axle train positions) would overlap the next one. In that case
+/// the multiplier 1000 is raised to the power of ten that fits all the sub combinations. Otherwise
+/// the sub numbers are not changed.
+pub fn set_unique_sub_numbers(calc_lcs: &mut [CalcLoadCombination]) {
+    let get_index = |calc_lc: &CalcLoadCombination| {
+        calc_lc.sub_number.saturating_sub(calc_lc.parent_load_combination_number * 1000)
+    };
+    let max_index = calc_lcs.iter().map(get_index).max().unwrap_or(0);
+    let mut multiplier = 1000;
+    while max_index >= multiplier {
+        multiplier *= 10;
+    }
+    if multiplier == 1000 {
+        return;
+    }
+    for calc_lc in calc_lcs.iter_mut() {
+        calc_lc.sub_number = calc_lc.parent_load_combination_number * multiplier + get_index(calc_lc);
+    }
+}
+
+/// Gets the patterns (sets of element numbers with the moving loads in full strength) for the
+/// given elements. The patterns are: each element alone, each pair of adjacent elements and every
+/// other element along the chains of connected elements (starting from the first and from the
+/// second element of each chain). The elements are adjacent if they share a node. The set of all
+/// elements is not included, because it equals the combination without any pattern.
+/// * `elements` - The elements the moving loads are linked to
+pub fn get_moving_load_patterns(elements: &[&Element]) -> Vec<Vec<i32>> {
+    let mut patterns: Vec<Vec<i32>> = Vec::new();
+    if elements.len() < 2 {
+        return patterns;
+    }
+    let mut add_pattern = |mut pattern: Vec<i32>| {
+        pattern.sort();
+        if !pattern.is_empty() && pattern.len() < elements.len() && !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    };
+    for e in elements.iter() {
+        add_pattern(vec![e.number]);
+    }
+    for (i, e) in elements.iter().enumerate() {
+        for other in elements[i + 1..].iter().filter(|o| elements_are_adjacent(e, o)) {
+            add_pattern(vec![e.number, other.number]);
+        }
+    }
+    let (mut first, mut second) = (Vec::new(), Vec::new());
+    for chain in get_element_chains(elements) {
+        for (i, number) in chain.into_iter().enumerate() {
+            if i % 2 == 0 {
+                first.push(number);
+            } else {
+                second.push(number);
+            }
+        }
+    }
+    add_pattern(first);
+    add_pattern(second);
+    patterns
+}
+
+/// Returns true if the elements share a node
+fn elements_are_adjacent(a: &Element, b: &Element) -> bool {
+    a.node_start == b.node_start
+        || a.node_start == b.node_end
+        || a.node_end == b.node_start
+        || a.node_end == b.node_end
+}
+
+/// Gets the chains of the connected elements as the element numbers in the order of the chain.
+/// The chains start from the elements that have at most one adjacent element (the ends of the
+/// chains). The elements that are left (the closed loops) start new chains in the given order.
+fn get_element_chains(elements: &[&Element]) -> Vec<Vec<i32>> {
+    let neighbours = |e: &Element| elements.iter().filter(|o| o.number != e.number && elements_are_adjacent(e, o)).count();
+    let mut starts: Vec<&Element> = elements.iter().copied().filter(|e| neighbours(e) <= 1).collect();
+    starts.extend(elements.iter().copied());
+    let mut visited: BTreeSet<i32> = BTreeSet::new();
+    let mut chains = Vec::new();
+    for start in starts {
+        if visited.contains(&start.number) {
+            continue;
+        }
+        let mut chain = Vec::new();
+        let mut current = Some(start);
+        while let Some(e) = current {
+            visited.insert(e.number);
+            chain.push(e.number);
+            current = elements
+                .iter()
+                .copied()
+                .find(|o| !visited.contains(&o.number) && elements_are_adjacent(e, o));
+        }
+        chains.push(chain);
+    }
+    chains
+}
+
 pub fn load_is_included(lc: &LoadCombination, load_name: &str) -> bool {
     if lc.loads_n_factors.is_empty() || lc.loads_n_factors.contains_key("ALL") {
         return true;
@@ -165,6 +512,20 @@ pub fn calc_load_is_included(lc: &CalcLoadCombination, load_name: &str) -> bool
     lc.loads_n_factors.contains_key(load_name)
 }
 
+/// Gets the strength factor for the loads with given name in the load combination. If the
+/// loads_n_factors map is empty, all the loads are included with the factor 1.0. Returns None if
+/// the load is not included in the load combination.
+pub fn get_load_factor(lc: &CalcLoadCombination, load_name: &str) -> Option<f64> {
+    if lc.loads_n_factors.is_empty() {
+        return Some(1.0);
+    }
+    if !calc_load_is_included(lc, load_name) {
+        return None;
+    }
+    let factor = lc.loads_n_factors.get(load_name).or_else(|| lc.loads_n_factors.get("ALL"));
+    Some(factor.copied().unwrap_or(1.0))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -308,8 +669,334 @@ mod test {
         test_load_combination(lc, &loads, 4);
     }
 
+    /// Creates a continuous beam of the elements between the nodes 1, 2, 3...
+    fn continuous_beam(count: i32) -> Vec<Element> {
+        (1..=count)
+            .map(|i| Element { number: i, node_start: i, node_end: i + 1, ..Default::default() })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_sls() {
+        let mut loads = Vec::new();
+        for (name, group) in [
+            ("g", LoadGroup::PERMANENT),
+            ("q1", LoadGroup::CLASS_A),
+            ("qw", LoadGroup::WIND_POS),
+        ] {
+            loads.push(Load::new_line_load(
+                name.to_string(),
+                "1".to_string(),
+                "0.0".to_string(),
+                "L".to_string(),
+                "10.0".to_string(),
+                -90.0,
+                group,
+            ));
+        }
+        let factor = |lc: &CalcLoadCombination, name: &str| lc.loads_n_factors[name];
+
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::new(),
+            combination_type: LoadCombinationType::SLSc { is_auto: true },
+        };
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 3);
+        assert_eq!(factor(&result[0], "g"), 1.0);
+        assert_eq!(result[0].loads_n_factors.len(), 1);
+        assert_eq!(factor(&result[1], "g"), 1.0);
+        assert_eq!(factor(&result[1], "q1"), 1.0);
+        assert_eq!(factor(&result[1], "qw"), 0.6);
+        assert_eq!(factor(&result[2], "q1"), 0.7);
+        assert_eq!(factor(&result[2], "qw"), 1.0);
+
+        let lc = LoadCombination {
+            combination_type: LoadCombinationType::SLSf { is_auto: true },
+            ..lc
+        };
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 3);
+        assert_eq!(factor(&result[1], "g"), 1.0);
+        assert_eq!(factor(&result[1], "q1"), 0.5);
+        assert_eq!(factor(&result[1], "qw"), 0.0);
+        assert_eq!(factor(&result[2], "q1"), 0.3);
+        assert_eq!(factor(&result[2], "qw"), 0.2);
+
+        let lc = LoadCombination {
+            combination_type: LoadCombinationType::SLSqp { is_auto: true },
+            ..lc
+        };
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 1);
+        assert_eq!(factor(&result[0], "g"), 1.0);
+        assert_eq!(factor(&result[0], "q1"), 0.3);
+        assert_eq!(factor(&result[0], "qw"), 0.0);
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_all_factor() {
+        let loads: Vec<Load> = [("g", LoadGroup::PERMANENT), ("q", LoadGroup::CLASS_A)]
+            .into_iter()
+            .map(|(name, group)| Load::new_line_load(
+                name.to_string(),
+                "1".to_string(),
+                "0.0".to_string(),
+                "L".to_string(),
+                "10.0".to_string(),
+                -90.0,
+                group,
+            ))
+            .collect();
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::from([("ALL".to_string(), 2.0)]),
+            combination_type: LoadCombinationType::ULS { is_auto: true },
+        };
+        // The factor for 'ALL' is used only for the combination of the permanent loads
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 2);
+        assert!((result[0].loads_n_factors["g"] - 2.0 * 1.35).abs() < 1e-9);
+        assert!((result[1].loads_n_factors["q"] - 1.5).abs() < 1e-9);
+
+        // The same rule is used in the quasi-permanent combination
+        let lc = LoadCombination {
+            combination_type: LoadCombinationType::SLSqp { is_auto: true },
+            ..lc
+        };
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 1);
+        assert!((result[0].loads_n_factors["g"] - 2.0).abs() < 1e-9);
+        assert!((result[0].loads_n_factors["q"] - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_without_permanent_loads() {
+        let loads = vec![Load::new_line_load(
+            "q".to_string(),
+            "1".to_string(),
+            "0.0".to_string(),
+            "L".to_string(),
+            "10.0".to_string(),
+            -90.0,
+            LoadGroup::CLASS_A,
+        )];
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::new(),
+            combination_type: LoadCombinationType::ULS { is_auto: true },
+        };
+        // An empty _PERM combination would calculate the live load unfactored
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].sub_name, "_LL(KL A)");
+        assert!(result.iter().all(|r| !r.loads_n_factors.is_empty()));
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_partial_factors() {
+        let mut loads = Vec::new();
+        for (name, group) in [
+            ("g", LoadGroup::PERMANENT),
+            ("g_fav", LoadGroup::PERMANENT_FAV),
+            ("q1", LoadGroup::CLASS_A),
+            ("qw", LoadGroup::WIND_POS),
+        ] {
+            loads.push(Load::new_line_load(
+                name.to_string(),
+                "1".to_string(),
+                "0.0".to_string(),
+                "L".to_string(),
+                "10.0".to_string(),
+                -90.0,
+                group,
+            ));
+        }
+        let factor = |lc: &CalcLoadCombination, name: &str| lc.loads_n_factors[name];
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::new(),
+            combination_type: LoadCombinationType::ULS { is_auto: true },
+        };
+
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert_eq!(result.len(), 3);
+        assert_eq!(factor(&result[0], "g"), 1.35);
+        assert_eq!(factor(&result[0], "g_fav"), 0.9);
+        assert!(!result[0].loads_n_factors.contains_key("q1"));
+        assert_eq!(factor(&result[1], "g"), 1.15);
+        assert_eq!(factor(&result[1], "q1"), 1.5);
+        assert!((factor(&result[1], "qw") - 0.9).abs() < 1e-9);
+
+        let mut partial_factors = PartialFactors::new_recommended();
+        partial_factors.k_fi = 1.1;
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &partial_factors);
+        assert!((factor(&result[1], "g") - 1.35 * 1.1).abs() < 1e-9);
+        assert_eq!(factor(&result[1], "g_fav"), 1.0);
+        assert!((factor(&result[1], "q1") - 1.5 * 1.1).abs() < 1e-9);
+        assert!((factor(&result[1], "qw") - 1.5 * 1.1 * 0.6).abs() < 1e-9);
+
+        let mut partial_factors = PartialFactors::new_recommended();
+        partial_factors.expression = UlsExpression::Eq6_10ab;
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &partial_factors);
+        assert!((factor(&result[0], "q1") - 1.5 * 0.7).abs() < 1e-9);
+        assert!((factor(&result[1], "g") - 0.85 * 1.35).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_user_groups() {
+        let loads: Vec<Load> = [
+            LoadGroup::new_user_1("G".to_string(), 1.2, 1.0, 1.0, 1.0, GroupType::Permanent),
+            LoadGroup::new_user_2("Q".to_string(), 1.3, 0.5, 0.5, 0.5, GroupType::LiveLoad),
+            LoadGroup::CLASS_A,
+        ]
+        .into_iter()
+        .zip(["g", "q", "q1"])
+        .map(|(group, name)| Load::new_line_load(
+            name.to_string(),
+            "1".to_string(),
+            "0.0".to_string(),
+            "L".to_string(),
+            "10.0".to_string(),
+            -90.0,
+            group,
+        ))
+        .collect();
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::new(),
+            combination_type: LoadCombinationType::ULS { is_auto: true },
+        };
+        let mut partial_factors = PartialFactors::default();
+        partial_factors.k_fi = 1.1;
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &partial_factors);
+        // _PERM, _LL(Class A), _LL(Q)
+        assert_eq!(result.len(), 3);
+        let factor = |lc: &CalcLoadCombination, name: &str| lc.loads_n_factors[name];
+        // Expression 6.10a with the factor of the group and 6.10b with the reduced factor ξ·γG
+        assert!((factor(&result[0], "g") - 1.2 * 1.1).abs() < 1e-9);
+        assert!((factor(&result[1], "g") - 1.2 * 1.15 / 1.35 * 1.1).abs() < 1e-9);
+        assert!((factor(&result[1], "q1") - 1.5 * 1.1).abs() < 1e-9);
+        assert!((factor(&result[1], "q") - 1.3 * 1.1 * 0.5).abs() < 1e-9);
+        assert!((factor(&result[2], "g") - 1.2 * 1.15 / 1.35 * 1.1).abs() < 1e-9);
+        assert!((factor(&result[2], "q") - 1.3 * 1.1).abs() < 1e-9);
+
+        // Expression 6.10 uses the factor of the group in all combinations
+        let partial_factors = PartialFactors::new_recommended();
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &partial_factors);
+        assert!((factor(&result[0], "g") - 1.2).abs() < 1e-9);
+        assert!((factor(&result[1], "g") - 1.2).abs() < 1e-9);
+
+        // The favourable user defined group replaces γG,inf
+        let loads = vec![Load::new_line_load(
+            "g_fav".to_string(),
+            "1".to_string(),
+            "0.0".to_string(),
+            "L".to_string(),
+            "10.0".to_string(),
+            -90.0,
+            LoadGroup::new_user_1("G_FAV".to_string(), 0.8, 1.0, 1.0, 1.0, GroupType::PermanentFav),
+        )];
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
+        assert!((factor(&result[0], "g_fav") - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moving_load_patterns() {
+        let elements = continuous_beam(4);
+        let refs: Vec<&Element> = elements.iter().collect();
+        assert_eq!(get_moving_load_patterns(&refs[..1]).len(), 0);
+        // [1], [2], [1,2] is the full set
+        assert_eq!(get_moving_load_patterns(&refs[..2]).len(), 2);
+        // [1], [2], [3], [1,2], [2,3], [1,3]
+        let patterns = get_moving_load_patterns(&refs[..3]);
+        assert_eq!(patterns.len(), 6);
+        assert!(patterns.contains(&vec![1, 3]));
+        // [1], [2], [3], [4], [1,2], [2,3], [3,4], [1,3], [2,4]
+        assert_eq!(get_moving_load_patterns(&refs).len(), 9);
+    }
+
+    #[test]
+    fn test_moving_load_patterns_by_connectivity() {
+        // The element 2 is not connected to the element 1 although it is numbered after it. The
+        // chains are [1] and [2, 3].
+        let elements = vec![
+            Element { number: 1, node_start: 1, node_end: 2, ..Default::default() },
+            Element { number: 2, node_start: 3, node_end: 4, ..Default::default() },
+            Element { number: 3, node_start: 4, node_end: 5, ..Default::default() },
+        ];
+        let refs: Vec<&Element> = elements.iter().collect();
+        let patterns = get_moving_load_patterns(&refs);
+        assert_eq!(patterns, vec![vec![1], vec![2], vec![3], vec![2, 3], vec![1, 2]]);
+
+        // The adjacency does not depend on the numbering
+        let elements = vec![
+            Element { number: 1, node_start: 1, node_end: 2, ..Default::default() },
+            Element { number: 2, node_start: 3, node_end: 4, ..Default::default() },
+            Element { number: 3, node_start: 2, node_end: 3, ..Default::default() },
+        ];
+        let refs: Vec<&Element> = elements.iter().collect();
+        let patterns = get_moving_load_patterns(&refs);
+        assert!(patterns.contains(&vec![1, 3]) && patterns.contains(&vec![2, 3]));
+        assert!(patterns.contains(&vec![1, 2]));
+        assert_eq!(patterns.len(), 6);
+    }
+
+    #[test]
+    fn test_get_calc_load_combinations_moving() {
+        let elements = continuous_beam(3);
+        let g = Load::new_line_load(
+            "g".to_string(),
+            "-1".to_string(),
+            "0.0".to_string(),
+            "L".to_string(),
+            "10.0".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let mut q = Load::new_line_load(
+            "q".to_string(),
+            "-1".to_string(),
+            "0.0".to_string(),
+            "L".to_string(),
+            "10.0".to_string(),
+            -90.0,
+            LoadGroup::CLASS_A,
+        );
+        q.is_moving_load = true;
+        let loads = vec![g, q];
+        let lc = LoadCombination {
+            number: 1,
+            name: "TEST".to_string(),
+            loads_n_factors: BTreeMap::new(),
+            combination_type: LoadCombinationType::ULS { is_auto: true },
+        };
+        let result = get_calc_load_combinations(&lc, &loads, &elements, &PartialFactors::default());
+        // _PERM, _LL(Class A) and 6 patterns of the live load combination
+        assert_eq!(result.len(), 8);
+        assert!(result[0].moving_load_elements.is_none());
+        assert!(result[1].moving_load_elements.is_none());
+        assert_eq!(result[2].moving_load_elements, Some(vec![1]));
+        assert_eq!(result[7].moving_load_elements, Some(vec![1, 3]));
+        for (i, r) in result.iter().enumerate() {
+            assert_eq!(r.sub_number, 1001 + i);
+        }
+
+        // Not automatic load combinations are not exploded
+        let lc = LoadCombination {
+            combination_type: LoadCombinationType::ULS { is_auto: false },
+            ..lc
+        };
+        assert_eq!(get_calc_load_combinations(&lc, &loads, &elements, &PartialFactors::default()).len(), 1);
+    }
+
     fn test_load_combination(lc: LoadCombination, loads: &Vec<Load>, assert_count: usize) {
-        let result = get_calc_load_combinations(&lc, &loads);
+        let result = get_calc_load_combinations(&lc, &loads, &Vec::new(), &PartialFactors::default());
         for r in result.iter() {
             println!("{}", r.sub_name);
         }
@@ -1,5 +1,6 @@
 use crate::loads::load::LoadType::{
-    Line, Point, Rotational, Strain, Thermal, Trapezoid, Triangular,
+    AxleTrain, Line, Point, Rotational, SelfWeight, Strain, SupportDisplacement, SupportRotation,
+    Thermal, Trapezoid, Triangular,
 };
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -16,6 +17,8 @@ pub struct Load {
     /// from 'S' to 'E' are included (the element with number 'E' is also included)
     ///
     /// If all load should be linked to all elements, -1 can be used (e.g. for self weight load)
+    ///
+    /// For the support displacement and support rotation loads these are the node numbers.
     pub element_numbers: String,
     /// The load type for the load. Controls how the load needs to be handled.
     pub load_type: LoadType,
@@ -28,7 +31,9 @@ pub struct Load {
     /// Comment for the load
     pub comment: String,
     /// The strength of the load (can be an equation). For trapezoid loads, the start and end values are separated
-    /// with ; (semicolon)
+    /// with ; (semicolon). An empty strength is zero, except for the self weight load where it
+    /// means the full self weight (multiplier 1)
+    #[serde(default)]
     pub strength: String,
     /// The rotation of the load. 0 means the load is pointing towards positive X-axis in global coordinates (right).
     pub rotation: f64,
@@ -175,6 +180,135 @@ impl Load {
         }
     }
 
+    /// Creates new self weight load. The load is calculated from the profile and material of each
+    /// linked element and it is always pointing downwards (global Z-axis). The strength is used
+    /// as a multiplier for the weight (default 1).
+    pub fn new_self_weight_load(name: String, element_numbers: String, load_group: LoadGroup) -> Self {
+        Self {
+            name,
+            element_numbers,
+            strength: "1".to_string(),
+            load_group,
+            load_type: SelfWeight,
+            ..Self::default()
+        }
+    }
+
+    /// Creates new support displacement load (e.g. a settlement). The strength is the displacement
+    /// (mm) in the direction of the rotation (-90 is downwards). Only the locked translations of
+    /// the supports are displaced.
+    pub fn new_support_displacement(
+        name: String,
+        node_numbers: String,
+        strength: String,
+        rotation: f64,
+        load_group: LoadGroup,
+    ) -> Self {
+        Self {
+            name,
+            element_numbers: node_numbers,
+            strength,
+            rotation,
+            load_group,
+            load_type: SupportDisplacement,
+            ..Self::default()
+        }
+    }
+
+    /// Creates new support rotation load. The strength is the rotation (rad) about the Y-axis.
+    /// Only the locked rotations of the supports are rotated.
+    pub fn new_support_rotation(
+        name: String,
+        node_numbers: String,
+        strength: String,
+        load_group: LoadGroup,
+    ) -> Self {
+        Self {
+            name,
+            element_numbers: node_numbers,
+            strength,
+            load_group,
+            load_type: SupportRotation,
+            ..Self::default()
+        }
+    }
+
+    /// Creates new axle train load (e.g. a vehicle or a crane). The train is moved along the path
+    /// elements in the order of the element numbers and each train position is calculated as a
+    /// separate sub load combination (see [crate::settings::calc_settings::AxleTrainSettings]).
+    /// * `path_elements` - the element numbers of the path in the order of the path
+    /// * `axle_loads` - the axle loads (kN) from the first axle separated with ; (semicolon)
+    /// * `axle_spacings` - the spacings (mm) between the consecutive axles separated with ;
+    ///   (semicolon)
+    pub fn new_axle_train(
+        name: String,
+        path_elements: String,
+        axle_loads: String,
+        axle_spacings: String,
+        rotation: f64,
+        load_group: LoadGroup,
+    ) -> Self {
+        Self {
+            name,
+            element_numbers: path_elements,
+            strength: axle_loads,
+            offset_start: axle_spacings,
+            rotation,
+            load_group,
+            load_type: AxleTrain,
+            ..Self::default()
+        }
+    }
+
+    /// Gets the axle loads (kN) of the axle train load from the first axle. Returns the error
+    /// message if any of the axle loads can not be calculated.
+    pub fn get_axle_loads(&self, equation_handler: &EquationHandler) -> Result<Vec<f64>, String> {
+        calculate_separated_values(&self.strength, equation_handler).map_err(|value| {
+            format!(
+                "Error while parsing the axle load '{}' of the axle train '{}'. Use semicolon ';' to separate the axle loads.",
+                value, self.name
+            )
+        })
+    }
+
+    /// Gets the offsets (mm) of the axles of the axle train load measured from the first axle
+    /// backwards. There is an offset for each axle load (the offset of the first axle is zero).
+    /// Returns the error message if the axle loads or the spacings can not be calculated or if
+    /// the number of the spacings does not match the number of the axles.
+    pub fn get_axle_offsets(&self, equation_handler: &EquationHandler) -> Result<Vec<f64>, String> {
+        let axle_count = self.get_axle_loads(equation_handler)?.len();
+        if axle_count < 2 {
+            return Ok(vec![0.0]);
+        }
+        let spacings = calculate_separated_values(&self.offset_start, equation_handler).map_err(|value| {
+            format!(
+                "Error while parsing the axle spacing '{}' of the axle train '{}'. Use semicolon ';' to separate the axle spacings.",
+                value, self.name
+            )
+        })?;
+        if spacings.len() != axle_count - 1 {
+            return Err(format!(
+                "The axle train '{}' has {} axle loads, but {} axle spacings. There must be one spacing less than axle loads.",
+                self.name,
+                axle_count,
+                spacings.len()
+            ));
+        }
+        let mut offset = 0.0;
+        let mut offsets = vec![0.0];
+        for spacing in spacings {
+            offset += spacing;
+            offsets.push(offset);
+        }
+        Ok(offsets)
+    }
+
+    /// Returns true if the load is a support displacement or a support rotation (linked to the
+    /// nodes instead of the elements)
+    pub fn is_support_displacement(&self) -> bool {
+        matches!(self.load_type, SupportDisplacement | SupportRotation)
+    }
+
     pub fn get_length(&self, equation_handler: &EquationHandler) -> f64 {
         let off_end = equation_handler
             .calculate_formula(&self.offset_end)
@@ -186,6 +320,18 @@ impl Load {
     }
 }
 
+/// Calculates the values separated with ; (semicolon). Returns the first value that can not be
+/// calculated as the error.
+fn calculate_separated_values(values: &str, equation_handler: &EquationHandler) -> Result<Vec<f64>, String> {
+    values
+        .split(';')
+        .map(|s| {
+            let value = equation_handler.calculate_formula(s.trim()).unwrap_or(f64::NAN);
+            if value.is_finite() { Ok(value) } else { Err(s.trim().to_string()) }
+        })
+        .collect()
+}
+
 impl Default for Load {
     fn default() -> Self {
         Self {
@@ -194,7 +340,7 @@ impl Default for Load {
             element_numbers: "".to_string(),
             offset_start: "0".to_string(),
             offset_end: "L".to_string(),
-            strength: "0".to_string(),
+            strength: "".to_string(),
             rotation: -90.0,
             comment: "".to_string(),
             is_moving_load: false,
@@ -215,6 +361,21 @@ pub enum LoadType {
     Trapezoid = 4,
     Strain = 5,
     Thermal = 6,
+    /// Self weight of the element calculated from the profile area and the material density or
+    /// from the custom weight of the profile. The strength is a multiplier for the weight
+    /// (empty strength means multiplier 1).
+    SelfWeight = 7,
+    /// The prescribed displacement of the supports (e.g. a settlement). The element numbers are
+    /// the node numbers and the strength is the displacement (mm) in the direction of the
+    /// rotation. Only the locked translations of the supports are displaced.
+    SupportDisplacement = 8,
+    /// The prescribed rotation of the supports (rad). The element numbers are the node numbers.
+    /// Only the locked rotations of the supports are rotated.
+    SupportRotation = 9,
+    /// The train of axle point loads moved along the path elements. The element numbers are the
+    /// path elements, the strength is the axle loads (kN) and the offset start is the spacings
+    /// (mm) between the axles. The loads and the spacings are separated with ; (semicolon).
+    AxleTrain = 10,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
@@ -224,9 +385,12 @@ pub enum CalculationLoadType {
     Triangular,
     Rotational,
     Strain,
+    /// The imposed curvature of the element (1/mm), e.g. the shrinkage curvature of the concrete
+    /// elements. Positive value bends the element like a positive (sagging) bending moment.
+    Curvature,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CalculationLoad {
     pub name: String,
     pub load_type: CalculationLoadType,
@@ -242,3 +406,18 @@ impl CalculationLoad {
         (self.offset_end - self.offset_start).abs()
     }
 }
+
+/// The prescribed displacement of a support node (e.g. a settlement) in the global coordinates.
+/// Extracted from the support displacement and support rotation loads.
+#[derive(Debug, Clone)]
+pub struct PrescribedDisplacement {
+    pub name: String,
+    /// The number of the displaced node
+    pub node_number: i32,
+    /// The translation in the global X-direction (mm)
+    pub x: f64,
+    /// The translation in the global Z-direction (mm)
+    pub z: f64,
+    /// The rotation about the Y-axis (rad)
+    pub ry: f64,
+}
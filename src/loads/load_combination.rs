@@ -100,7 +100,27 @@ pub struct CalcLoadCombination {
     pub loads_n_factors: BTreeMap<String, f64>,
     pub parent_load_combination: String,
     pub parent_load_combination_number: usize,
+    /// The model element numbers on which the moving loads are set with full strength. On the
+    /// rest of the linked elements the moving loads are multiplied with their moving percent.
+    /// None if the combination is not a moving load pattern (moving loads are set on all of
+    /// their linked elements with full strength).
+    pub moving_load_elements: Option<Vec<i32>>,
+    /// The position of the axle trains. None if the combination has no axle train loads (the axle
+    /// trains are not set without the position).
+    pub axle_train_position: Option<AxleTrainPosition>,
 }
+
+/// The position of the axle trains in a calculation load combination. All the axle trains of the
+/// combination are at the same position on their paths.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxleTrainPosition {
+    /// The position of the first axle along the path measured from the start of the path (mm)
+    pub path_position: f64,
+    /// True if the train moves from the end of the path to the start. The first axle leads in the
+    /// direction of the travel, so the other axles are behind it towards the end of the path.
+    pub reversed: bool,
+}
+
 impl CalcLoadCombination {
     pub fn new(
         parent_load_comb_number: usize,
@@ -116,6 +136,8 @@ impl CalcLoadCombination {
             loads_n_factors: BTreeMap::new(),
             parent_load_combination: parent_load_comb_name,
             parent_load_combination_number: parent_load_comb_number,
+            moving_load_elements: None,
+            axle_train_position: None,
         }
     }
 }
@@ -129,6 +151,8 @@ impl Default for CalcLoadCombination {
             loads_n_factors: Default::default(),
             parent_load_combination: "LoadCombination::DEFAULT_NAME".to_string(),
             parent_load_combination_number: 0,
+            moving_load_elements: None,
+            axle_train_position: None,
         }
     }
 }
\ No newline at end of file
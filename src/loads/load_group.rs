@@ -7,6 +7,10 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 pub struct LoadGroup {
     id: u8,
     name: Cow<'static, str>,
+    /// The partial factor of the group. The automatic load combinations use the partial factors
+    /// from the calculation settings (see [`crate::settings::PartialFactors`]) for the predefined
+    /// groups. The factor of a user defined group (see [`LoadGroup::new_user_1`]) replaces the base
+    /// factor of the partial factor set (γG,sup, γG,inf or γQ).
     pub uls_factor: f64,
     pub psii0: f64,
     pub psii1: f64,
@@ -55,6 +59,11 @@ impl LoadGroup {
         }
     }
 
+    /// Returns true if the group is a user defined group with its own partial factor
+    pub fn is_user_group(&self) -> bool {
+        self.id >= 21
+    }
+
     pub fn get_id(&self) -> u8 {
         self.id
     }
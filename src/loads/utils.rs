@@ -4,12 +4,15 @@ use vputilslib::equation_handler::EquationHandler;
 
 use crate::fem::CalcModel;
 use crate::loads::load::Load;
-use crate::loads::load_combination::CalcLoadCombination;
-use crate::structure::CalculationElement;
+use crate::loads::load_combination::{AxleTrainPosition, CalcLoadCombination};
+use crate::structure::{utils as structure_utils, CalculationElement};
 
-use super::load::CalculationLoad;
+use super::load::{CalculationLoad, PrescribedDisplacement};
 use super::{lc_utils, LoadGroup};
 
+/// The standard acceleration of gravity (m/s²)
+pub const GRAVITY: f64 = 9.81;
+
 /// Gets the element numbers that are linked to given load. Different elements are separated with , (comma).
 ///
 /// For multiple elements a 'S..E' (double dots with numbers before and after it) can be used.
@@ -153,26 +156,28 @@ pub fn extract_calculation_loads(
 ) -> Vec<CalculationLoad> {
     let mut calc_loads: Vec<CalculationLoad> = Vec::new();
     let mut temp_eq_handler = eq_handler.clone();
-    let lc_is_empty = load_combination.loads_n_factors.is_empty();
     for load in loads {
-        let mut strength_factor = 1.0;
-        // Check if the loads_n_factor contains any items. If that vector is empty, all loads
-        // will be extracted as calculation loads. Otherwise the loads will be extracted by names
-        // in the loads_n_factor vector
-        if !lc_is_empty {
-            // Check if the current load is in the loads_n_factor vector
-            if lc_utils::calc_load_is_included(load_combination, &load.name) {
-                if load_combination.loads_n_factors.contains_key(&load.name) {
-                    strength_factor = load_combination.loads_n_factors[&load.name];
-                } else if load_combination.loads_n_factors.contains_key("ALL") {
-                    strength_factor = load_combination.loads_n_factors["ALL"];
-                } else {
-                    strength_factor = 1.0;
-                }
-            } else {
-                // If current load is not in the loads_n_factor vector, skip it
-                continue;
+        // If current load is not in the loads_n_factor vector, skip it
+        let Some(strength_factor) = lc_utils::get_load_factor(load_combination, &load.name) else {
+            continue;
+        };
+        // The support displacements are linked to the nodes instead of the elements (see
+        // [extract_prescribed_displacements])
+        if load.is_support_displacement() {
+            continue;
+        }
+        // The axle trains are set only to the combinations with the train position
+        if load.load_type == super::load::LoadType::AxleTrain {
+            if let Some(position) = &load_combination.axle_train_position {
+                calc_loads.extend(extract_axle_train_loads(
+                    calc_model,
+                    load,
+                    position,
+                    strength_factor,
+                    &temp_eq_handler,
+                ));
             }
+            continue;
         }
         let rotation = load.rotation;
         let linked_elem_numbers = get_linked_element_numbers(load);
@@ -180,6 +185,14 @@ pub fn extract_calculation_loads(
             if !load_is_linked(element.model_el_num, &linked_elem_numbers) {
                 continue;
             }
+            // Moving loads that are not in the current moving load pattern are multiplied with
+            // the moving percent
+            let strength_factor = match &load_combination.moving_load_elements {
+                Some(pattern) if load.is_moving_load && !pattern.contains(&element.model_el_num) => {
+                    strength_factor * load.moving_percent / 100.0
+                }
+                _ => strength_factor,
+            };
             let name = load.name.clone();
             let element_number = element.calc_el_num;
             temp_eq_handler.set_variable("L", element.model_el_length);
@@ -193,26 +206,8 @@ pub fn extract_calculation_loads(
                 .calculate_formula(&load.strength)
                 .unwrap_or(0.0);
 
-            if load.load_type == super::load::LoadType::Trapezoid {
-                if offset_start < offset_end {
-                    if offset_end < element.offset_from_model_el
-                        || offset_start > element.offset_from_model_el + element.length
-                    {
-                        continue;
-                    }
-                } else {
-                    if offset_start < element.offset_from_model_el
-                        || offset_end > element.offset_from_model_el + element.length
-                    {
-                        continue;
-                    }
-                }
-            } else {
-                if offset_end < element.offset_from_model_el
-                    || offset_start > element.offset_from_model_el + element.length
-                {
-                    continue;
-                }
+            if !load_is_on_element(load, element, offset_start, offset_end) {
+                continue;
             }
 
             match load.load_type {
@@ -320,6 +315,22 @@ pub fn extract_calculation_loads(
                     };
                     calc_loads.push(calc_load);
                 }
+                super::load::LoadType::SelfWeight => {
+                    // The self weight is applied on the whole element and it is always pointing
+                    // downwards in global coordinates
+                    let calc_load = CalculationLoad {
+                        name,
+                        offset_start: 0.0,
+                        offset_end: element.length,
+                        strength: get_self_weight_per_length(element)
+                            * get_self_weight_multiplier(load, &temp_eq_handler)
+                            * strength_factor,
+                        rotation: -90.0,
+                        element_number,
+                        load_type: super::load::CalculationLoadType::Line,
+                    };
+                    calc_loads.push(calc_load);
+                }
                 super::load::LoadType::Thermal => {
                     // Convert the thermal coefficient to strain load
                     let thermal_coefficient = crate::material::get_thermal_expansion_coefficient(
@@ -337,6 +348,9 @@ pub fn extract_calculation_loads(
                     };
                     calc_loads.push(calc_load);
                 }
+                super::load::LoadType::SupportDisplacement
+                | super::load::LoadType::SupportRotation
+                | super::load::LoadType::AxleTrain => {}
             }
         }
     }
@@ -344,6 +358,157 @@ pub fn extract_calculation_loads(
     calc_loads
 }
 
+/// Extracts the axle loads of the axle train at the train position as point loads. The axles that
+/// are outside the path are skipped.
+fn extract_axle_train_loads(
+    calc_model: &CalcModel,
+    load: &Load,
+    position: &AxleTrainPosition,
+    strength_factor: f64,
+    eq_handler: &EquationHandler,
+) -> Vec<CalculationLoad> {
+    let path = structure_utils::get_element_path(
+        calc_model.structure_elements,
+        calc_model.structure_nodes,
+        &get_linked_element_numbers(load),
+    );
+    // The errors are reported when the train positions are created (see
+    // [lc_utils::add_axle_train_positions])
+    let (Ok(axle_loads), Ok(axle_offsets)) = (load.get_axle_loads(eq_handler), load.get_axle_offsets(eq_handler))
+    else {
+        return vec![];
+    };
+    let mut calc_loads = Vec::new();
+    for (strength, offset) in axle_loads.into_iter().zip(axle_offsets) {
+        // The other axles are behind the first axle in the direction of the travel
+        let path_position = if position.reversed {
+            position.path_position + offset
+        } else {
+            position.path_position - offset
+        };
+        let Some((model_el_num, pos_on_element)) =
+            structure_utils::get_path_location(&path, path_position)
+        else {
+            continue;
+        };
+        let Some(element) = calc_model.get_calc_element_at(model_el_num, pos_on_element) else {
+            continue;
+        };
+        calc_loads.push(CalculationLoad {
+            name: load.name.clone(),
+            load_type: super::load::CalculationLoadType::Point,
+            offset_start: pos_on_element - element.offset_from_model_el,
+            offset_end: 0.0,
+            strength: strength * 1e3 * strength_factor, // kN => N
+            rotation: load.rotation,
+            element_number: element.calc_el_num,
+        });
+    }
+    calc_loads
+}
+
+/// Extracts the prescribed displacements of the support displacement and support rotation loads
+/// for the linked nodes (-1 links the load to all the nodes). The displacements are set only to
+/// the locked degrees of freedom when the displacements are solved. The loads whose strength can
+/// not be calculated are reported and left out.
+/// * `loads` - List of loads (the other load types are skipped)
+/// * `eq_handler` - Equation handler with pre initialized variables
+pub fn extract_prescribed_displacements(
+    calc_model: &CalcModel,
+    loads: &[Load],
+    load_combination: &CalcLoadCombination,
+    eq_handler: &EquationHandler,
+) -> Vec<PrescribedDisplacement> {
+    let mut result = Vec::new();
+    for load in loads.iter().filter(|l| l.is_support_displacement()) {
+        let Some(strength_factor) = lc_utils::get_load_factor(load_combination, &load.name) else {
+            continue;
+        };
+        let strength = eq_handler.calculate_formula(&load.strength).unwrap_or(f64::NAN);
+        if !strength.is_finite() {
+            println!(
+                "Error while calculating the strength '{}' of the support displacement '{}'. The load is skipped.",
+                load.strength, load.name
+            );
+            continue;
+        }
+        let strength = strength * strength_factor;
+        let (x, z, ry) = match load.load_type {
+            super::load::LoadType::SupportRotation => (0.0, 0.0, strength),
+            _ => (
+                strength * load.rotation.to_radians().cos(),
+                strength * load.rotation.to_radians().sin(),
+                0.0,
+            ),
+        };
+        let linked_node_numbers = get_linked_element_numbers(load);
+        result.extend(
+            calc_model
+                .structure_nodes
+                .values()
+                .filter(|n| load_is_linked(n.number, &linked_node_numbers))
+                .map(|n| PrescribedDisplacement {
+                    name: load.name.clone(),
+                    node_number: n.number,
+                    x,
+                    z,
+                    ry,
+                }),
+        );
+    }
+    result
+}
+
+/// Checks if the load (with the calculated offsets) is located on the calculation element. When
+/// the model element is split into multiple calculation elements, the point loads and rotational
+/// loads are only added to one of them. If the point load is located exactly at the split
+/// position, it is added to the latter element.
+fn load_is_on_element(
+    load: &Load,
+    element: &CalculationElement,
+    offset_start: f64,
+    offset_end: f64,
+) -> bool {
+    let element_start = element.offset_from_model_el;
+    let element_end = element.offset_from_model_el + element.length;
+    match load.load_type {
+        super::load::LoadType::Point | super::load::LoadType::Rotational => {
+            let is_last = element_end >= element.model_el_length;
+            offset_start >= element_start
+                && (offset_start < element_end || (is_last && offset_start <= element_end))
+        }
+        super::load::LoadType::Line
+        | super::load::LoadType::Triangular
+        | super::load::LoadType::Trapezoid => {
+            // The triangular and trapezoid loads can be defined from right to left
+            let left = offset_start.min(offset_end);
+            let right = offset_start.max(offset_end);
+            right > element_start && left < element_end
+        }
+        super::load::LoadType::Strain
+        | super::load::LoadType::Thermal
+        | super::load::LoadType::SelfWeight => true,
+        super::load::LoadType::SupportDisplacement
+        | super::load::LoadType::SupportRotation
+        | super::load::LoadType::AxleTrain => false,
+    }
+}
+
+/// Gets the self weight of the element per unit length (N/mm). If the profile has a custom weight,
+/// it is used. Otherwise the weight is calculated from the profile area and the material density.
+pub fn get_self_weight_per_length(element: &CalculationElement) -> f64 {
+    element.get_mass_per_length() * GRAVITY
+}
+
+/// Gets the multiplier for the self weight load. Empty strength means the full self weight.
+fn get_self_weight_multiplier(load: &Load, equation_handler: &EquationHandler) -> f64 {
+    if load.strength.trim().is_empty() {
+        1.0
+    } else {
+        equation_handler.calculate_formula(&load.strength).unwrap_or(0.0)
+    }
+}
+
 fn handle_line_load_extracting(
     calc_element: &CalculationElement,
     load_name: String,
@@ -515,7 +680,16 @@ fn handle_triang_load_extracting(
 
 #[cfg(test)]
 mod tests {
-    use crate::{material::Steel, profile::PolygonProfile, structure::Release};
+    use std::collections::{BTreeMap, HashMap};
+
+    use vputilslib::geometry2d::VpPoint;
+
+    use crate::{
+        material::Steel,
+        profile::PolygonProfile,
+        settings::CalculationSettings,
+        structure::{Element, Node, Release},
+    };
 
     use super::*;
 
@@ -550,6 +724,140 @@ mod tests {
         assert_eq!(vec![-1], result4);
     }
 
+    /// Creates a 4000 mm long element that is split into two calculation elements at 2000 mm
+    fn split_element_loads(load: Load) -> Vec<CalculationLoad> {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let elements = vec![Element { number: 1, node_start: 1, node_end: 2, ..Element::default() }];
+        let (calc_elements, extra_nodes) = crate::structure::utils::get_calc_elements(
+            &elements,
+            &nodes,
+            &HashMap::from([(1, vec![2000])]),
+            &CalculationSettings::default(),
+        );
+        let calc_model = CalcModel::new(&nodes, extra_nodes, &elements, calc_elements);
+        extract_calculation_loads(
+            &calc_model,
+            &vec![load],
+            &CalcLoadCombination::default(),
+            &EquationHandler::new(),
+        )
+    }
+
+    #[test]
+    fn t_point_load_at_split_position() {
+        let load = Load::new_point_load(
+            "P".to_string(),
+            "1".to_string(),
+            "2000".to_string(),
+            "10".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let calc_loads = split_element_loads(load);
+        assert_eq!(1, calc_loads.len());
+        assert_eq!(1002, calc_loads[0].element_number);
+        assert_eq!(0.0, calc_loads[0].offset_start);
+
+        // The point load at the end of the model element is added to the last calculation element
+        let load = Load::new_point_load(
+            "P".to_string(),
+            "1".to_string(),
+            "L".to_string(),
+            "10".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let calc_loads = split_element_loads(load);
+        assert_eq!(1, calc_loads.len());
+        assert_eq!(1002, calc_loads[0].element_number);
+    }
+
+    #[test]
+    fn t_right_to_left_triangular_load_on_split_element() {
+        let load = Load::new_triangular_load(
+            "T".to_string(),
+            "1".to_string(),
+            "3000".to_string(),
+            "1000".to_string(),
+            "10".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let calc_loads = split_element_loads(load);
+        assert!(calc_loads.iter().any(|l| l.element_number == 1001));
+        assert!(calc_loads.iter().any(|l| l.element_number == 1002));
+    }
+
+    #[test]
+    fn t_extract_prescribed_displacements() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let elements = vec![Element { number: 1, node_start: 1, node_end: 2, ..Element::default() }];
+        let (calc_elements, extra_nodes) = crate::structure::utils::get_calc_elements(
+            &elements,
+            &nodes,
+            &HashMap::new(),
+            &CalculationSettings::default(),
+        );
+        let calc_model = CalcModel::new(&nodes, extra_nodes, &elements, calc_elements);
+        let loads = vec![
+            Load::new_support_displacement("S".to_string(), "2".to_string(), "10".to_string(),
+                -90.0, LoadGroup::PERMANENT),
+            Load::new_support_rotation("R".to_string(), "-1".to_string(), "0.001".to_string(),
+                LoadGroup::PERMANENT),
+            // The strength can not be calculated, so the load is left out
+            Load::new_support_displacement("E".to_string(), "1".to_string(), "abc".to_string(),
+                -90.0, LoadGroup::PERMANENT),
+            Load::new_point_load("P".to_string(), "1".to_string(), "0".to_string(), "10".to_string(),
+                -90.0, LoadGroup::PERMANENT),
+        ];
+        let result = extract_prescribed_displacements(
+            &calc_model,
+            &loads,
+            &CalcLoadCombination::default(),
+            &EquationHandler::new(),
+        );
+        assert_eq!(3, result.len());
+        assert_eq!(2, result[0].node_number);
+        assert!(result[0].x.abs() < 1e-9);
+        assert!((result[0].z + 10.0).abs() < 1e-9);
+        assert_eq!(0.0, result[0].ry);
+        assert!(result[1..].iter().all(|d| d.name == "R" && d.ry == 0.001));
+        // The support displacements are not element loads
+        let calc_loads = extract_calculation_loads(
+            &calc_model,
+            &loads,
+            &CalcLoadCombination::default(),
+            &EquationHandler::new(),
+        );
+        assert!(calc_loads.iter().all(|l| l.name == "P"));
+    }
+
+    #[test]
+    fn t_self_weight_multiplier() {
+        let eq_handler = EquationHandler::new();
+        let load = Load {
+            load_type: crate::loads::load::LoadType::SelfWeight,
+            element_numbers: "-1".to_string(),
+            ..Load::default()
+        };
+        assert_eq!(1.0, get_self_weight_multiplier(&load, &eq_handler));
+
+        // Loads from JSON without the strength
+        let mut json = serde_json::to_value(&load).unwrap();
+        json.as_object_mut().unwrap().remove("strength");
+        let load: Load = serde_json::from_value(json).unwrap();
+        assert_eq!(1.0, get_self_weight_multiplier(&load, &eq_handler));
+
+        let load = Load { strength: "0.5".to_string(), ..load };
+        assert_eq!(0.5, get_self_weight_multiplier(&load, &eq_handler));
+    }
+
     #[test]
     fn t_handle_triang_load_extracting() {
         let calc_elem: CalculationElement = CalculationElement {
@@ -570,7 +878,13 @@ mod tests {
             profile_area: 100.0 * 100.0,
             elastic_modulus: 210e3,
             major_smoa: 100.0 * 100.0f64.powi(3) / 12.0,
+            shear_modulus: 81000.0,
+            shear_area: 100.0 * 100.0 * 5.0 / 6.0,
             offset_from_model_el: 1000.0,
+            axial_force: 0.0,
+            foundation: Default::default(),
+            rigid_offset_start: (0.0, 0.0),
+            rigid_offset_end: (0.0, 0.0),
         };
         let tr_load = Load::new_triangular_load(
             "ABC".to_string(),
@@ -762,4 +1076,30 @@ mod tests {
         assert!(tr.offset_start == 800.0);
         assert!(tr.offset_end == 200.0);
     }
+
+    #[test]
+    fn t_axle_train_values() {
+        let eq_handler = EquationHandler::new();
+        let new_train = |axle_loads: &str, axle_spacings: &str| {
+            Load::new_axle_train(
+                "Crane".to_string(),
+                "1".to_string(),
+                axle_loads.to_string(),
+                axle_spacings.to_string(),
+                -90.0,
+                LoadGroup::CLASS_A,
+            )
+        };
+        let train = new_train("10; 20; 30", "1000; 500");
+        assert_eq!(train.get_axle_loads(&eq_handler).unwrap(), vec![10.0, 20.0, 30.0]);
+        assert_eq!(train.get_axle_offsets(&eq_handler).unwrap(), vec![0.0, 1000.0, 1500.0]);
+        // A single axle needs no spacings
+        assert_eq!(new_train("10", "").get_axle_offsets(&eq_handler).unwrap(), vec![0.0]);
+
+        // The values that can not be calculated are reported instead of using zeros
+        assert!(new_train("10;abc", "1000").get_axle_loads(&eq_handler).is_err());
+        assert!(new_train("10;abc", "1000").get_axle_offsets(&eq_handler).is_err());
+        assert!(new_train("10;20", "abc").get_axle_offsets(&eq_handler).is_err());
+        assert!(new_train("10;20;30", "1000").get_axle_offsets(&eq_handler).is_err());
+    }
 }
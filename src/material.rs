@@ -2,12 +2,17 @@
 mod steel;
 mod timber;
 
+pub use concrete::CementClass;
 pub use concrete::Concrete;
 pub use concrete::ConcreteCalcType;
 pub use concrete::StandardConcrete;
 use serde::{Deserialize, Serialize};
 pub use steel::Steel;
+pub use timber::LoadDurationClass;
+pub use timber::ServiceClass;
 pub use timber::Timber;
+pub use timber::TimberStrengthClass;
+pub use timber::TimberType;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "$type", content = "data")]
@@ -29,6 +34,14 @@ impl MaterialData {
     pub fn get_elastic_modulus(&self) -> f64 {
         self.value().get_elastic_modulus()
     }
+
+    pub fn get_shear_modulus(&self) -> f64 {
+        self.value().get_shear_modulus()
+    }
+
+    pub fn get_density(&self) -> f64 {
+        self.value().get_density()
+    }
 }
 
 impl Default for MaterialData {
@@ -48,4 +61,8 @@ pub fn get_thermal_expansion_coefficient(material_type: &dyn MaterialTrait) -> f
 pub trait MaterialTrait {
     fn get_thermal_expansion_coefficient(&self) -> f64;
     fn get_elastic_modulus(&self) -> f64;
+    /// Gets the shear modulus (G) of the material
+    fn get_shear_modulus(&self) -> f64;
+    /// Gets the density of the material (kg/m³). Used for the self weight loads
+    fn get_density(&self) -> f64;
 }
\ No newline at end of file
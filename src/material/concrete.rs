@@ -14,6 +14,34 @@ pub struct Concrete {
     pub char_strength: f64,
     pub reinforcement: reinforcement::ElementReinforcement,
     pub concrete_calc_type: ConcreteCalcType,
+    /// The density of the (reinforced) concrete (kg/m³)
+    #[serde(default = "default_density")]
+    pub density: f64,
+    /// The relative humidity of the ambient environment (RH, %)
+    #[serde(default = "default_relative_humidity")]
+    pub relative_humidity: f64,
+    /// The age of the concrete at the time of loading (t<sub>0</sub>, days)
+    #[serde(default = "default_age_at_loading")]
+    pub age_at_loading: f64,
+    /// The class of the cement (EN 1992-1-1 3.1.2 (6))
+    #[serde(default)]
+    pub cement_class: CementClass,
+    /// The final creep coefficient φ(∞,t<sub>0</sub>). If None, the value is calculated by
+    /// EN 1992-1-1 Annex B.
+    #[serde(default)]
+    pub creep_coefficient: Option<f64>,
+}
+
+fn default_density() -> f64 {
+    2500.0
+}
+
+fn default_relative_humidity() -> f64 {
+    50.0
+}
+
+fn default_age_at_loading() -> f64 {
+    28.0
 }
 
 impl Concrete {
@@ -24,6 +52,59 @@ impl Concrete {
     pub fn standard(t: StandardConcrete) -> Self {
         t.value()
     }
+
+    /// Gets the mean value of axial tensile strength of the concrete (f_ctm, MPa)
+    pub fn get_mean_tensile_strength(&self) -> f64 {
+        calc_mean_tensile_strength(self.char_strength)
+    }
+
+    /// Gets the final creep coefficient φ(∞,t<sub>0</sub>). The value set by the user is used if
+    /// it is given. Otherwise the value is calculated by EN 1992-1-1 Annex B.
+    /// * `notional_size` - the notional size of the cross section (h<sub>0</sub> = 2A<sub>c</sub>/u, mm)
+    pub fn get_creep_coefficient(&self, notional_size: f64) -> f64 {
+        match self.creep_coefficient {
+            Some(c) => c,
+            None => calc_creep_coefficient(
+                self.char_strength,
+                self.relative_humidity,
+                notional_size,
+                self.age_at_loading,
+                self.cement_class,
+            ),
+        }
+    }
+
+    /// Gets the final total shrinkage strain (ε<sub>cs</sub> = ε<sub>cd,∞</sub> + ε<sub>ca,∞</sub>).
+    /// The value is positive for the shortening of the concrete.
+    /// * `notional_size` - the notional size of the cross section (h<sub>0</sub> = 2A<sub>c</sub>/u, mm)
+    pub fn get_shrinkage_strain(&self, notional_size: f64) -> f64 {
+        calc_drying_shrinkage_strain(
+            self.char_strength,
+            self.relative_humidity,
+            notional_size,
+            self.cement_class,
+        ) + calc_autogenous_shrinkage_strain(self.char_strength)
+    }
+
+    /// Gets the effective modulus of elasticity for the long-term loads
+    /// (E<sub>c,eff</sub> = E<sub>cm</sub> / (1 + φ(∞,t<sub>0</sub>)), EN 1992-1-1 7.4.3 (5))
+    /// * `notional_size` - the notional size of the cross section (h<sub>0</sub> = 2A<sub>c</sub>/u, mm)
+    pub fn get_effective_elastic_modulus(&self, notional_size: f64) -> f64 {
+        self.elastic_modulus / (1.0 + self.get_creep_coefficient(notional_size))
+    }
+}
+
+/// The class of the cement by EN 1992-1-1 3.1.2 (6)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum CementClass {
+    /// Slow hardening cement (CEM 32.5 N)
+    S = 0,
+    /// Normal hardening cement (CEM 32.5 R, CEM 42.5 N)
+    #[default]
+    N = 1,
+    /// Rapid hardening cement (CEM 42.5 R, CEM 52.5 N, CEM 52.5 R)
+    R = 2,
 }
 
 pub enum StandardConcrete {
@@ -94,6 +175,93 @@ pub fn calc_mean_compressive_strength(char_strength: f64) -> f64 {
     char_strength + 8.0
 }
 
+/// Calculates the mean value of axial tensile strength of concrete by EN 1992-1-1 table 3.1
+/// (f_ctm, MPa)
+/// * `char_strength` - The characteristic strength of the concrete (f_ck, MPa)
+pub fn calc_mean_tensile_strength(char_strength: f64) -> f64 {
+    if char_strength <= 50.0 {
+        0.30 * char_strength.powf(2.0 / 3.0)
+    } else {
+        2.12 * (1.0 + calc_mean_compressive_strength(char_strength) / 10.0).ln()
+    }
+}
+
+/// Calculates the final creep coefficient φ(∞,t<sub>0</sub>) by EN 1992-1-1 Annex B. The
+/// temperature is assumed to be 20 °C.
+/// * `char_strength` - The characteristic strength of the concrete (f_ck, MPa)
+/// * `relative_humidity` - The relative humidity of the ambient environment (RH, %)
+/// * `notional_size` - The notional size of the cross section (h<sub>0</sub> = 2A<sub>c</sub>/u, mm)
+/// * `age_at_loading` - The age of the concrete at the time of loading (t<sub>0</sub>, days)
+/// * `cement_class` - The class of the cement
+pub fn calc_creep_coefficient(
+    char_strength: f64,
+    relative_humidity: f64,
+    notional_size: f64,
+    age_at_loading: f64,
+    cement_class: CementClass,
+) -> f64 {
+    let fcm = calc_mean_compressive_strength(char_strength);
+    // The effect of the relative humidity (B.3a and B.3b)
+    let rh_factor = (1.0 - relative_humidity / 100.0) / (0.1 * notional_size.cbrt());
+    let phi_rh = if fcm <= 35.0 {
+        1.0 + rh_factor
+    } else {
+        let alpha_1 = (35.0 / fcm).powf(0.7);
+        let alpha_2 = (35.0 / fcm).powf(0.2);
+        (1.0 + rh_factor * alpha_1) * alpha_2
+    };
+    // The effect of the concrete strength (B.4)
+    let beta_fcm = 16.8 / fcm.sqrt();
+    // The effect of the type of the cement on the age at loading (B.9)
+    let alpha = match cement_class {
+        CementClass::S => -1.0,
+        CementClass::N => 0.0,
+        CementClass::R => 1.0,
+    };
+    let t0 = (age_at_loading * (9.0 / (2.0 + age_at_loading.powf(1.2)) + 1.0).powf(alpha)).max(0.5);
+    // The effect of the age at loading (B.5)
+    let beta_t0 = 1.0 / (0.1 + t0.powf(0.2));
+    phi_rh * beta_fcm * beta_t0
+}
+
+/// Calculates the final drying shrinkage strain (ε<sub>cd,∞</sub> = k<sub>h</sub> * ε<sub>cd,0</sub>)
+/// by EN 1992-1-1 3.1.4 (6) and Annex B.2.
+/// * `char_strength` - The characteristic strength of the concrete (f_ck, MPa)
+/// * `relative_humidity` - The relative humidity of the ambient environment (RH, %)
+/// * `notional_size` - The notional size of the cross section (h<sub>0</sub> = 2A<sub>c</sub>/u, mm)
+/// * `cement_class` - The class of the cement
+pub fn calc_drying_shrinkage_strain(
+    char_strength: f64,
+    relative_humidity: f64,
+    notional_size: f64,
+    cement_class: CementClass,
+) -> f64 {
+    let fcm = calc_mean_compressive_strength(char_strength);
+    let (alpha_ds1, alpha_ds2) = match cement_class {
+        CementClass::S => (3.0, 0.13),
+        CementClass::N => (4.0, 0.12),
+        CementClass::R => (6.0, 0.11),
+    };
+    // B.12
+    let beta_rh = 1.55 * (1.0 - (relative_humidity / 100.0).powi(3));
+    // B.11
+    let eps_cd_0 = 0.85 * (220.0 + 110.0 * alpha_ds1) * (-alpha_ds2 * fcm / 10.0).exp() * 1e-6 * beta_rh;
+    // Table 3.3 (linear interpolation between the values)
+    let k_h = match notional_size {
+        h if h <= 100.0 => 1.0,
+        h if h <= 200.0 => 1.0 - 0.15 * (h - 100.0) / 100.0,
+        h if h <= 300.0 => 0.85 - 0.10 * (h - 200.0) / 100.0,
+        h if h <= 500.0 => 0.75 - 0.05 * (h - 300.0) / 200.0,
+        _ => 0.70,
+    };
+    k_h * eps_cd_0
+}
+
+/// Calculates the final autogenous shrinkage strain (ε<sub>ca,∞</sub>) by EN 1992-1-1 3.1.4 (6)
+/// * `char_strength` - The characteristic strength of the concrete (f_ck, MPa)
+pub fn calc_autogenous_shrinkage_strain(char_strength: f64) -> f64 {
+    2.5 * (char_strength - 10.0) * 1e-6
+}
 
 impl Default for Concrete {
     fn default() -> Self {
@@ -104,6 +272,11 @@ impl Default for Concrete {
             thermal_expansion_coefficient: 14.0e-6, 
             reinforcement: Default::default(),
             concrete_calc_type: ConcreteCalcType::WithReinforcement,
+            density: default_density(),
+            relative_humidity: default_relative_humidity(),
+            age_at_loading: default_age_at_loading(),
+            cement_class: CementClass::default(),
+            creep_coefficient: None,
         }
     }
 }
@@ -115,4 +288,42 @@ impl MaterialTrait for Concrete {
     fn get_elastic_modulus(&self) -> f64 {
         self.elastic_modulus
     }
-}
\ No newline at end of file
+    /// G = E / (2 * (1 + ν)), where ν = 0.2 for uncracked concrete (EN 1992-1-1 3.1.3)
+    fn get_shear_modulus(&self) -> f64 {
+        self.elastic_modulus / (2.0 * (1.0 + 0.2))
+    }
+    fn get_density(&self) -> f64 {
+        self.density
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_creep_coefficient() {
+        // C30/37, RH = 50 %, h0 = 187.5 mm, t0 = 28 days, cement class N
+        let fcm: f64 = 38.0;
+        let phi_rh = (1.0 + 0.5 / (0.1 * 187.5f64.cbrt()) * (35.0 / fcm).powf(0.7)) * (35.0 / fcm).powf(0.2);
+        let expected = phi_rh * 16.8 / fcm.sqrt() / (0.1 + 28f64.powf(0.2));
+        let phi = calc_creep_coefficient(30.0, 50.0, 187.5, 28.0, CementClass::N);
+        assert!((phi - expected).abs() < 1e-12);
+        assert!((phi - 2.389).abs() < 1e-3);
+        // The user given value overrides the calculated one
+        let concrete = Concrete { creep_coefficient: Some(2.0), ..Concrete::standard(StandardConcrete::C30_37) };
+        assert_eq!(concrete.get_creep_coefficient(187.5), 2.0);
+        assert!((concrete.get_effective_elastic_modulus(187.5) - concrete.elastic_modulus / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shrinkage_strain() {
+        // C30/37, RH = 50 %, h0 = 150 mm, cement class N
+        let eps_cd_0 = 0.85 * 660.0 * (-0.12f64 * 3.8).exp() * 1e-6 * 1.55 * (1.0 - 0.125);
+        let eps_cd = calc_drying_shrinkage_strain(30.0, 50.0, 150.0, CementClass::N);
+        assert!((eps_cd - 0.925 * eps_cd_0).abs() < 1e-12);
+        assert!((calc_autogenous_shrinkage_strain(30.0) - 50e-6).abs() < 1e-12);
+        let concrete = Concrete::standard(StandardConcrete::C30_37);
+        assert!((concrete.get_shrinkage_strain(600.0) - (0.70 * eps_cd_0 + 50e-6)).abs() < 1e-12);
+    }
+}
@@ -10,6 +10,13 @@ pub struct Steel {
     pub thermal_expansion_coefficient: f64,
     pub yield_strength: f64,
     pub break_strength: f64,
+    /// The density of the steel (kg/m³)
+    #[serde(default = "default_density")]
+    pub density: f64,
+}
+
+fn default_density() -> f64 {
+    7850.0
 }
 
 impl Steel {
@@ -63,6 +70,7 @@ impl Default for Steel {
             thermal_expansion_coefficient: 12.5e-6,
             yield_strength: 355.0,
             break_strength: 510.0,
+            density: default_density(),
         }
     }
 }
@@ -73,4 +81,11 @@ impl MaterialTrait for Steel {
     fn get_elastic_modulus(&self) -> f64 {
         self.elastic_modulus
     }
+    /// G = E / (2 * (1 + ν)), where ν = 0.3 (EN 1993-1-1 3.2.6)
+    fn get_shear_modulus(&self) -> f64 {
+        self.elastic_modulus / (2.0 * (1.0 + 0.3))
+    }
+    fn get_density(&self) -> f64 {
+        self.density
+    }
 }
\ No newline at end of file
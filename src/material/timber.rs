@@ -1,36 +1,238 @@
 ﻿#![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use super::MaterialTrait;
 
+/// Timber material. The strength and stiffness values are the characteristic values of the
+/// strength classes of EN 338 (solid timber) and EN 14080 (glued laminated timber). Stresses and
+/// moduli are in MPa (N/mm²) and densities in kg/m³.
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Timber {
+    /// The mean value of the modulus of elasticity parallel to the grain (E_0,mean)
     pub elastic_modulus: f64,
     pub thermal_expansion_coefficient: f64,
+    /// The type of the timber (solid or glued laminated)
+    pub timber_type: TimberType,
+    /// The service class of the timber (EN 1995-1-1 2.3.1.3)
+    pub service_class: ServiceClass,
+    /// The characteristic bending strength (f_m,k)
+    pub bending_strength: f64,
+    /// The characteristic tension strength parallel to the grain (f_t,0,k)
+    pub tension_strength_0: f64,
+    /// The characteristic tension strength perpendicular to the grain (f_t,90,k)
+    pub tension_strength_90: f64,
+    /// The characteristic compression strength parallel to the grain (f_c,0,k)
+    pub compression_strength_0: f64,
+    /// The characteristic compression strength perpendicular to the grain (f_c,90,k)
+    pub compression_strength_90: f64,
+    /// The characteristic shear strength (f_v,k)
+    pub shear_strength: f64,
+    /// The 5% fractile of the modulus of elasticity parallel to the grain (E_0,05)
+    pub elastic_modulus_005: f64,
+    /// The mean value of the modulus of elasticity perpendicular to the grain (E_90,mean)
+    pub elastic_modulus_90: f64,
+    /// The mean value of the shear modulus (G_mean)
+    pub shear_modulus: f64,
+    /// The characteristic density (ρ_k)
+    pub char_density: f64,
+    /// The mean density (ρ_mean)
+    pub mean_density: f64,
 }
 
 impl Timber {
     pub fn new() -> Self {
         Self{ ..Self::default() }
     }
-    
+
+    pub fn standard(t: TimberStrengthClass) -> Self {
+        t.value()
+    }
+
     pub fn new_c18() -> Self {
-        Self{ ..Self::default() }
+        Self::standard(TimberStrengthClass::C18)
     }
 
     pub fn new_c24() -> Self {
-        Self{ elastic_modulus: 11e3, ..Self::default() }
+        Self::standard(TimberStrengthClass::C24)
+    }
+
+    /// Gets the partial factor for the material properties (γ_M) by EN 1995-1-1 table 2.3
+    pub fn get_partial_factor(&self) -> f64 {
+        match self.timber_type {
+            TimberType::Solid => 1.3,
+            TimberType::Glulam => 1.25,
+        }
+    }
+
+    /// Gets the modification factor for the duration of load and moisture content (k_mod) by
+    /// EN 1995-1-1 table 3.1 for the service class of the timber.
+    pub fn get_kmod(&self, load_duration: LoadDurationClass) -> f64 {
+        let values = match self.service_class {
+            ServiceClass::SC1 | ServiceClass::SC2 => [0.6, 0.7, 0.8, 0.9, 1.1],
+            ServiceClass::SC3 => [0.5, 0.55, 0.65, 0.7, 0.9],
+        };
+        match load_duration {
+            LoadDurationClass::Permanent => values[0],
+            LoadDurationClass::LongTerm => values[1],
+            LoadDurationClass::MediumTerm => values[2],
+            LoadDurationClass::ShortTerm => values[3],
+            LoadDurationClass::Instantaneous => values[4],
+        }
+    }
+
+    /// Calculates the design value of the given characteristic strength
+    /// (f_d = k_mod * f_k / γ_M, EN 1995-1-1 2.4.1)
+    pub fn get_design_strength(&self, char_strength: f64, load_duration: LoadDurationClass) -> f64 {
+        self.get_kmod(load_duration) * char_strength / self.get_partial_factor()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum TimberType {
+    /// Solid (sawn) timber, EN 338
+    Solid = 0,
+    /// Glued laminated timber, EN 14080
+    Glulam = 1,
+}
+
+/// The service classes of EN 1995-1-1 2.3.1.3
+#[derive(Debug, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ServiceClass {
+    SC1 = 1,
+    SC2 = 2,
+    SC3 = 3,
+}
+
+/// The load-duration classes of EN 1995-1-1 2.3.1.2
+#[derive(Debug, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum LoadDurationClass {
+    Permanent = 0,
+    LongTerm = 1,
+    MediumTerm = 2,
+    ShortTerm = 3,
+    Instantaneous = 4,
+}
+
+/// The strength classes of EN 338 (C-classes) and EN 14080 (GL-classes)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimberStrengthClass {
+    C14,
+    C16,
+    C18,
+    C20,
+    C22,
+    C24,
+    C27,
+    C30,
+    C35,
+    C40,
+    C45,
+    C50,
+    GL20h,
+    GL22h,
+    GL24h,
+    GL26h,
+    GL28h,
+    GL30h,
+    GL32h,
+    GL20c,
+    GL22c,
+    GL24c,
+    GL26c,
+    GL28c,
+    GL30c,
+    GL32c,
+}
+impl TimberStrengthClass {
+    fn value(&self) -> Timber {
+        // f_m,k, f_t,0,k, f_t,90,k, f_c,0,k, f_c,90,k, f_v,k, E_0,mean, E_0,05, E_90,mean, G_mean, ρ_k, ρ_mean
+        match *self {
+            TimberStrengthClass::C14 => solid([14.0, 7.2, 0.4, 16.0, 2.0, 3.0, 7000.0, 4700.0, 230.0, 440.0, 290.0, 350.0]),
+            TimberStrengthClass::C16 => solid([16.0, 8.5, 0.4, 17.0, 2.2, 3.2, 8000.0, 5400.0, 270.0, 500.0, 310.0, 370.0]),
+            TimberStrengthClass::C18 => solid([18.0, 10.0, 0.4, 18.0, 2.2, 3.4, 9000.0, 6000.0, 300.0, 560.0, 320.0, 380.0]),
+            TimberStrengthClass::C20 => solid([20.0, 11.5, 0.4, 19.0, 2.3, 3.6, 9500.0, 6400.0, 320.0, 590.0, 330.0, 400.0]),
+            TimberStrengthClass::C22 => solid([22.0, 13.0, 0.4, 20.0, 2.4, 3.8, 10000.0, 6700.0, 330.0, 630.0, 340.0, 410.0]),
+            TimberStrengthClass::C24 => solid([24.0, 14.5, 0.4, 21.0, 2.5, 4.0, 11000.0, 7400.0, 370.0, 690.0, 350.0, 420.0]),
+            TimberStrengthClass::C27 => solid([27.0, 16.5, 0.4, 22.0, 2.5, 4.0, 11500.0, 7700.0, 380.0, 720.0, 360.0, 430.0]),
+            TimberStrengthClass::C30 => solid([30.0, 19.0, 0.4, 24.0, 2.7, 4.0, 12000.0, 8000.0, 400.0, 750.0, 380.0, 460.0]),
+            TimberStrengthClass::C35 => solid([35.0, 22.5, 0.4, 25.0, 2.7, 4.0, 13000.0, 8700.0, 430.0, 810.0, 390.0, 470.0]),
+            TimberStrengthClass::C40 => solid([40.0, 26.0, 0.4, 27.0, 2.8, 4.0, 14000.0, 9400.0, 470.0, 880.0, 400.0, 480.0]),
+            TimberStrengthClass::C45 => solid([45.0, 30.0, 0.4, 29.0, 2.9, 4.0, 15000.0, 10100.0, 500.0, 940.0, 410.0, 490.0]),
+            TimberStrengthClass::C50 => solid([50.0, 33.5, 0.4, 30.0, 3.0, 4.0, 16000.0, 10700.0, 530.0, 1000.0, 430.0, 520.0]),
+            TimberStrengthClass::GL20h => glulam([20.0, 16.0, 0.5, 20.0, 2.5, 3.5, 8400.0, 7000.0, 300.0, 650.0, 340.0, 370.0]),
+            TimberStrengthClass::GL22h => glulam([22.0, 17.6, 0.5, 22.0, 2.5, 3.5, 10500.0, 8800.0, 300.0, 650.0, 370.0, 410.0]),
+            TimberStrengthClass::GL24h => glulam([24.0, 19.2, 0.5, 24.0, 2.5, 3.5, 11500.0, 9600.0, 300.0, 650.0, 385.0, 420.0]),
+            TimberStrengthClass::GL26h => glulam([26.0, 20.8, 0.5, 26.0, 2.5, 3.5, 12100.0, 10100.0, 300.0, 650.0, 405.0, 445.0]),
+            TimberStrengthClass::GL28h => glulam([28.0, 22.3, 0.5, 28.0, 2.5, 3.5, 12600.0, 10500.0, 300.0, 650.0, 425.0, 460.0]),
+            TimberStrengthClass::GL30h => glulam([30.0, 24.0, 0.5, 30.0, 2.5, 3.5, 13600.0, 11300.0, 300.0, 650.0, 430.0, 480.0]),
+            TimberStrengthClass::GL32h => glulam([32.0, 25.6, 0.5, 32.0, 2.5, 3.5, 14200.0, 11800.0, 300.0, 650.0, 440.0, 490.0]),
+            TimberStrengthClass::GL20c => glulam([20.0, 15.0, 0.5, 18.5, 2.5, 3.5, 10400.0, 8600.0, 300.0, 650.0, 355.0, 390.0]),
+            TimberStrengthClass::GL22c => glulam([22.0, 16.0, 0.5, 20.0, 2.5, 3.5, 10400.0, 8600.0, 300.0, 650.0, 355.0, 390.0]),
+            TimberStrengthClass::GL24c => glulam([24.0, 17.0, 0.5, 21.5, 2.5, 3.5, 11000.0, 9100.0, 300.0, 650.0, 365.0, 400.0]),
+            TimberStrengthClass::GL26c => glulam([26.0, 19.0, 0.5, 23.5, 2.5, 3.5, 12000.0, 10000.0, 300.0, 650.0, 385.0, 420.0]),
+            TimberStrengthClass::GL28c => glulam([28.0, 19.5, 0.5, 24.0, 2.5, 3.5, 12500.0, 10400.0, 300.0, 650.0, 390.0, 420.0]),
+            TimberStrengthClass::GL30c => glulam([30.0, 19.5, 0.5, 24.5, 2.5, 3.5, 13000.0, 10800.0, 300.0, 650.0, 390.0, 430.0]),
+            TimberStrengthClass::GL32c => glulam([32.0, 19.5, 0.5, 24.5, 2.5, 3.5, 13500.0, 11200.0, 300.0, 650.0, 400.0, 440.0]),
+        }
+    }
+}
+
+fn solid(values: [f64; 12]) -> Timber {
+    new_with_values(TimberType::Solid, values)
+}
+
+fn glulam(values: [f64; 12]) -> Timber {
+    new_with_values(TimberType::Glulam, values)
+}
+
+fn new_with_values(timber_type: TimberType, values: [f64; 12]) -> Timber {
+    Timber {
+        timber_type,
+        bending_strength: values[0],
+        tension_strength_0: values[1],
+        tension_strength_90: values[2],
+        compression_strength_0: values[3],
+        compression_strength_90: values[4],
+        shear_strength: values[5],
+        elastic_modulus: values[6],
+        elastic_modulus_005: values[7],
+        elastic_modulus_90: values[8],
+        shear_modulus: values[9],
+        char_density: values[10],
+        mean_density: values[11],
+        ..Timber::default()
     }
 }
 
 impl Default for Timber {
-    /// Default values from S355
+    /// Default values from C18
     fn default() -> Self {
-        // Default thermal coefficient got from (wood, pine). Elastic modulus is from 
-        // "Eurokoodi 5 lyhennetty suunnitteluohje" "Sahatavara C18"
+        // Default thermal coefficient got from (wood, pine). Strength and stiffness values are
+        // from EN 338 "C18"
         // https://www.engineeringtoolbox.com/linear-expansion-coefficients-d_95.html
-        Self { elastic_modulus: 9e3, thermal_expansion_coefficient: 5.0e-6 }
+        Self {
+            elastic_modulus: 9e3,
+            thermal_expansion_coefficient: 5.0e-6,
+            timber_type: TimberType::Solid,
+            service_class: ServiceClass::SC1,
+            bending_strength: 18.0,
+            tension_strength_0: 10.0,
+            tension_strength_90: 0.4,
+            compression_strength_0: 18.0,
+            compression_strength_90: 2.2,
+            shear_strength: 3.4,
+            elastic_modulus_005: 6000.0,
+            elastic_modulus_90: 300.0,
+            shear_modulus: 560.0,
+            char_density: 320.0,
+            mean_density: 380.0,
+        }
     }
 }
 impl MaterialTrait for Timber {
@@ -40,4 +242,39 @@ impl MaterialTrait for Timber {
     fn get_elastic_modulus(&self) -> f64 {
         self.elastic_modulus
     }
-}
\ No newline at end of file
+    fn get_shear_modulus(&self) -> f64 {
+        self.shear_modulus
+    }
+    /// The mean density is used for the self weight (EN 1991-1-1 annex A)
+    fn get_density(&self) -> f64 {
+        self.mean_density
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strength_classes() {
+        let c24 = Timber::standard(TimberStrengthClass::C24);
+        assert_eq!(c24.elastic_modulus, 11000.0);
+        assert_eq!(c24.shear_modulus, 690.0);
+        assert_eq!(c24.timber_type, TimberType::Solid);
+        let gl30c = Timber::standard(TimberStrengthClass::GL30c);
+        assert_eq!(gl30c.bending_strength, 30.0);
+        assert_eq!(gl30c.elastic_modulus_005, 10800.0);
+        assert_eq!(gl30c.timber_type, TimberType::Glulam);
+    }
+
+    #[test]
+    fn test_design_strength() {
+        let mut c24 = Timber::new_c24();
+        let f_md = c24.get_design_strength(c24.bending_strength, LoadDurationClass::MediumTerm);
+        assert!((f_md - 0.8 * 24.0 / 1.3).abs() < 1e-9);
+        c24.service_class = ServiceClass::SC3;
+        assert_eq!(c24.get_kmod(LoadDurationClass::Permanent), 0.5);
+        let gl24h = Timber::standard(TimberStrengthClass::GL24h);
+        assert_eq!(gl24h.get_partial_factor(), 1.25);
+    }
+}
@@ -3,6 +3,7 @@ pub mod profile_polygon;
 pub mod profile_standard;
 pub mod smoa;
 pub mod area;
+pub mod shear_area;
 
 pub use profile_custom::CustomProfile;
 pub use profile_polygon::PolygonProfile;
@@ -78,6 +79,42 @@ impl Profile {
         }
     }
 
+    /// Gets the shear area of the profile in square millimeters (mm²) for the shear deformations
+    /// in the direction of the major axis bending
+    pub fn get_shear_area(&self) -> f64 {
+        match self {
+            Profile::PolygonProfile(p) => p.get_shear_area(),
+            Profile::StandardProfile(s) => s.get_shear_area(),
+            Profile::CustomProfile(c) => c.get_shear_area(),
+        }
+    }
+
+    /// Gets the perimeter of the profile in millimeters. For polygon profiles the value is
+    /// calculated from the polygon. For standard and custom profiles the perimeter of the bounding
+    /// box (2 * (width + height)) is used.
+    pub fn get_perimeter(&self) -> f64 {
+        match self {
+            Profile::PolygonProfile(p) => {
+                let points = &p.polygon.points;
+                let count = points.len();
+                (0..count)
+                    .map(|i| geometry2d::calc_length_between_points(&points[i], &points[(i + 1) % count]))
+                    .sum()
+            }
+            _ => 2.0 * (self.get_width() + self.get_height()),
+        }
+    }
+
+    /// Gets the custom weight of the profile (kg/m). Returns zero if the custom weight is not set
+    /// or the profile is a polygon profile.
+    pub fn get_custom_weight_per_meter(&self) -> f64 {
+        match self {
+            Profile::PolygonProfile(_) => 0.0,
+            Profile::StandardProfile(s) => s.custom_weight_per_meter,
+            Profile::CustomProfile(c) => c.custom_weight_per_meter,
+        }
+    }
+
     /// Gets the width of the profile
     pub fn get_width(&self) -> f64 {
         match self {
@@ -96,6 +133,33 @@ impl Profile {
         }
     }
 
+    /// Interpolates the profile between this profile (factor 0) and the other profile (factor 1).
+    /// Only the polygon profiles with the same number of points can be interpolated. The points of
+    /// the polygons are interpolated linearly, e.g. the height of a rectangular profile varies
+    /// linearly. Returns None for the other profiles.
+    pub fn interpolate(&self, other: &Profile, factor: f64) -> Option<Profile> {
+        let (Profile::PolygonProfile(start), Profile::PolygonProfile(end)) = (self, other) else {
+            return None;
+        };
+        if start.polygon.points.len() != end.polygon.points.len() {
+            return None;
+        }
+        let lerp = |a: f64, b: f64| a + (b - a) * factor;
+        let points = start
+            .polygon
+            .points
+            .iter()
+            .zip(end.polygon.points.iter())
+            .map(|(s, e)| VpPoint::new(lerp(s.x, e.x), lerp(s.y, e.y)))
+            .collect();
+        Some(Profile::PolygonProfile(PolygonProfile {
+            name: start.name.clone(),
+            height: lerp(start.height, end.height),
+            width: lerp(start.width, end.width),
+            polygon: Polygon::new(points),
+        }))
+    }
+
     /// Gets the polygon profile from the enum. Panics if the profile is not a polygon profile
     pub fn get_polygon_profile (&self) -> &PolygonProfile {
         match self {
@@ -21,6 +21,10 @@ pub struct CustomProfile {
     pub custom_minor_sec_mom_of_area: f64,
     /// Custom weight for CustomProfile or Custom profile types
     pub custom_weight_per_meter: f64,
+    /// Custom shear area for CustomProfile or Custom profile types. If zero, the shear area of
+    /// a rectangle (5/6 * A) is used
+    #[serde(default)]
+    pub custom_shear_area: f64,
     /// Custom torsional constant for CustomProfile or Custom profile types
     pub custom_torsional_constant: f64,
     /// Custom warping constant for CustomProfile or Custom profile types
@@ -45,6 +49,16 @@ impl CustomProfile {
     pub fn get_major_second_mom_of_area(&self) -> f64 {
         self.custom_major_sec_mom_of_area
     }
+
+    /// Gets the shear area of the profile in square millimeters (mm²). If the custom shear area
+    /// is not set, the shear area of a rectangle (5/6 * A) is used.
+    pub fn get_shear_area(&self) -> f64 {
+        if self.custom_shear_area > 0.0 {
+            self.custom_shear_area
+        } else {
+            5.0 / 6.0 * self.custom_area
+        }
+    }
 }
 
 impl Default for CustomProfile {
@@ -57,6 +71,7 @@ impl Default for CustomProfile {
             custom_major_sec_mom_of_area: 0.0,
             custom_minor_sec_mom_of_area: 0.0,
             custom_weight_per_meter: 0.0,
+            custom_shear_area: 0.0,
             custom_torsional_constant: 0.0,
             custom_warping_constant: 0.0,
             center_of_gravity_x: 0.0,
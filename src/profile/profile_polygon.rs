@@ -7,7 +7,7 @@ use vputilslib::geometry2d::{Polygon, VpPoint};
 use crate::material::MaterialData;
 use crate::settings::CalculationSettings;
 
-use super::{area, smoa, Profile};
+use super::{area, shear_area, smoa, Profile};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PolygonProfile {
@@ -112,6 +112,12 @@ impl PolygonProfile {
         }
     }
 
+    /// Gets the shear area of the profile in square millimeters (mm²). The value is calculated
+    /// from the polygon (the reinforcement is ignored).
+    pub fn get_shear_area(&self) -> f64 {
+        shear_area::shear_area_from_polygon(&self.polygon)
+    }
+
     
 }
 
@@ -2,7 +2,7 @@
 use serde::{Deserialize, Serialize};
 use vputilslib::geometry2d::Polygon;
 
-use super::Profile;
+use super::{shear_area, Profile};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StandardProfile {
@@ -38,6 +38,10 @@ pub struct StandardProfile {
     pub custom_minor_sec_mom_of_area: f64,
     /// Custom weight for StandardProfile or Custom profile types
     pub custom_weight_per_meter: f64,
+    /// Custom shear area for StandardProfile or Custom profile types. If zero, the shear area
+    /// is calculated from the polygon
+    #[serde(default)]
+    pub custom_shear_area: f64,
     /// Custom torsional constant for StandardProfile or Custom profile types
     pub custom_torsional_constant: f64,
     /// Custom warping constant for StandardProfile or Custom profile types
@@ -63,6 +67,19 @@ impl StandardProfile {
     pub fn get_major_second_mom_of_area(&self) -> f64 {
         self.custom_major_sec_mom_of_area
     }
+
+    /// Gets the shear area of the profile in square millimeters (mm²). If the custom shear area
+    /// is not set, the value is calculated from the polygon or if the polygon is not set,
+    /// the shear area of a rectangle (5/6 * A) is used.
+    pub fn get_shear_area(&self) -> f64 {
+        if self.custom_shear_area > 0.0 {
+            self.custom_shear_area
+        } else if self.polygon.points.len() > 2 {
+            shear_area::shear_area_from_polygon(&self.polygon)
+        } else {
+            5.0 / 6.0 * self.custom_area
+        }
+    }
 }
 
 impl Default for StandardProfile {
@@ -76,6 +93,7 @@ impl Default for StandardProfile {
             custom_major_sec_mom_of_area: 0.0,
             custom_minor_sec_mom_of_area: 0.0,
             custom_weight_per_meter: 0.0,
+            custom_shear_area: 0.0,
             custom_torsional_constant: 0.0,
             custom_warping_constant: 0.0,
             center_of_gravity_x: 0.0,
@@ -98,6 +116,7 @@ impl Clone for StandardProfile {
             custom_major_sec_mom_of_area: self.custom_major_sec_mom_of_area,
             custom_minor_sec_mom_of_area: self.custom_minor_sec_mom_of_area,
             custom_weight_per_meter: self.custom_weight_per_meter,
+            custom_shear_area: self.custom_shear_area,
             custom_torsional_constant: self.custom_torsional_constant,
             custom_warping_constant: self.custom_warping_constant,
             center_of_gravity_x: self.center_of_gravity_x,
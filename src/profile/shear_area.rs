@@ -0,0 +1,97 @@
+/// Shear area calculation methods
+use vputilslib::geometry2d::{self, Polygon};
+
+use super::smoa;
+
+/// The number of horizontal strips used in the numerical integration of the shear area
+const STRIP_COUNT: usize = 1000;
+
+/// Calculates the shear area (for the shear force in the direction of Y-axis) of the polygon.
+/// The value is calculated from the shear stress distribution of the section by
+/// A<sub>s</sub> = I<sup>2</sup> / ∫ S(y)<sup>2</sup> / b(y) dy, where S(y) is the first moment of area
+/// above y about the centroid and b(y) the width of the section at y.
+/// For a rectangle the shear area is 5/6 * A.
+pub fn shear_area_from_polygon(polygon: &Polygon) -> f64 {
+    if polygon.points.len() < 3 {
+        return 0.0;
+    }
+    let centroid_y = geometry2d::centroid_from_polygon(polygon).y;
+    let smoa = smoa::smoa_from_polygon(polygon);
+    let y_max = polygon.points.iter().map(|p| p.y).fold(f64::MIN, f64::max);
+    let y_min = polygon.points.iter().map(|p| p.y).fold(f64::MAX, f64::min);
+    let dy = (y_max - y_min) / STRIP_COUNT as f64;
+
+    // The first moment of area above the current strip
+    let mut first_moment = 0.0;
+    let mut integral = 0.0;
+    for i in 0..STRIP_COUNT {
+        let y = y_max - (i as f64 + 0.5) * dy;
+        let b = width_at(polygon, y);
+        if b <= 0.0 {
+            continue;
+        }
+        let first_moment_mid = first_moment + 0.5 * b * dy * (y - centroid_y);
+        integral += first_moment_mid.powi(2) / b * dy;
+        first_moment += b * dy * (y - centroid_y);
+    }
+    if integral <= 0.0 {
+        return geometry2d::calculate_area(polygon);
+    }
+    smoa.powi(2) / integral
+}
+
+/// Gets the width of the polygon at the horizontal line y (the sum of the lengths of the parts
+/// of the line that are inside the polygon)
+pub fn width_at(polygon: &Polygon, y: f64) -> f64 {
+    let points = &polygon.points;
+    let mut intersections: Vec<f64> = Vec::new();
+    for i in 0..points.len() {
+        let p1 = points[i];
+        let p2 = points[(i + 1) % points.len()];
+        if (p1.y <= y && y < p2.y) || (p2.y <= y && y < p1.y) {
+            intersections.push(p1.x + (y - p1.y) / (p2.y - p1.y) * (p2.x - p1.x));
+        }
+    }
+    intersections.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    intersections.chunks(2).filter(|c| c.len() == 2).map(|c| c[1] - c[0]).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use vputilslib::geometry2d::{Polygon, VpPoint};
+
+    use super::shear_area_from_polygon;
+    use crate::profile::Profile;
+
+    #[test]
+    fn test_shear_area_rectangle() {
+        let profile = Profile::new_rectangle("R200x100".to_string(), 200.0, 100.0);
+        let shear_area = profile.get_shear_area();
+        println!("Shear area: {}", shear_area);
+        assert!((shear_area - 5.0 / 6.0 * 200.0 * 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_shear_area_i_section() {
+        // I-section 300x150, flanges 10 mm, web 6 mm. The shear area is close to the area of the web.
+        let points = vec![
+            VpPoint::new(0.0, 0.0),
+            VpPoint::new(150.0, 0.0),
+            VpPoint::new(150.0, 10.0),
+            VpPoint::new(78.0, 10.0),
+            VpPoint::new(78.0, 290.0),
+            VpPoint::new(150.0, 290.0),
+            VpPoint::new(150.0, 300.0),
+            VpPoint::new(0.0, 300.0),
+            VpPoint::new(0.0, 290.0),
+            VpPoint::new(72.0, 290.0),
+            VpPoint::new(72.0, 10.0),
+            VpPoint::new(0.0, 10.0),
+            VpPoint::new(0.0, 0.0),
+        ];
+        let shear_area = shear_area_from_polygon(&Polygon::new(points));
+        println!("Shear area: {}", shear_area);
+        let web_area = 280.0 * 6.0;
+        assert!(shear_area > 0.9 * web_area && shear_area < 1.2 * web_area);
+    }
+}
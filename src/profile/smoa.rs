@@ -1,7 +1,7 @@
 /// Second moment of area calculation methods
 use vputilslib::{
     equation_handler::EquationHandler,
-    geometry2d::{self, Polygon},
+    geometry2d::{self, Polygon, VpPoint},
 };
 
 use crate::{
@@ -102,32 +102,143 @@ fn smoa_with_reinf_internal_uncracked(
     cumulative_smoa
 }
 
-/// DO NOT USE. NOT YET IMPLEMENTED
-/// Calculates the second moment of area with cracked concrete.
+/// Calculates the second moment of area with cracked concrete. The concrete on the tension side
+/// of the neutral axis is ignored and the rebars are transformed with the Es/Ec ratio. The
+/// compression is assumed to be on the top side of the section (positive bending moment).
 fn smoa_with_reinf_internal_cracked(
     profile: &PolygonProfile,
-    _conc: &Concrete,
+    concrete: &Concrete,
     _calc_settings: &CalculationSettings,
 ) -> f64 {
-    let smoa_for_polygon = smoa_from_polygon(&profile.polygon);
+    smoa_cracked(profile, concrete, true)
+}
+
+/// Calculates the second moment of area with cracked concrete about the neutral axis of the
+/// cracked section. The concrete on the tension side of the neutral axis is ignored and the rebars
+/// are transformed with the Es/Ec ratio.
+/// * `compression_on_top` - true if the top side of the section is in compression (positive bending
+///   moment), false if the bottom side is in compression (negative bending moment)
+pub fn smoa_cracked(profile: &PolygonProfile, concrete: &Concrete, compression_on_top: bool) -> f64 {
+    cracked_section(profile, concrete, compression_on_top).0
+}
 
-    // Try to find the neutral axis by iterating the neutral axis offset from top and comparing the
-    // elastic area of compression and tension sides. The sum of both sides should be as close to
-    // each other as possible. Notice that the concrete on the tension side is ignored (assumed to
-    // be cracked).
+/// Calculates the Y-coordinate of the neutral axis of the cracked section (in the coordinates of
+/// the profile polygon). See [smoa_cracked].
+/// * `compression_on_top` - true if the top side of the section is in compression (positive bending
+///   moment), false if the bottom side is in compression (negative bending moment)
+pub fn neutral_axis_cracked(profile: &PolygonProfile, concrete: &Concrete, compression_on_top: bool) -> f64 {
+    cracked_section(profile, concrete, compression_on_top).1
+}
 
-    // At the start the absolute value on the compression side should be much lower than
-    // the value on the tension side because the neutral axis is almost at the top of the section.
-    // The neutral axis is found when the value goes 'over the line' and the sum of the elastic areas
-    // is below zero. The value on the compression side is set to negative values and the value on
-    // the tension side is positive.
+/// Calculates the second moment of area and the Y-coordinate of the neutral axis of the cracked
+/// section. Returns (second moment of area, y of the neutral axis).
+fn cracked_section(profile: &PolygonProfile, concrete: &Concrete, compression_on_top: bool) -> (f64, f64) {
+    let ec = concrete.elastic_modulus;
+    // For the negative bending moment the section is mirrored about the X-axis, so that the
+    // compression is always on top
+    let sign = if compression_on_top { 1.0 } else { -1.0 };
+    let mut rebars = vec![];
+    for r in &concrete.reinforcement.main_rebars {
+        for s in r.get_calculation_rebars(profile, &EquationHandler::new()) {
+            rebars.push((sign * s.y, s.area, s.reinf_data.get_elastic_modulus() / ec));
+        }
+    }
+    // If there is no reinforcement in the concrete, the section can't be cracked
+    if rebars.is_empty() {
+        let centroid = geometry2d::centroid_from_polygon(&profile.polygon);
+        return (smoa_from_polygon(&profile.polygon), centroid.y);
+    }
+    let mut points: Vec<VpPoint> =
+        profile.polygon.points.iter().map(|p| VpPoint::new(p.x, sign * p.y)).collect();
+    if !compression_on_top {
+        // Reverse the order of the points to keep the direction of the mirrored polygon
+        points.reverse();
+    }
+    let polygon = Polygon::new(points);
+
+    let (bottom, top) = y_limits(&polygon);
+    // The first moment of area of the transformed section about the neutral axis (y_na). The value
+    // is positive when the compression side dominates. Notice that the concrete on the tension side
+    // is ignored (assumed to be cracked) and the rebars in compression 'take' the area from concrete.
+    let first_moment = |y_na: f64| -> f64 {
+        let compression = clip_polygon_above(&polygon, y_na);
+        let mut sum = 0.0;
+        if compression.points.len() > 3 {
+            let area = geometry2d::calculate_area(&compression);
+            let centroid = geometry2d::centroid_from_polygon(&compression);
+            sum += area * (centroid.y - y_na);
+        }
+        for (y, area, n) in &rebars {
+            let factor = if *y > y_na { n - 1.0 } else { *n };
+            sum += factor * area * (y - y_na);
+        }
+        sum
+    };
 
-    // If there are is no reinforcement in the concrete, the neutral axis is the centroid of the polygon.
+    // The first moment decreases monotonically when the neutral axis is moved upwards, so the
+    // neutral axis can be found by halving the interval between the bottom and the top of the section.
+    let mut low = bottom;
+    let mut high = top;
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if first_moment(mid) > 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+        if high - low < 1e-9 {
+            break;
+        }
+    }
+    let y_na = (low + high) / 2.0;
 
-    // Note that when iterating the neutral axis offset, the polygon needs to be split into two
-    // parts to calculate the area on the tension side.
+    let mut cumulative_smoa = 0.0;
+    let compression = clip_polygon_above(&polygon, y_na);
+    if compression.points.len() > 3 {
+        let area = geometry2d::calculate_area(&compression);
+        let centroid = geometry2d::centroid_from_polygon(&compression);
+        cumulative_smoa += smoa_from_polygon(&compression) + (centroid.y - y_na).powi(2) * area;
+    }
+    for (y, area, n) in &rebars {
+        let factor = if *y > y_na { n - 1.0 } else { *n };
+        let transformed_area = factor * area;
+        let diam_from_tr_area = (4.0 * transformed_area / std::f64::consts::PI).sqrt();
+        cumulative_smoa += smoa_diameter(diam_from_tr_area) + (y - y_na).powi(2) * transformed_area;
+    }
+    // Mirror the neutral axis back to the coordinates of the profile
+    (cumulative_smoa, sign * y_na)
+}
 
-    smoa_for_polygon
+/// Gets the minimum and maximum Y-coordinates of the polygon
+fn y_limits(polygon: &Polygon) -> (f64, f64) {
+    polygon.points.iter().fold((f64::MAX, f64::MIN), |(min, max), p| {
+        (min.min(p.y), max.max(p.y))
+    })
+}
+
+/// Clips the polygon with a horizontal line at `y` and returns the part above the line. The
+/// returned polygon is closed (start and end points are at the same location).
+fn clip_polygon_above(polygon: &Polygon, y: f64) -> Polygon {
+    let mut points = vec![];
+    let count = polygon.points.len();
+    for i in 0..count {
+        let cur = &polygon.points[i];
+        let next = &polygon.points[(i + 1) % count];
+        let cur_inside = cur.y >= y;
+        let next_inside = next.y >= y;
+        if cur_inside {
+            points.push(VpPoint::new(cur.x, cur.y));
+        }
+        if cur_inside != next_inside {
+            let t = (y - cur.y) / (next.y - cur.y);
+            points.push(VpPoint::new(cur.x + t * (next.x - cur.x), y));
+        }
+    }
+    if let Some(first) = points.first() {
+        let first = VpPoint::new(first.x, first.y);
+        points.push(first);
+    }
+    Polygon::new(points)
 }
 
 #[cfg(test)]
@@ -183,4 +294,50 @@ mod tests {
         );
         assert!((smoa - (58.13e12 / 25e3)).abs() < 1e7);
     }
+
+    #[test]
+    fn test_smoa_with_reinf_cracked() {
+        let profile = Profile::new_rectangle("name".to_string(), 450.0, 300.0);
+        let mut concrete = Concrete {
+            elastic_modulus: 25e3,
+            char_strength: 1.0,
+            ..Default::default()
+        };
+        concrete
+            .reinforcement
+            .main_rebars
+            .push(RebarCollection::new_bot_full(
+                ReinforcementData::Rebar(RebarData::new(500.0, 210e3)),
+                RebarDistribution::Even {
+                    diam: 20.0,
+                    count: 3,
+                    cc_start: "60".to_string(),
+                    cc_end: "60".to_string(),
+                },
+                "50-20/2".to_string(),
+            ));
+        concrete.concrete_calc_type = crate::material::ConcreteCalcType::Cracked;
+
+        let smoa = smoa::smoa_with_reinf(
+            &profile.get_polygon_profile(),
+            &concrete,
+            &CalculationSettings::default(),
+        );
+        // b*x²/2 = n*As*(d - x) => x = n*As/b * (sqrt(1 + 2*b*d/(n*As)) - 1)
+        let (b, d, n) = (300.0, 400.0, 210e3 / 25e3);
+        let n_as = n * 3.0 * std::f64::consts::PI * 20f64.powi(2) / 4.0;
+        let x = n_as / b * ((1.0 + 2.0 * b * d / n_as).sqrt() - 1.0);
+        // The own second moment of area of the transformed rebars: I = A² / (4 * pi)
+        let rebar_smoa = 3.0 * (n_as / 3.0).powi(2) / (4.0 * std::f64::consts::PI);
+        let expected = b * x.powi(3) / 3.0 + n_as * (d - x).powi(2) + rebar_smoa;
+        println!("Smoa: {}, expected: {}", smoa, expected);
+        assert!((smoa - expected).abs() / expected < 1e-6);
+        concrete.concrete_calc_type = crate::material::ConcreteCalcType::WithReinforcement;
+        let smoa_uncracked = smoa::smoa_with_reinf(
+            &profile.get_polygon_profile(),
+            &concrete,
+            &CalculationSettings::default(),
+        );
+        assert!(smoa < smoa_uncracked);
+    }
 }
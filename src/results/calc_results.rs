@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::fmt::{Debug, Formatter};
 use serde::{Deserialize, Serialize};
 use super::{InternalForceResults, NodeResults};
+use crate::loads::AxleTrainPosition;
 
 #[derive(Serialize, Deserialize)]
 pub struct CalculationResults {
@@ -10,6 +11,136 @@ pub struct CalculationResults {
     pub sub_load_comb_num: usize,
     pub node_results: NodeResults,
     pub internal_force_results: BTreeMap<i32, InternalForceResults>,
+    /// The final results with the creep and the shrinkage of the concrete elements. Calculated only
+    /// for the quasi-permanent load combinations if the long-term deflections are enabled in the
+    /// concrete deflection settings.
+    #[serde(default)]
+    pub long_term_results: Option<LongTermResults>,
+    /// The information of the second-order analysis. Only set if the analysis type is second-order.
+    /// The node results and the internal forces of the calculation results are the second-order
+    /// (amplified) results.
+    #[serde(default)]
+    pub second_order_results: Option<SecondOrderResults>,
+    /// The active tension-only and compression-only elements and one-directional supports. Only
+    /// set if the structure has any of them.
+    #[serde(default)]
+    pub active_set_results: Option<ActiveSetResults>,
+    /// The position of the axle trains. Only set if the load combination has axle train loads.
+    #[serde(default)]
+    pub axle_train_position: Option<AxleTrainPosition>,
+}
+
+/// The final active set of the tension-only and compression-only elements and the one-directional
+/// supports of a load combination
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActiveSetResults {
+    /// False if the active set still changed after the maximum number of iterations or the
+    /// structure became a mechanism
+    pub converged: bool,
+    /// The number of the iterations
+    pub iterations: usize,
+    /// The tension-only and compression-only elements mapped by the element numbers. True if the
+    /// element is active (takes forces).
+    pub elements: BTreeMap<i32, bool>,
+    /// The nodes with one-directional supports mapped by the node numbers. The values are for the
+    /// directions X, Z and the rotation. True if the support is active in the direction (the
+    /// directions without the condition are always true).
+    pub supports: BTreeMap<i32, [bool; 3]>,
+}
+
+/// The information of the second-order (P-Δ) analysis of a load combination
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecondOrderResults {
+    /// False if the displacements did not converge or the structure became unstable (the loads
+    /// exceed the critical loads). The results are not reliable if the analysis did not converge.
+    pub converged: bool,
+    /// The number of the iterations
+    pub iterations: usize,
+    /// The moment amplification factors of the elements (the ratio of the largest second-order
+    /// moment to the largest first-order moment) mapped by the element numbers
+    pub moment_amplifications: BTreeMap<i32, f64>,
+}
+
+/// The long-term (final) results of a load combination
+#[derive(Serialize, Deserialize)]
+pub struct LongTermResults {
+    pub node_results: NodeResults,
+    pub internal_force_results: BTreeMap<i32, InternalForceResults>,
+}
+
+/// The results of a single load case (the loads with the same name with the factor 1.0)
+#[derive(Serialize, Deserialize)]
+pub struct LoadCaseResults {
+    /// The name of the loads in the load case
+    pub load_name: String,
+    pub node_results: NodeResults,
+    pub internal_force_results: BTreeMap<i32, InternalForceResults>,
+}
+
+/// The results of the linear buckling analysis of a load combination. The buckling modes are
+/// solved from the axial forces of the first-order analysis of the load combination.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BucklingResults {
+    pub load_combination: String,
+    pub load_comb_num: usize,
+    pub sub_load_comb_num: usize,
+    /// False if the critical load factors did not converge in the maximum number of iterations
+    pub converged: bool,
+    /// The buckling modes in the ascending order of the critical load factors. Empty if there are
+    /// no compressed elements.
+    pub modes: Vec<BucklingMode>,
+}
+
+/// A single buckling mode of the linear buckling analysis
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BucklingMode {
+    /// The critical load factor α_cr. The structure buckles in this mode when the loads of the
+    /// load combination are multiplied by the factor (EN 1993-1-1 5.2.1).
+    pub critical_load_factor: f64,
+    /// The mode shape in the same order as the displacements of the node results (tx, tz, ry for
+    /// each node). The mode shape is normalized so that the largest translation is 1.0.
+    pub mode_shape: Vec<f64>,
+    /// The effective buckling lengths (mm) of the compressed elements mapped by the element
+    /// numbers. Calculated from the critical axial force of the element: π √(EI / (α_cr N_Ed)).
+    pub buckling_lengths: BTreeMap<i32, f64>,
+}
+
+/// The results of the modal analysis. The masses are the masses of the elements and the nodes and
+/// the masses of the loads of the load combination (if the mass load combination is set in the
+/// modal settings).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModalResults {
+    /// The name of the load combination whose loads are included in the masses. Empty if the
+    /// loads are not included.
+    pub load_combination: String,
+    pub load_comb_num: usize,
+    pub sub_load_comb_num: usize,
+    /// False if the natural frequencies did not converge in the maximum number of iterations
+    pub converged: bool,
+    /// The total mass of the structure (kg)
+    pub total_mass: f64,
+    /// The vibration modes in the ascending order of the natural frequencies
+    pub modes: Vec<VibrationMode>,
+}
+
+/// A single vibration mode of the modal analysis
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VibrationMode {
+    /// The natural frequency (Hz)
+    pub frequency: f64,
+    /// The angular frequency ω (rad/s)
+    pub angular_frequency: f64,
+    /// The mode shape in the same order as the displacements of the node results (tx, tz, ry for
+    /// each node). The mode shape is normalized so that the largest translation is 1.0.
+    pub mode_shape: Vec<f64>,
+    /// The modal participation factor in the global X-direction for the normalized mode shape
+    pub participation_factor_x: f64,
+    /// The modal participation factor in the global Z-direction for the normalized mode shape
+    pub participation_factor_z: f64,
+    /// The effective modal mass in the global X-direction (kg)
+    pub effective_mass_x: f64,
+    /// The effective modal mass in the global Z-direction (kg)
+    pub effective_mass_z: f64,
 }
 
 impl Debug for CalculationResults {
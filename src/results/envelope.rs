@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+use super::{internal_force_results::get_force_at_interpolated, CalculationResults, InternalForcePoint};
+use crate::loads::AxleTrainPosition;
+
+/// The maximum and minimum values of the results over multiple (sub) load combinations
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResultEnvelope {
+    /// The envelopes of the internal forces mapped by the element numbers
+    pub element_envelopes: BTreeMap<i32, ElementEnvelope>,
+    /// The envelopes of the support reactions and displacements mapped by the node numbers
+    pub node_envelopes: BTreeMap<i32, NodeEnvelope>,
+    /// The positions of the axle trains mapped by the sub load combination numbers. Used to get
+    /// the governing train positions of the envelope values.
+    #[serde(default)]
+    pub axle_train_positions: BTreeMap<usize, AxleTrainPosition>,
+}
+
+impl ResultEnvelope {
+    /// Creates the envelope from the given calculation results. Usually all the results from
+    /// [`crate::fem::fem_handler::calculate`], but the results can be filtered (e.g. only the results
+    /// of a single load combination or limit state).
+    pub fn new<'a>(results: impl IntoIterator<Item = &'a CalculationResults>) -> Self {
+        let mut envelope = ResultEnvelope::default();
+        for result in results {
+            envelope.add_results(result);
+        }
+        envelope
+    }
+
+    /// Gets the axle train position of the sub load combination (e.g. the position that gives the
+    /// maximum value of an envelope). None if the combination has no axle trains.
+    pub fn get_axle_train_position(&self, sub_load_comb_num: usize) -> Option<&AxleTrainPosition> {
+        self.axle_train_positions.get(&sub_load_comb_num)
+    }
+
+    /// Adds the results of a single sub load combination into the envelope
+    pub fn add_results(&mut self, results: &CalculationResults) {
+        let lc_num = results.sub_load_comb_num;
+        if let Some(position) = results.axle_train_position {
+            self.axle_train_positions.insert(lc_num, position);
+        }
+        for (elem_num, forces) in results.internal_force_results.iter() {
+            let elem_envelope = self
+                .element_envelopes
+                .entry(*elem_num)
+                .or_insert_with(|| ElementEnvelope::new(*elem_num));
+            add_force_points(&mut elem_envelope.axial_forces, &forces.axial_forces, lc_num);
+            add_force_points(&mut elem_envelope.shear_forces, &forces.shear_forces, lc_num);
+            add_force_points(&mut elem_envelope.moment_forces, &forces.moment_forces, lc_num);
+            add_force_points(&mut elem_envelope.deflections, &forces.deflections, lc_num);
+        }
+
+        let node_results = &results.node_results;
+        // The results without the node numbers (e.g. deserialized from the older versions) are
+        // numbered consecutively
+        let node_numbers: Vec<i32> = if node_results.node_numbers.is_empty() {
+            (1..=node_results.node_count as i32).collect()
+        } else {
+            node_results.node_numbers.clone()
+        };
+        for node_number in node_numbers {
+            let node_envelope = self
+                .node_envelopes
+                .entry(node_number)
+                .or_insert_with(|| NodeEnvelope::new(node_number));
+            for d in 0..node_results.dof_count {
+                add_value(
+                    &mut node_envelope.support_reactions,
+                    d,
+                    node_results.get_support_reaction(node_number, d),
+                    lc_num,
+                );
+                add_value(
+                    &mut node_envelope.displacements,
+                    d,
+                    node_results.get_global_displacement(node_number, d),
+                    lc_num,
+                );
+            }
+        }
+    }
+}
+
+/// Adds the value into the envelope values at the index. The envelope value is created with the
+/// first value.
+fn add_value(values: &mut Vec<EnvelopeValue>, index: usize, value: f64, lc_num: usize) {
+    match values.get_mut(index) {
+        Some(envelope_value) => envelope_value.update(value, lc_num),
+        None => values.push(EnvelopeValue::new(value, lc_num)),
+    }
+}
+
+/// Adds the force points into the envelope points. If the envelope is empty, the positions of the
+/// envelope points are taken from the given points. Otherwise the values are interpolated at the
+/// positions of the envelope points.
+fn add_force_points(envelope: &mut Vec<EnvelopePoint>, points: &Vec<InternalForcePoint>, lc_num: usize) {
+    if envelope.is_empty() {
+        for p in points.iter() {
+            envelope.push(EnvelopePoint {
+                pos_on_element: p.pos_on_element,
+                value: EnvelopeValue::new(p.value_y, lc_num),
+            });
+        }
+        return;
+    }
+    for (i, env_point) in envelope.iter_mut().enumerate() {
+        let value = match points.get(i) {
+            Some(p) if p.pos_on_element == env_point.pos_on_element => Some(p.value_y),
+            _ => get_force_at_interpolated(points, env_point.pos_on_element).map(|p| p.value_y),
+        };
+        if let Some(value) = value {
+            env_point.value.update(value, lc_num);
+        }
+    }
+}
+
+/// The envelopes of the internal forces of a single element
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElementEnvelope {
+    /// The element number to which the envelope is linked
+    pub element_number: i32,
+    pub axial_forces: Vec<EnvelopePoint>,
+    pub shear_forces: Vec<EnvelopePoint>,
+    pub moment_forces: Vec<EnvelopePoint>,
+    /// The envelope of the deflections in the elements local z-direction
+    pub deflections: Vec<EnvelopePoint>,
+}
+
+impl ElementEnvelope {
+    pub fn new(element_number: i32) -> Self {
+        Self {
+            element_number,
+            axial_forces: Vec::new(),
+            shear_forces: Vec::new(),
+            moment_forces: Vec::new(),
+            deflections: Vec::new(),
+        }
+    }
+}
+
+/// The envelopes of the support reactions and displacements of a single node. The values are
+/// in the order of the degrees of freedom (x, z, rotation).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeEnvelope {
+    pub node_number: i32,
+    /// The envelope of the support reactions (in the local coordinate system of the node)
+    pub support_reactions: Vec<EnvelopeValue>,
+    /// The envelope of the displacements (in the global coordinate system)
+    pub displacements: Vec<EnvelopeValue>,
+}
+
+impl NodeEnvelope {
+    /// Creates new node envelope without any values
+    pub fn new(node_number: i32) -> Self {
+        Self {
+            node_number,
+            support_reactions: Vec::new(),
+            displacements: Vec::new(),
+        }
+    }
+}
+
+/// A single point of the internal force envelope
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct EnvelopePoint {
+    /// The position on the element measured from the start of the element
+    pub pos_on_element: f64,
+    pub value: EnvelopeValue,
+}
+
+/// The maximum and minimum values and the sub load combination numbers that govern them
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct EnvelopeValue {
+    pub max: f64,
+    /// The number of the sub load combination that gives the maximum value
+    pub max_sub_load_comb_num: usize,
+    pub min: f64,
+    /// The number of the sub load combination that gives the minimum value
+    pub min_sub_load_comb_num: usize,
+}
+
+impl EnvelopeValue {
+    /// Creates new envelope value from the first value
+    pub fn new(value: f64, sub_load_comb_num: usize) -> Self {
+        Self {
+            max: value,
+            max_sub_load_comb_num: sub_load_comb_num,
+            min: value,
+            min_sub_load_comb_num: sub_load_comb_num,
+        }
+    }
+
+    /// Updates the maximum and minimum values if the given value exceeds them
+    pub fn update(&mut self, value: f64, sub_load_comb_num: usize) {
+        if value > self.max {
+            self.max = value;
+            self.max_sub_load_comb_num = sub_load_comb_num;
+        }
+        if value < self.min {
+            self.min = value;
+            self.min_sub_load_comb_num = sub_load_comb_num;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use super::ForceType;
+
+/// The response quantity of an influence line
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(tag = "$type", content = "data")]
+pub enum InfluenceQuantity {
+    /// The internal force of the model element at the position measured from the start of the
+    /// element (mm). The values are in the local coordinates of the element like in
+    /// [super::InternalForceResults] (the deflection is the value y).
+    InternalForce {
+        element_number: i32,
+        position: f64,
+        force_type: ForceType,
+    },
+    /// The support reaction of the node in the direction (0 = X, 1 = Z, 2 = moment about Y)
+    SupportReaction { node_number: i32, direction: usize },
+    /// The displacement of the node in the global coordinates in the direction (0 = X, 1 = Z,
+    /// 2 = rotation about Y)
+    Displacement { node_number: i32, direction: usize },
+}
+
+/// The influence line of a response quantity. The ordinates are the values of the quantity when
+/// the unit point load (1 kN) is at the load positions along the path elements. The values are in
+/// the units of the other results (N, Nmm, mm).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluenceLine {
+    /// The response quantity of the influence line
+    pub quantity: InfluenceQuantity,
+    /// The ordinates in the order of the path positions
+    pub ordinates: Vec<InfluenceOrdinate>,
+}
+
+/// A single ordinate of the influence line
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct InfluenceOrdinate {
+    /// The position of the unit load along the path measured from the start of the path (mm)
+    pub path_position: f64,
+    /// The model element number on which the unit load is
+    pub element_number: i32,
+    /// The position of the unit load measured from the start of the element
+    pub pos_on_element: f64,
+    /// The value of the response quantity
+    pub value: f64,
+}
+
+impl InfluenceLine {
+    /// Gets the value at the path position interpolated linearly between the ordinates. Returns
+    /// None if the position is outside the path.
+    pub fn get_value_at(&self, path_position: f64) -> Option<f64> {
+        let first = self.ordinates.first()?;
+        if path_position < first.path_position {
+            return None;
+        }
+        for pair in self.ordinates.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            if path_position <= next.path_position {
+                let length = next.path_position - prev.path_position;
+                if length <= 0.0 {
+                    return Some(next.value);
+                }
+                let factor = (path_position - prev.path_position) / length;
+                return Some(prev.value + (next.value - prev.value) * factor);
+            }
+        }
+        let last = self.ordinates.last()?;
+        (path_position == last.path_position).then_some(last.value)
+    }
+
+    /// Gets the ordinate with the maximum value
+    pub fn get_max(&self) -> Option<&InfluenceOrdinate> {
+        self.ordinates.iter().max_by(|a, b| a.value.total_cmp(&b.value))
+    }
+
+    /// Gets the ordinate with the minimum value
+    pub fn get_min(&self) -> Option<&InfluenceOrdinate> {
+        self.ordinates.iter().min_by(|a, b| a.value.total_cmp(&b.value))
+    }
+}
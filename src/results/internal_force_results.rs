@@ -8,7 +8,17 @@ pub struct InternalForceResults {
     pub axial_forces: Vec<InternalForcePoint>,
     pub shear_forces: Vec<InternalForcePoint>,
     pub moment_forces: Vec<InternalForcePoint>,
-    pub deflections: Vec<InternalForcePoint>
+    pub deflections: Vec<InternalForcePoint>,
+    /// The soil pressures (N/mm²) of the elastic foundation. The value y is the pressure
+    /// perpendicular to the element and the value x in the direction of the element axis. Empty
+    /// if the element has no foundation.
+    #[serde(default)]
+    pub soil_pressures: Vec<InternalForcePoint>,
+    /// The rotations (rad) of the element ends relative to the nodes at the start and at the end
+    /// of the element, i.e. the rotations of the released (hinged or semi-rigid) connections. Zero
+    /// if the rotation is not released.
+    #[serde(default)]
+    pub end_rotations: [f64; 2],
 }
 
 impl InternalForceResults {
@@ -18,6 +28,7 @@ impl InternalForceResults {
             ForceType::Shear => get_force_at_interpolated(&self.shear_forces, pos_on_element),
             ForceType::Moment => get_force_at_interpolated(&self.moment_forces, pos_on_element),
             ForceType::Deflection => get_force_at_interpolated(&self.deflections, pos_on_element),
+            ForceType::SoilPressure => get_force_at_interpolated(&self.soil_pressures, pos_on_element),
         }
     }
 
@@ -28,6 +39,7 @@ impl InternalForceResults {
             ForceType::Shear => self.shear_forces.iter().find(|f| f.pos_on_element >= pos_on_element),
             ForceType::Moment => self.moment_forces.iter().find(|f| f.pos_on_element >= pos_on_element),
             ForceType::Deflection => self.deflections.iter().find(|f| f.pos_on_element >= pos_on_element),
+            ForceType::SoilPressure => self.soil_pressures.iter().find(|f| f.pos_on_element >= pos_on_element),
         }
     }
 }
@@ -93,7 +105,8 @@ pub enum ForceType {
     Axial = 0,
     Shear = 1,
     Moment = 2,
-    Deflection = 3
+    Deflection = 3,
+    SoilPressure = 4,
 }
 
 #[cfg(test)]
@@ -1,9 +1,23 @@
 pub mod node_results;
 pub mod internal_force_results;
 pub mod calc_results;
+pub mod envelope;
+pub mod influence_line;
 
 pub use node_results::NodeResults;
 pub use internal_force_results::InternalForceResults;
 pub use internal_force_results::InternalForcePoint;
 pub use internal_force_results::ForceType;
-pub use calc_results::CalculationResults;
\ No newline at end of file
+pub use calc_results::ActiveSetResults;
+pub use calc_results::BucklingMode;
+pub use calc_results::BucklingResults;
+pub use calc_results::CalculationResults;
+pub use calc_results::LoadCaseResults;
+pub use calc_results::LongTermResults;
+pub use calc_results::ModalResults;
+pub use calc_results::SecondOrderResults;
+pub use calc_results::VibrationMode;
+pub use envelope::ResultEnvelope;
+pub use influence_line::InfluenceLine;
+pub use influence_line::InfluenceOrdinate;
+pub use influence_line::InfluenceQuantity;
\ No newline at end of file
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use vputilslib::equation_handler::EquationHandler;
 
 use crate::{fem::stiffness, loads::load::CalculationLoad, structure::CalculationElement};
+use crate::settings::calc_settings::AnalysisType;
 use crate::structure::Node;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,7 +17,14 @@ pub struct NodeResults {
     pub support_reactions: Vec<f64>,
     /// These displacements are guaranteed to be in the global coordinate system
     pub global_displacements: Vec<f64>,
+    /// The forces that the nodal constraints (e.g. the rigid links) apply to the nodes in the
+    /// global coordinate system. Empty if there are no constraints in the structure.
+    #[serde(default)]
+    pub constraint_forces: Vec<f64>,
     pub node_count: usize,
+    /// The numbers of the nodes of the structure model in ascending order
+    #[serde(default)]
+    pub node_numbers: Vec<i32>,
     pub dof_count: usize,
     pub equation_handler: EquationHandler,
 }
@@ -79,9 +87,11 @@ impl NodeResults {
             displacements,
             support_reactions,
             node_count,
+            node_numbers: nodes.keys().copied().collect(),
             equation_handler: copied_eq_handler,
             dof_count: 3,
             global_displacements,
+            constraint_forces: vec![],
         }
     }
 
@@ -105,7 +115,9 @@ impl NodeResults {
         self.global_displacements[((node_number - 1) * self.dof_count as i32 + dir as i32) as usize]
     }
 
-    /// Get the support reactions at given node number and direction.
+    /// Get the support reactions at given node number and direction. If the node has nodal
+    /// constraints, the reaction includes the constraint forces at the node (see
+    /// [NodeResults::get_constraint_force]).
     /// The direction is as follows:
     /// - 0 = reaction in X-axis,
     /// - 1 = reaction in Z-axis,
@@ -114,14 +126,30 @@ impl NodeResults {
         self.support_reactions[((node_number - 1) * self.dof_count as i32 + dir as i32) as usize]
     }
 
-    /// Get the local nodal force vectors for the element
+    /// Get the force that the nodal constraints apply to the node in the global coordinate system.
+    /// Returns zero if there are no constraints.
+    /// The direction is as follows:
+    /// - 0 = force in X-axis,
+    /// - 1 = force in Z-axis,
+    /// - 2 = moment about Y-axis.
+    pub fn get_constraint_force(&self, node_number: i32, dir: usize) -> f64 {
+        let index = ((node_number - 1) * self.dof_count as i32 + dir as i32) as usize;
+        self.constraint_forces.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Get the local nodal force vectors for the element. In the second-order analysis the
+    /// geometric stiffness of the element is included, so that the end forces are in equilibrium
+    /// in the deformed geometry.
     pub fn get_elem_local_nodal_force_vectors(
         &self,
         element: &CalculationElement,
         loads: &Vec<CalculationLoad>,
         settings: &crate::settings::CalculationSettings
     ) -> DMatrix<f64> {
-        let el_stiff_matrix = stiffness::get_element_stiffness_matrix(element, settings, false);
+        let mut el_stiff_matrix = stiffness::get_element_stiffness_matrix(element, settings, false);
+        if settings.analysis_type == AnalysisType::SecondOrder && element.axial_force != 0.0 {
+            el_stiff_matrix += stiffness::get_element_geometric_stiffness_matrix(element);
+        }
         let el_eq_loads = crate::fem::equivalent_loads::get_element_g_eq_loads(element, loads, settings);
         let rot_matrix = crate::fem::matrices::get_rotation_matrix(element.rotation);
         let local_displacements = self.get_elem_local_displacements(element);
@@ -129,7 +157,26 @@ impl NodeResults {
         el_stiff_matrix * local_displacements - rot_matrix * el_eq_loads
     }
 
-    /// Get the local displacement matrix for the element
+    /// Get the local displacement matrix of the element ends. The displacements of the released
+    /// directions are the displacements of the element ends instead of the nodes (e.g. the
+    /// rotation of a hinged end or the rotation of a semi-rigid connection). See
+    /// [stiffness::get_element_end_displacements].
+    pub fn get_elem_local_end_displacements(
+        &self,
+        element: &CalculationElement,
+        loads: &Vec<CalculationLoad>,
+        settings: &crate::settings::CalculationSettings
+    ) -> DMatrix<f64> {
+        let local_displacements = self.get_elem_local_displacements(element);
+        if element.releases.start_release_count() + element.releases.end_release_count() == 0 {
+            return local_displacements;
+        }
+        let el_eq_loads = crate::fem::equivalent_loads::get_element_local_eq_loads(element, loads, settings, true);
+        stiffness::get_element_end_displacements(element, settings, &local_displacements, &el_eq_loads)
+    }
+
+    /// Get the local displacement matrix for the element. The displacements are at the ends of the
+    /// element, i.e. the displacements of the nodes are moved through the rigid end offsets.
     pub fn get_elem_local_displacements(
         &self,
         element: &CalculationElement
@@ -142,6 +189,9 @@ impl NodeResults {
         for i in 0..self.dof_count {
             global_matrix[(self.dof_count + i, 0)] = self.get_global_displacement(element.node_end, i);
         }
+        if element.has_rigid_offsets() {
+            global_matrix = crate::fem::matrices::get_rigid_offset_matrix(element) * global_matrix;
+        }
         let rot_matrix = crate::fem::matrices::get_rotation_matrix(element.rotation);
 
         rot_matrix * global_matrix
@@ -1,10 +1,309 @@
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use super::PartialFactors;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CalculationSettings {
     pub calc_split_interval: CalcSplitInterval,
     pub calc_threaded: bool,
+    /// The partial factors used when creating the automatic ultimate limit state load combinations
+    #[serde(default)]
+    pub partial_factors: PartialFactors,
+    /// The beam theory used for the elements
+    #[serde(default)]
+    pub beam_theory: BeamTheory,
+    /// The settings for the deflections of the reinforced concrete elements (EN 1992-1-1 7.4.3)
+    #[serde(default)]
+    pub concrete_deflections: ConcreteDeflectionSettings,
+    /// Controls whether the load combinations are calculated by the superposition of the load cases.
+    /// Each distinct load name is solved once as a load case and the results of the load
+    /// combinations are the factored sums of the load case results. The iterations of the cracked
+    /// concrete elements and the long-term results are still calculated for each load combination.
+    /// The superposition is not used if there are axle train loads.
+    #[serde(default)]
+    pub load_case_superposition: bool,
+    /// The analysis type (first-order or second-order analysis)
+    #[serde(default)]
+    pub analysis_type: AnalysisType,
+    /// The settings for the second-order analysis
+    #[serde(default)]
+    pub second_order: SecondOrderSettings,
+    /// The settings for the linear buckling analysis
+    #[serde(default)]
+    pub buckling: BucklingSettings,
+    /// The settings for the modal analysis
+    #[serde(default)]
+    pub modal: ModalSettings,
+    /// The settings for the active set iteration of the tension-only and compression-only
+    /// elements and the one-directional supports
+    #[serde(default)]
+    pub active_set: ActiveSetSettings,
+    /// The settings for the elements with an elastic foundation
+    #[serde(default)]
+    pub foundation: FoundationSettings,
+    /// The settings for the influence lines
+    #[serde(default)]
+    pub influence_line: InfluenceLineSettings,
+    /// The settings for the axle train loads
+    #[serde(default)]
+    pub axle_trains: AxleTrainSettings,
+    /// The settings for the tapered and non-prismatic elements
+    #[serde(default)]
+    pub tapered_elements: TaperedElementSettings,
+}
+
+/// The analysis type of the calculation
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum AnalysisType {
+    /// First-order linear analysis. The equilibrium is solved in the undeformed geometry.
+    #[default]
+    FirstOrder = 0,
+    /// Second-order (P-Δ) analysis. The geometric stiffnesses of the elements are calculated from
+    /// the axial forces and the displacements are iterated until they converge. The load case
+    /// superposition is not used with the second-order analysis.
+    SecondOrder = 1,
+}
+
+/// The settings for the second-order (P-Δ) analysis
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecondOrderSettings {
+    /// The number of calculation elements each element is split into. The splitting takes the
+    /// deformations inside the elements (P-δ) into account.
+    pub segment_count: usize,
+    /// The maximum number of iterations. If the displacements have not converged after the
+    /// maximum number of iterations, the results are marked as not converged.
+    pub max_iterations: usize,
+    /// The iteration is stopped when the relative change of the displacements is smaller than
+    /// the tolerance
+    pub tolerance: f64,
+}
+
+impl Default for SecondOrderSettings {
+    fn default() -> Self {
+        Self {
+            segment_count: 4,
+            max_iterations: 30,
+            tolerance: 1e-4,
+        }
+    }
+}
+
+/// The settings for the linear buckling (eigenvalue) analysis
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BucklingSettings {
+    /// The number of the lowest buckling modes that are solved
+    pub mode_count: usize,
+    /// The number of calculation elements each element is split into. The geometric stiffness
+    /// ignores the curvature inside the calculation elements, so the elements must be split to
+    /// get the buckling modes of the single elements.
+    pub segment_count: usize,
+    /// The maximum number of the subspace iterations
+    pub max_iterations: usize,
+    /// The iteration is stopped when the relative change of the critical load factors is smaller
+    /// than the tolerance
+    pub tolerance: f64,
+}
+
+impl Default for BucklingSettings {
+    fn default() -> Self {
+        Self {
+            mode_count: 3,
+            segment_count: 10,
+            max_iterations: 50,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// The settings for the active set iteration of the tension-only and compression-only elements and
+/// the one-directional supports
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActiveSetSettings {
+    /// The maximum number of iterations. If the active set still changes after the maximum number
+    /// of iterations, the results are marked as not converged.
+    pub max_iterations: usize,
+}
+
+impl Default for ActiveSetSettings {
+    fn default() -> Self {
+        Self { max_iterations: 20 }
+    }
+}
+
+/// The settings for the elements with an elastic (Winkler) foundation
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoundationSettings {
+    /// The number of calculation elements each element with a foundation is split into. The soil
+    /// pressures are linear on each calculation element, so the accuracy of the internal forces
+    /// depends on the segment length compared to the characteristic length of the beam.
+    pub segment_count: usize,
+}
+
+impl Default for FoundationSettings {
+    fn default() -> Self {
+        Self { segment_count: 20 }
+    }
+}
+
+/// The settings for the influence lines. The unit point load is stepped along the path elements
+/// and each load position is solved as a separate load case.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluenceLineSettings {
+    /// The number of the load steps on each path element. The unit load is set at both ends of
+    /// the elements and at the step_count - 1 points between them.
+    pub step_count: usize,
+    /// The direction of the unit load in degrees (0 points towards positive X-axis and goes
+    /// counterclockwise). The default is downwards (-90).
+    pub load_rotation: f64,
+}
+
+impl Default for InfluenceLineSettings {
+    fn default() -> Self {
+        Self { step_count: 20, load_rotation: -90.0 }
+    }
+}
+
+/// The settings for the axle train loads. The trains are moved along their paths by the step and
+/// each train position is a separate sub load combination. The train enters the path with the
+/// first axle at the start of the path and leaves it with the last axle at the end of the path.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AxleTrainSettings {
+    /// The step (mm) between the train positions. The step is adjusted so that the positions are
+    /// evenly distributed.
+    pub step: f64,
+    /// Controls whether the trains are moved in both directions. If false, the trains are moved
+    /// only from the start of the path to the end.
+    pub both_directions: bool,
+}
+
+impl Default for AxleTrainSettings {
+    fn default() -> Self {
+        Self { step: 100.0, both_directions: true }
+    }
+}
+
+/// The settings for the tapered and non-prismatic elements (the elements with a section variation)
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaperedElementSettings {
+    /// The number of calculation elements each tapered element is split into. The elements are
+    /// split also at the profile stations. The section properties are constant on each
+    /// calculation element, so the accuracy of the stiffness depends on the segment count.
+    pub segment_count: usize,
+}
+
+impl Default for TaperedElementSettings {
+    fn default() -> Self {
+        Self { segment_count: 10 }
+    }
+}
+
+/// The settings for the modal analysis (the natural frequencies and the mode shapes)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModalSettings {
+    /// The number of the lowest vibration modes that are solved
+    pub mode_count: usize,
+    /// The type of the element mass matrices
+    pub mass_matrix: MassMatrixType,
+    /// The number of calculation elements each element is split into. The masses are distributed
+    /// along the elements more accurately with more segments.
+    pub segment_count: usize,
+    /// The name of the load combination whose loads are converted into masses (the downward loads
+    /// divided by the gravity). If empty, only the masses of the elements and the nodes are used.
+    pub mass_load_combination: String,
+    /// The maximum number of the subspace iterations
+    pub max_iterations: usize,
+    /// The iteration is stopped when the relative change of the eigenvalues is smaller than the
+    /// tolerance
+    pub tolerance: f64,
+}
+
+impl Default for ModalSettings {
+    fn default() -> Self {
+        Self {
+            mode_count: 3,
+            mass_matrix: MassMatrixType::Consistent,
+            segment_count: 10,
+            mass_load_combination: String::new(),
+            max_iterations: 50,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// The type of the element mass matrices
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum MassMatrixType {
+    /// The consistent mass matrix (the same shape functions as in the stiffness matrix)
+    #[default]
+    Consistent = 0,
+    /// The lumped mass matrix. Half of the mass of the element is set to the translations of
+    /// each end node.
+    Lumped = 1,
+}
+
+/// The settings for calculating the deflections of the reinforced concrete elements with the
+/// interpolation between the uncracked and the fully cracked states (EN 1992-1-1 7.4.3).
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConcreteDeflectionSettings {
+    /// Controls whether the cracking of the concrete is taken into account. Only the concrete elements
+    /// with polygon profiles and main reinforcement are affected.
+    pub enabled: bool,
+    /// The coefficient taking account of the influence of the duration of the loading or of
+    /// repeated loading on the average strain (β). 1.0 for a single short-term loading and 0.5
+    /// for sustained loads or many cycles of repeated loading.
+    pub load_duration_coefficient: f64,
+    /// The number of calculation elements each concrete element is split into. Each calculation
+    /// element has its own effective stiffness.
+    pub segment_count: usize,
+    /// The maximum number of iterations when solving the effective stiffnesses
+    pub max_iterations: usize,
+    /// The iteration is stopped when the relative change of the displacements is smaller than
+    /// the tolerance
+    pub tolerance: f64,
+    /// Controls whether the long-term deflections with the creep and the shrinkage are calculated
+    /// for the quasi-permanent load combinations (all the concrete elements are affected). The
+    /// results are in the long-term results of the calculation results.
+    pub long_term: bool,
+    /// The coefficient β for the long-term deflections (0.5 for sustained loads)
+    pub long_term_load_duration_coefficient: f64,
+}
+
+impl Default for ConcreteDeflectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            load_duration_coefficient: 1.0,
+            segment_count: 20,
+            max_iterations: 20,
+            tolerance: 1e-3,
+            long_term: false,
+            long_term_load_duration_coefficient: 0.5,
+        }
+    }
+}
+
+/// The beam theory used in the element stiffness matrices and deflection calculations
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum BeamTheory {
+    /// Euler-Bernoulli beam. The shear deformations are ignored.
+    #[default]
+    EulerBernoulli = 0,
+    /// Timoshenko beam. The shear deformations are taken into account with the shear modulus of the
+    /// material and the shear area of the profile.
+    Timoshenko = 1,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -21,6 +320,19 @@ impl Default for CalculationSettings {
         Self {
             calc_split_interval: CalcSplitInterval::Relative(0.01),
             calc_threaded: true,
+            partial_factors: PartialFactors::default(),
+            beam_theory: BeamTheory::EulerBernoulli,
+            concrete_deflections: ConcreteDeflectionSettings::default(),
+            load_case_superposition: false,
+            analysis_type: AnalysisType::FirstOrder,
+            second_order: SecondOrderSettings::default(),
+            buckling: BucklingSettings::default(),
+            modal: ModalSettings::default(),
+            active_set: ActiveSetSettings::default(),
+            foundation: FoundationSettings::default(),
+            influence_line: InfluenceLineSettings::default(),
+            axle_trains: AxleTrainSettings::default(),
+            tapered_elements: TaperedElementSettings::default(),
         }
     }
 }
\ No newline at end of file
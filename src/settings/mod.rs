@@ -1,3 +1,5 @@
 pub mod calc_settings;
+pub mod partial_factors;
 
-pub use calc_settings::CalculationSettings;
\ No newline at end of file
+pub use calc_settings::CalculationSettings;
+pub use partial_factors::PartialFactors;
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// The partial factors used when the ultimate limit state load combinations are created
+/// automatically (EN 1990 table A1.2(B)). The default values are from the Finnish national annex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialFactors {
+    /// The name of the partial factor set (e.g. the national annex)
+    pub name: String,
+    /// γG,sup. The partial factor for unfavourable permanent loads (expressions 6.10 and 6.10a)
+    pub gamma_g_sup: f64,
+    /// ξ·γG,sup. The partial factor for unfavourable permanent loads in expression 6.10b
+    pub gamma_g_sup_reduced: f64,
+    /// γG,inf. The partial factor for favourable permanent loads
+    pub gamma_g_inf: f64,
+    /// γQ. The partial factor for variable loads
+    pub gamma_q: f64,
+    /// KFI. The consequence class factor that multiplies the unfavourable loads (EN 1990 table B3)
+    pub k_fi: f64,
+    /// Which of the expressions is used for the combinations
+    pub expression: UlsExpression,
+    /// Are the variable loads included (with ψ0 values) in the expression 6.10a. Some national
+    /// annexes (e.g. Finland) use only the permanent loads in the expression 6.10a.
+    pub variable_loads_in_6_10a: bool,
+}
+
+impl PartialFactors {
+    /// The partial factors from the Finnish national annex (expressions 6.10a and 6.10b,
+    /// 6.10a without variable loads, KFI = 1.0)
+    pub fn new_finnish_na() -> Self {
+        Self {
+            name: "SFS-EN 1990 NA".to_string(),
+            gamma_g_sup: 1.35,
+            gamma_g_sup_reduced: 1.15,
+            gamma_g_inf: 0.9,
+            gamma_q: 1.5,
+            k_fi: 1.0,
+            expression: UlsExpression::Eq6_10ab,
+            variable_loads_in_6_10a: false,
+        }
+    }
+
+    /// The recommended partial factors of EN 1990 table A1.2(B) with the expression 6.10
+    pub fn new_recommended() -> Self {
+        Self {
+            name: "EN 1990".to_string(),
+            gamma_g_sup: 1.35,
+            gamma_g_sup_reduced: 0.85 * 1.35,
+            gamma_g_inf: 1.0,
+            gamma_q: 1.5,
+            k_fi: 1.0,
+            expression: UlsExpression::Eq6_10,
+            variable_loads_in_6_10a: true,
+        }
+    }
+
+    /// Gets the factor for unfavourable permanent loads in the combination with only the
+    /// permanent loads (expression 6.10a or 6.10 without variable loads)
+    pub fn get_permanent_factor(&self) -> f64 {
+        self.gamma_g_sup * self.k_fi
+    }
+
+    /// Gets the factor for unfavourable permanent loads in the combinations with leading
+    /// variable load (expression 6.10 or 6.10b)
+    pub fn get_permanent_factor_with_leading(&self) -> f64 {
+        match self.expression {
+            UlsExpression::Eq6_10 => self.gamma_g_sup * self.k_fi,
+            UlsExpression::Eq6_10ab => self.gamma_g_sup_reduced * self.k_fi,
+        }
+    }
+
+    /// Gets the factor for the variable loads
+    pub fn get_variable_factor(&self) -> f64 {
+        self.gamma_q * self.k_fi
+    }
+}
+
+impl Default for PartialFactors {
+    fn default() -> Self {
+        Self::new_finnish_na()
+    }
+}
+
+/// The expression used for the fundamental combinations (EN 1990 6.4.3.2)
+#[derive(Debug, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum UlsExpression {
+    /// Expression 6.10: γG,sup·Gk + γQ·Qk,1 + Σ γQ·ψ0,i·Qk,i
+    Eq6_10 = 0,
+    /// The less favourable of expressions 6.10a: γG,sup·Gk + Σ γQ·ψ0,i·Qk,i and
+    /// 6.10b: ξ·γG,sup·Gk + γQ·Qk,1 + Σ γQ·ψ0,i·Qk,i
+    Eq6_10ab = 1,
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// The constraint that ties the degrees of freedom of the slave node to the master node, e.g. a
+/// rigid diaphragm that links the horizontal displacements of the nodes on a floor (equal degrees
+/// of freedom) or a rigid link between an eccentric bearing and the beam axis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Constraint {
+    /// The number of the master node
+    pub master_node: i32,
+    /// The number of the slave node
+    pub slave_node: i32,
+    /// The type of the constraint
+    #[serde(default)]
+    pub constraint_type: ConstraintType,
+    /// If set to true, the translations in the global X-axis are equal (only for the equal degrees
+    /// of freedom constraints)
+    #[serde(default)]
+    pub tx: bool,
+    /// If set to true, the translations in the global Z-axis are equal (only for the equal degrees
+    /// of freedom constraints)
+    #[serde(default)]
+    pub tz: bool,
+    /// If set to true, the rotations about the global Y-axis are equal (only for the equal degrees
+    /// of freedom constraints)
+    #[serde(default)]
+    pub ry: bool,
+}
+
+/// The type of the constraint between two nodes
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ConstraintType {
+    /// The selected degrees of freedom of the slave node are equal to the master node
+    #[default]
+    EqualDof = 0,
+    /// The slave node is connected to the master node with a rigid bar. The slave node moves
+    /// with the translations and the rotation of the master node and it has the same rotation.
+    RigidLink = 1,
+}
+
+impl Constraint {
+    /// Creates new equal degrees of freedom constraint between the nodes
+    pub fn new_equal_dof(master_node: i32, slave_node: i32, tx: bool, tz: bool, ry: bool) -> Self {
+        Self {
+            master_node,
+            slave_node,
+            constraint_type: ConstraintType::EqualDof,
+            tx,
+            tz,
+            ry,
+        }
+    }
+
+    /// Creates new rigid link between the nodes
+    pub fn new_rigid_link(master_node: i32, slave_node: i32) -> Self {
+        Self {
+            master_node,
+            slave_node,
+            constraint_type: ConstraintType::RigidLink,
+            tx: true,
+            tz: true,
+            ry: true,
+        }
+    }
+
+    /// Returns true if the degree of freedom of the slave node is tied to the master node at
+    /// given index (0=tx, 1=tz, 2=ry). All the degrees of freedom of a rigid link are tied.
+    pub fn is_tied(&self, index: usize) -> bool {
+        if self.constraint_type == ConstraintType::RigidLink {
+            return true;
+        }
+        match index {
+            0 => self.tx,
+            1 => self.tz,
+            2 => self.ry,
+            _ => panic!(
+                "Tried to get degree of freedom from constraint outside of degrees of freedom count!"
+            ),
+        }
+    }
+}
@@ -1,14 +1,16 @@
 ﻿#![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 use crate::material;
 use crate::material::*;
 use crate::profile::Profile;
-use crate::settings::CalculationSettings;
+use crate::settings::{calc_settings::BeamTheory, CalculationSettings};
 use crate::structure::node::Node;
 use std::collections::BTreeMap;
 use crate::structure::release::Release;
+use vputilslib::geometry2d::{self, VpPoint};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Element {
@@ -18,6 +20,249 @@ pub struct Element {
     pub material: MaterialData,
     pub profile: Profile,
     pub releases: Release,
+    /// The behaviour of the element (e.g. tension-only bracing)
+    #[serde(default)]
+    pub behaviour: ElementBehaviour,
+    /// The elastic foundation of the element (e.g. the soil under a ground beam)
+    #[serde(default)]
+    pub foundation: ElasticFoundation,
+    /// The rigid end offsets of the element (e.g. a beam that frames into the face of a column or
+    /// a member that is eccentric to the system line)
+    #[serde(default)]
+    pub rigid_offsets: RigidOffsets,
+    /// The variation of the cross-section along the element (e.g. a haunched rafter or a tapered
+    /// glulam beam). The profile of the element is the profile at the start of the element.
+    #[serde(default)]
+    pub section_variation: SectionVariation,
+}
+
+/// The behaviour of the element. The tension-only and compression-only elements are solved
+/// iteratively for each load combination. The elements that violate their condition are removed
+/// from the stiffness matrix until the set of the active elements is stable.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ElementBehaviour {
+    /// The element takes both tension and compression
+    #[default]
+    Normal = 0,
+    /// The element takes only tension (e.g. a bracing rod)
+    TensionOnly = 1,
+    /// The element takes only compression (e.g. a contact strut)
+    CompressionOnly = 2,
+}
+
+/// The elastic (Winkler) foundation of the element. The foundation is modelled as continuous
+/// springs along the element, e.g. the soil under a ground beam or a strip footing. The foundation
+/// is active if the width and either of the subgrade moduli are greater than zero.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ElasticFoundation {
+    /// The subgrade modulus perpendicular to the element (kN/m³)
+    pub subgrade_modulus: f64,
+    /// The subgrade modulus in the direction of the element axis (kN/m³), e.g. the horizontal
+    /// resistance of a ground beam. Zero if the foundation resists only the displacements
+    /// perpendicular to the element.
+    pub horizontal_subgrade_modulus: f64,
+    /// The contact width of the foundation (mm)
+    pub width: f64,
+}
+
+impl ElasticFoundation {
+    /// Gets the stiffnesses of the foundation per unit length (N/mm²) perpendicular to the element
+    /// and in the direction of the element axis
+    pub fn get_stiffness(&self) -> (f64, f64) {
+        // kN/m³ => N/mm³
+        (
+            self.subgrade_modulus * 1e-6 * self.width,
+            self.horizontal_subgrade_modulus * 1e-6 * self.width,
+        )
+    }
+
+    /// Returns true if the foundation has any stiffness
+    pub fn is_active(&self) -> bool {
+        let (perpendicular, axial) = self.get_stiffness();
+        perpendicular > 0.0 || axial > 0.0
+    }
+
+    /// Gets the soil pressures (N/mm²) perpendicular to the element and in the direction of the
+    /// element axis from the local displacements of the element. The pressures are positive when
+    /// the element moves in the negative direction of the local axes.
+    pub fn get_pressures(&self, deflection: f64, axial_deformation: f64) -> (f64, f64) {
+        // kN/m³ => N/mm³
+        (
+            -self.subgrade_modulus * 1e-6 * deflection,
+            -self.horizontal_subgrade_modulus * 1e-6 * axial_deformation,
+        )
+    }
+}
+
+/// The rigid end offsets of the element. The offsets are the vectors from the nodes to the ends
+/// of the flexible part of the element (mm). The flexible part is connected to the nodes with
+/// rigid links, so its stiffness and loads are transformed to the nodes through the offsets. The
+/// load positions, the internal forces and the deflections are along the flexible part.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RigidOffsets {
+    /// The offset of the start of the flexible part from the start node in the X-axis
+    pub start_x: f64,
+    /// The offset of the start of the flexible part from the start node in the Z-axis
+    pub start_z: f64,
+    /// The offset of the end of the flexible part from the end node in the X-axis
+    pub end_x: f64,
+    /// The offset of the end of the flexible part from the end node in the Z-axis
+    pub end_z: f64,
+    /// The coordinate system of the offsets
+    pub coordinate_system: OffsetCoordinateSystem,
+}
+
+/// The coordinate system of the rigid end offsets
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum OffsetCoordinateSystem {
+    /// The X-axis points from the start node to the end node and the Z-axis is perpendicular to
+    /// it (e.g. a positive start X-offset and a negative end X-offset shorten the element)
+    #[default]
+    Local = 0,
+    /// The global coordinate system
+    Global = 1,
+}
+
+impl RigidOffsets {
+    /// Returns true if any of the offsets is not zero
+    pub fn is_active(&self) -> bool {
+        self.start_x != 0.0 || self.start_z != 0.0 || self.end_x != 0.0 || self.end_z != 0.0
+    }
+
+    /// Gets the start and end offsets as the (X, Z) vectors in the global coordinate system
+    /// * `rotation` - the rotation of the line from the start node to the end node in angles (°)
+    pub fn get_global_offsets(&self, rotation: f64) -> [(f64, f64); 2] {
+        match self.coordinate_system {
+            OffsetCoordinateSystem::Global => {
+                [(self.start_x, self.start_z), (self.end_x, self.end_z)]
+            }
+            OffsetCoordinateSystem::Local => {
+                let (s, c) = rotation.to_radians().sin_cos();
+                [
+                    (c * self.start_x - s * self.start_z, s * self.start_x + c * self.start_z),
+                    (c * self.end_x - s * self.end_z, s * self.end_x + c * self.end_z),
+                ]
+            }
+        }
+    }
+}
+
+/// The variation of the cross-section along the tapered or non-prismatic element. The section
+/// varies linearly between the profile of the element (at the start), the profiles of the stations
+/// and the end profile. After the last station the section is constant. The element is split into
+/// calculation elements with the section properties at the middle of each calculation element
+/// (see [crate::settings::calc_settings::TaperedElementSettings]).
+///
+/// Note that the cracking and the long-term deflections of the reinforced concrete elements are not
+/// calculated for the tapered elements (see [crate::fem::concrete_deflection::ConcreteProperties]).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SectionVariation {
+    /// The profile at the end of the element. None if the section does not vary to the end of the
+    /// element.
+    pub end_profile: Option<Profile>,
+    /// The profiles at the stations between the start and the end of the element
+    pub stations: Vec<ProfileStation>,
+}
+
+/// The profile at the station along the tapered element
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileStation {
+    /// The position of the station measured from the start of the element (mm)
+    pub position: f64,
+    /// The profile at the station
+    pub profile: Profile,
+}
+
+impl SectionVariation {
+    /// Creates new section variation that varies linearly from the profile of the element to the
+    /// end profile
+    pub fn new_tapered(end_profile: Profile) -> Self {
+        Self { end_profile: Some(end_profile), stations: vec![] }
+    }
+
+    /// Returns true if the section varies along the element
+    pub fn is_active(&self) -> bool {
+        self.end_profile.is_some() || !self.stations.is_empty()
+    }
+
+    /// Gets the split positions (mm) of the tapered element: the equal segments by the segment
+    /// count and the positions of the stations. Returns an empty vector if the section does not
+    /// vary.
+    pub fn get_split_positions(&self, length: f64, segment_count: usize) -> Vec<i64> {
+        if !self.is_active() {
+            return vec![];
+        }
+        let segment_count = segment_count.max(1);
+        let mut positions: Vec<i64> = (1..segment_count)
+            .map(|i| (length * i as f64 / segment_count as f64).round() as i64)
+            .collect();
+        for station in &self.stations {
+            let position = station.position.round() as i64;
+            if position > 0 && (position as f64) < length.round() && !positions.contains(&position) {
+                positions.push(position);
+            }
+        }
+        positions
+    }
+
+    /// Gets the section properties (area, second moment of area, shear area) at the position
+    /// measured from the start of the element. The polygon profiles with the same number of points
+    /// are interpolated by their points (see [Profile::interpolate]). Otherwise the area and the
+    /// shear area are interpolated linearly and the second moment of area by its cube root (exact
+    /// for a rectangular section whose height varies linearly). The shear area is calculated only
+    /// for the Timoshenko beam theory (zero otherwise).
+    /// * `profile` - the profile of the element (the profile at the start)
+    /// * `length` - the length of the element
+    pub fn get_section_properties_at(
+        &self,
+        profile: &Profile,
+        material: &MaterialData,
+        length: f64,
+        position: f64,
+        calc_settings: &CalculationSettings,
+    ) -> (f64, f64, f64) {
+        let get_properties = |p: &Profile| {
+            (
+                p.get_area(material, calc_settings),
+                p.get_major_second_mom_of_area(material, calc_settings),
+                get_shear_area(p, calc_settings),
+            )
+        };
+        let mut stations: Vec<(f64, &Profile)> = vec![(0.0, profile)];
+        stations.extend(
+            self.stations
+                .iter()
+                .filter(|s| s.position > 0.0 && s.position < length)
+                .map(|s| (s.position, &s.profile)),
+        );
+        stations.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some(end_profile) = &self.end_profile {
+            stations.push((length, end_profile));
+        }
+        let position = position.clamp(0.0, length);
+        let Some(next) = stations.iter().position(|(p, _)| *p > position) else {
+            return get_properties(stations.last().unwrap().1);
+        };
+        let (start_pos, start) = stations[next - 1];
+        let (end_pos, end) = stations[next];
+        let factor = (position - start_pos) / (end_pos - start_pos);
+        if let Some(p) = start.interpolate(end, factor) {
+            return get_properties(&p);
+        }
+        let (start_area, start_smoa, start_shear_area) = get_properties(start);
+        let (end_area, end_smoa, end_shear_area) = get_properties(end);
+        let lerp = |a: f64, b: f64| a + (b - a) * factor;
+        (
+            lerp(start_area, end_area),
+            lerp(start_smoa.cbrt(), end_smoa.cbrt()).powi(3),
+            lerp(start_shear_area, end_shear_area),
+        )
+    }
 }
 
 impl Element {
@@ -35,21 +280,46 @@ impl Element {
             profile,
             material,
             releases: Release::new(),
+            behaviour: ElementBehaviour::Normal,
+            foundation: ElasticFoundation::default(),
+            rigid_offsets: RigidOffsets::default(),
+            section_variation: SectionVariation::default(),
         }
     }
 
-    /// Gets the elements length in millimeters (mm)
+    /// Gets the elements length in millimeters (mm). With the rigid end offsets this is the length
+    /// of the flexible part of the element.
     pub fn get_length(&self, nodes: &BTreeMap<i32, Node>) -> f64 {
+        let (start, end) = self.get_end_points(nodes);
+        geometry2d::calc_length_between_points(&start, &end)
+    }
+
+    /// Gets the elements rotation in angles (°). With the rigid end offsets this is the rotation
+    /// of the flexible part of the element.
+    pub fn get_rotation(&self, nodes: &BTreeMap<i32, Node>) -> f64 {
+        let (start, end) = self.get_end_points(nodes);
+        geometry2d::get_angle_from_points(&start, &end)
+    }
+
+    /// Gets the rigid end offsets of the element as the (X, Z) vectors in the global coordinate
+    /// system. See [RigidOffsets].
+    pub fn get_rigid_offsets(&self, nodes: &BTreeMap<i32, Node>) -> [(f64, f64); 2] {
         let node_start = &nodes[&(self.node_start)];
         let node_end = &nodes[&(self.node_end)];
-        vputilslib::geometry2d::calc_length_between_points(&node_start.point, &node_end.point)
+        let rotation = geometry2d::get_angle_from_points(&node_start.point, &node_end.point);
+        self.rigid_offsets.get_global_offsets(rotation)
     }
 
-    /// Gets the elements rotation in angles (°)
-    pub fn get_rotation(&self, nodes: &BTreeMap<i32, Node>) -> f64 {
+    /// Gets the start and end points of the flexible part of the element (the points of the nodes
+    /// moved by the rigid end offsets)
+    pub fn get_end_points(&self, nodes: &BTreeMap<i32, Node>) -> (VpPoint, VpPoint) {
         let node_start = &nodes[&(self.node_start)];
         let node_end = &nodes[&(self.node_end)];
-        vputilslib::geometry2d::get_angle_from_points(&node_start.point, &node_end.point)
+        let [(start_x, start_z), (end_x, end_z)] = self.get_rigid_offsets(nodes);
+        (
+            VpPoint::new(node_start.point.x + start_x, node_start.point.y + start_z),
+            VpPoint::new(node_end.point.x + end_x, node_end.point.y + end_z),
+        )
     }
 
     pub fn get_elastic_modulus(&self) -> f64 {
@@ -66,11 +336,15 @@ impl Default for Element {
             profile: Profile::PolygonProfile(crate::profile::PolygonProfile::new_rectangle("R100x100".to_string(), 100.0, 100.0)),
             material: MaterialData::Steel(Steel::new(210000.0)),
             releases: Release::new(),
+            behaviour: ElementBehaviour::Normal,
+            foundation: ElasticFoundation::default(),
+            rigid_offsets: RigidOffsets::default(),
+            section_variation: SectionVariation::default(),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CalculationElement<'a> {
     pub calc_el_num: i32,
     pub model_el_num: i32,
@@ -85,12 +359,31 @@ pub struct CalculationElement<'a> {
     pub profile_area: f64,
     pub elastic_modulus: f64,
     pub major_smoa: f64, 
+    /// The shear modulus of the material (G)
+    pub shear_modulus: f64,
+    /// The shear area of the profile (A<sub>s</sub>). The shear area is calculated only for the
+    /// Timoshenko beam theory, otherwise it is zero.
+    pub shear_area: f64,
     pub offset_from_model_el: f64,
+    /// The axial force of the element for the geometric stiffness in the second-order analysis
+    /// (tension is positive). Zero in the first-order analysis.
+    pub axial_force: f64,
+    /// The elastic foundation of the model element
+    pub foundation: ElasticFoundation,
+    /// The rigid offset from the start node to the start of the calculation element as a (X, Z)
+    /// vector in the global coordinate system. Zero for the calculation elements that do not
+    /// start at the start of the model element.
+    pub rigid_offset_start: (f64, f64),
+    /// The rigid offset from the end node to the end of the calculation element as a (X, Z)
+    /// vector in the global coordinate system. Zero for the calculation elements that do not end
+    /// at the end of the model element.
+    pub rigid_offset_end: (f64, f64),
 }
 
 impl<'a> CalculationElement<'a> {
     pub fn from(element: &'a Element, structure_nodes: &BTreeMap<i32, Node>, number: i32, calc_settings: &CalculationSettings) -> Self {
         let el_length = element.get_length(structure_nodes);
+        let [rigid_offset_start, rigid_offset_end] = element.get_rigid_offsets(structure_nodes);
         Self {
             calc_el_num: number,
             model_el_num: element.number,
@@ -108,15 +401,87 @@ impl<'a> CalculationElement<'a> {
                 &element.material, 
                 calc_settings
             ),
+            shear_modulus: element.material.get_shear_modulus(),
+            shear_area: get_shear_area(&element.profile, calc_settings),
             offset_from_model_el: 0.0,
+            axial_force: 0.0,
+            foundation: element.foundation,
+            rigid_offset_start,
+            rigid_offset_end,
         }   
     }
+
+    /// Sets the section properties of the tapered model element (see [SectionVariation]) at the
+    /// middle of the calculation element
+    pub fn set_section_variation(&mut self, element: &Element, calc_settings: &CalculationSettings) {
+        let (area, smoa, shear_area) = element.section_variation.get_section_properties_at(
+            &element.profile,
+            &element.material,
+            self.model_el_length,
+            self.offset_from_model_el + self.length / 2.0,
+            calc_settings,
+        );
+        self.profile_area = area;
+        self.major_smoa = smoa;
+        self.shear_area = shear_area;
+    }
+
+    /// Returns true if the calculation element has rigid offsets at either end
+    pub fn has_rigid_offsets(&self) -> bool {
+        self.rigid_offset_start != (0.0, 0.0) || self.rigid_offset_end != (0.0, 0.0)
+    }
+
+    /// Gets the mass of the element per unit length (kg/mm). If the profile has a custom weight,
+    /// it is used. Otherwise the mass is calculated from the profile area and the material density.
+    pub fn get_mass_per_length(&self) -> f64 {
+        let custom_weight = self.profile.get_custom_weight_per_meter();
+        if custom_weight > 0.0 {
+            // kg/m => kg/mm
+            custom_weight * 1e-3
+        } else {
+            // mm² * kg/m³ => kg/mm
+            self.profile_area * self.material.get_density() * 1e-9
+        }
+    }
+
+    /// Gets the shear deformation factor Φ = 12EI / (G A<sub>s</sub> L²) of the element. Returns zero
+    /// if the shear deformations are ignored (Euler-Bernoulli beam theory or shear stiffness is not
+    /// available).
+    pub fn get_shear_deformation_factor(&self, settings: &CalculationSettings) -> f64 {
+        let ga_s = self.get_shear_stiffness(settings);
+        if ga_s <= 0.0 {
+            return 0.0;
+        }
+        12.0 * self.elastic_modulus * self.major_smoa / (ga_s * self.length.powi(2))
+    }
+
+    /// Gets the shear stiffness G A<sub>s</sub> of the element. Returns zero if the shear deformations
+    /// are ignored (Euler-Bernoulli beam theory).
+    pub fn get_shear_stiffness(&self, settings: &CalculationSettings) -> f64 {
+        match settings.beam_theory {
+            BeamTheory::EulerBernoulli => 0.0,
+            BeamTheory::Timoshenko => self.shear_modulus * self.shear_area,
+        }
+    }
+}
+
+/// Gets the shear area of the element profile for the Timoshenko beam theory. The shear area of
+/// the polygon profiles is integrated numerically, so it is not calculated if the shear
+/// deformations are ignored.
+fn get_shear_area(profile: &Profile, calc_settings: &CalculationSettings) -> f64 {
+    match calc_settings.beam_theory {
+        BeamTheory::EulerBernoulli => 0.0,
+        BeamTheory::Timoshenko => profile.get_shear_area(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::material::*;
-    use crate::structure::element::{Element, MaterialData};
+    use crate::settings::{calc_settings::BeamTheory, CalculationSettings};
+    use crate::structure::element::{
+        CalculationElement, Element, MaterialData, ProfileStation, RigidOffsets, SectionVariation,
+    };
     use crate::structure::node::Node;
     use crate::profile::{Profile, CustomProfile};
     use std::collections::BTreeMap;
@@ -142,4 +507,80 @@ mod tests {
         );
         assert_eq!(e1.get_length(&nodes), 4000.0);
     }
+
+    #[test]
+    fn shear_area_by_beam_theory() {
+        let mut nodes: BTreeMap<i32, Node> = BTreeMap::new();
+        nodes.insert(1, Node::new_hinged(1, VpPoint::new(0.0, 0.0)));
+        nodes.insert(2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0)));
+        let e1 = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R200x100".to_string(), 200.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        let settings = CalculationSettings::default();
+        assert_eq!(CalculationElement::from(&e1, &nodes, 1, &settings).shear_area, 0.0);
+        let settings = CalculationSettings { beam_theory: BeamTheory::Timoshenko, ..Default::default() };
+        let shear_area = CalculationElement::from(&e1, &nodes, 1, &settings).shear_area;
+        assert!((shear_area / (200.0 * 100.0 * 5.0 / 6.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn rigid_offsets() {
+        let mut nodes: BTreeMap<i32, Node> = BTreeMap::new();
+        nodes.insert(1, Node::new_hinged(1, VpPoint::new(0.0, 0.0)));
+        nodes.insert(2, Node::new_hinged(2, VpPoint::new(0.0, 4000.0)));
+
+        let mut e1 = Element::default();
+        // The local offsets of a vertical element are along the global Z-axis
+        e1.rigid_offsets = RigidOffsets { start_x: 500.0, end_x: -500.0, ..RigidOffsets::default() };
+        let (start, end) = e1.get_end_points(&nodes);
+        assert!(start.x.abs() < 1e-9 && (start.y - 500.0).abs() < 1e-9);
+        assert!(end.x.abs() < 1e-9 && (end.y - 3500.0).abs() < 1e-9);
+        assert!((e1.get_length(&nodes) - 3000.0).abs() < 1e-9);
+        assert!((e1.get_rotation(&nodes) - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn section_variation() {
+        let settings = CalculationSettings::default();
+        let material = MaterialData::Steel(Steel::new(210e3));
+        let profile = Profile::new_rectangle("R400x100".to_string(), 400.0, 100.0);
+        let end_profile = Profile::new_rectangle("R200x100".to_string(), 200.0, 100.0);
+
+        // The polygons are interpolated, so the section at the middle is a 300x100 rectangle
+        let tapered = SectionVariation::new_tapered(end_profile.clone());
+        let (area, smoa, shear_area) = tapered.get_section_properties_at(&profile, &material, 4000.0, 2000.0, &settings);
+        assert!((area - 30000.0).abs() < 1e-6);
+        assert!((smoa / (100.0 * 300f64.powi(3) / 12.0) - 1.0).abs() < 1e-9);
+        // The shear area is only needed for the Timoshenko beam theory
+        assert_eq!(shear_area, 0.0);
+        let timoshenko = CalculationSettings { beam_theory: BeamTheory::Timoshenko, ..Default::default() };
+        let (_, _, shear_area) = tapered.get_section_properties_at(&profile, &material, 4000.0, 2000.0, &timoshenko);
+        assert!((shear_area / (30000.0 * 5.0 / 6.0) - 1.0).abs() < 0.01);
+
+        // The haunch ends at the station and the section is constant after it
+        let haunched = SectionVariation {
+            end_profile: None,
+            stations: vec![ProfileStation { position: 1500.0, profile: end_profile }],
+        };
+        let (area, _, _) = haunched.get_section_properties_at(&profile, &material, 4000.0, 3000.0, &settings);
+        assert!((area - 20000.0).abs() < 1e-6);
+        assert_eq!(haunched.get_split_positions(4000.0, 4), vec![1000, 2000, 3000, 1500]);
+        assert!(SectionVariation::default().get_split_positions(4000.0, 4).is_empty());
+
+        // The other profiles are interpolated by the section properties
+        let custom = |area: f64, smoa: f64| Profile::CustomProfile(CustomProfile {
+            name: "TEST".to_string(),
+            custom_area: area,
+            custom_major_sec_mom_of_area: smoa,
+            ..CustomProfile::default()
+        });
+        let tapered = SectionVariation::new_tapered(custom(2000.0, 8000.0));
+        let (area, smoa, _) = tapered.get_section_properties_at(&custom(1000.0, 1000.0), &material, 4000.0, 2000.0, &settings);
+        assert!((area - 1500.0).abs() < 1e-9);
+        assert!((smoa - 3375.0).abs() < 1e-6);
+    }
 }
@@ -1,4 +1,5 @@
 ﻿pub mod element;
+mod constraint;
 mod node;
 mod release;
 mod support;
@@ -8,9 +9,18 @@ pub mod utils;
 pub type NodeCollection = BTreeMap<i32, Node>;
 
 use std::collections::BTreeMap;
+pub use constraint::Constraint;
+pub use constraint::ConstraintType;
+pub use element::ElasticFoundation;
 pub use element::Element;
+pub use element::ElementBehaviour;
+pub use element::OffsetCoordinateSystem;
+pub use element::ProfileStation;
+pub use element::RigidOffsets;
+pub use element::SectionVariation;
 pub use node::Node;
 pub use release::Release;
+pub use support::ReactionDirection;
 pub use support::Support;
 pub use structure_model::StructureModel;
 pub use element::CalculationElement;
\ No newline at end of file
@@ -9,6 +9,10 @@ pub struct Node {
     pub number: i32,
     pub point: VpPoint,
     pub support: Support,
+    /// The additional mass at the node (kg) for the modal analysis. The mass affects the
+    /// translations of the node.
+    #[serde(default)]
+    pub mass: f64,
 }
 
 impl Node {
@@ -18,6 +22,7 @@ impl Node {
             number,
             point,
             support,
+            mass: 0.0,
         }
     }
 
@@ -27,6 +32,7 @@ impl Node {
             number,
             point,
             support: Support::new(),
+            mass: 0.0,
         }
     }
 
@@ -36,6 +42,7 @@ impl Node {
             number,
             point,
             support: Support::new_hinged(),
+            mass: 0.0,
         }
     }
 
@@ -45,6 +52,7 @@ impl Node {
             number,
             point,
             support: Support::new_fixed(),
+            mass: 0.0,
         }
     }
 }
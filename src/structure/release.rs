@@ -17,6 +17,30 @@ pub struct Release {
     pub e_tz: bool,
     /// End rotation release about elements local Y-axis. If set to true, the rotation about Y-axis is released
     pub e_ry: bool,
+    /// The spring constant of the start release in elements local X-axis (kN/mm). Used only if
+    /// the translation is released.
+    #[serde(default)]
+    pub s_x_spring: f64,
+    /// The spring constant of the start release in elements local Z-axis (kN/mm). Used only if
+    /// the translation is released.
+    #[serde(default)]
+    pub s_z_spring: f64,
+    /// The spring constant of the start release about elements local Y-axis (kNm/rad). Used only
+    /// if the rotation is released.
+    #[serde(default)]
+    pub s_r_spring: f64,
+    /// The spring constant of the end release in elements local X-axis (kN/mm). Used only if the
+    /// translation is released.
+    #[serde(default)]
+    pub e_x_spring: f64,
+    /// The spring constant of the end release in elements local Z-axis (kN/mm). Used only if the
+    /// translation is released.
+    #[serde(default)]
+    pub e_z_spring: f64,
+    /// The spring constant of the end release about elements local Y-axis (kNm/rad). Used only if
+    /// the rotation is released.
+    #[serde(default)]
+    pub e_r_spring: f64,
 }
 impl Release {
     /// Creates new Release object that has no releases set (translations and rotation are all locked)
@@ -39,6 +63,39 @@ impl Release {
         }
     }
 
+    /// Gets the spring value from given index (from range 0..=5, 0: s_x_spring, 1: s_z_spring,
+    /// 2: s_r_spring, 3: e_x_spring, 4: e_z_spring, 5: e_r_spring) in kN/mm or kNm/rad
+    pub fn get_spring_value(&self, i: usize) -> Option<f64> {
+        match i {
+            0 => Some(self.s_x_spring),
+            1 => Some(self.s_z_spring),
+            2 => Some(self.s_r_spring),
+            3 => Some(self.e_x_spring),
+            4 => Some(self.e_z_spring),
+            5 => Some(self.e_r_spring),
+            _ => None,
+        }
+    }
+
+    /// Gets the stiffness of the release spring at given index (see [Release::get_spring_value])
+    /// in N/mm or Nmm/rad. The stiffness is zero if the degree of freedom is not released.
+    pub fn get_spring_stiffness(&self, i: usize) -> f64 {
+        if !self.get_release_value(i).unwrap_or(false) {
+            return 0.0;
+        }
+        match i {
+            // kN/mm => N/mm
+            0 | 1 | 3 | 4 => self.get_spring_value(i).unwrap() * 1e3,
+            // kNm/rad => Nmm/rad
+            _ => self.get_spring_value(i).unwrap() * 1e6,
+        }
+    }
+
+    /// Returns true if any of the released degrees of freedom has a spring (semi-rigid release)
+    pub fn has_springs(&self) -> bool {
+        (0..6).any(|i| self.get_spring_stiffness(i) != 0.0)
+    }
+
     pub fn start_release_any(&self) -> bool {
         self.s_tx || self.s_tz || self.s_ry
     }
@@ -61,15 +118,21 @@ impl Release {
         tx + tz + ry
     }
 
+    /// Gets the releases as a short string, where each character is one degree of freedom in the
+    /// order of [Release::get_release_value]: 'x' is locked, 'f' is released and 's' is released
+    /// with a spring (semi-rigid).
     pub fn to_string(&self) -> String {
         let mut result = String::with_capacity(6);
 
-        result.push(if self.s_tx {'f'} else {'x'});
-        result.push(if self.s_tz {'f'} else {'x'});
-        result.push(if self.s_ry {'f'} else {'x'});
-        result.push(if self.e_tx {'f'} else {'x'});
-        result.push(if self.e_tz {'f'} else {'x'});
-        result.push(if self.e_ry {'f'} else {'x'});
+        for i in 0..6 {
+            result.push(if !self.get_release_value(i).unwrap() {
+                'x'
+            } else if self.get_spring_stiffness(i) != 0.0 {
+                's'
+            } else {
+                'f'
+            });
+        }
 
         result
 
@@ -84,6 +147,12 @@ impl Default for Release {
             e_tx: false,
             e_tz: false,
             e_ry: false,
+            s_x_spring: 0.0,
+            s_z_spring: 0.0,
+            s_r_spring: 0.0,
+            e_x_spring: 0.0,
+            e_z_spring: 0.0,
+            e_r_spring: 0.0,
         }
     }
 }
@@ -1,4 +1,4 @@
-use super::{Element, NodeCollection};
+use super::{Constraint, Element, NodeCollection};
 use crate::{loads::{Load, LoadCombination}, settings::CalculationSettings};
 use serde::{Deserialize, Serialize};
 
@@ -15,4 +15,7 @@ pub struct StructureModel {
     pub loads: Vec<Load>,
     /// The calculation settings
     pub calc_settings: CalculationSettings,
+    /// The constraints between the nodes (e.g. the rigid links and the equal degrees of freedom)
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Support {
@@ -17,6 +18,30 @@ pub struct Support {
     pub r_spring: f64,
     /// The rotation of the support about the global Y-axis in degrees.
     pub rotation: f64,
+    /// The allowed direction of the support reaction in the X-axis (if the translation is locked)
+    #[serde(default)]
+    pub x_direction: ReactionDirection,
+    /// The allowed direction of the support reaction in the Z-axis (if the translation is locked)
+    #[serde(default)]
+    pub z_direction: ReactionDirection,
+    /// The allowed direction of the support moment about the Y-axis (if the rotation is locked)
+    #[serde(default)]
+    pub r_direction: ReactionDirection,
+}
+
+/// The allowed direction of a support reaction. The one-directional supports (e.g. a bearing that
+/// can lift off) are solved iteratively for each load combination. The supports whose reaction
+/// would be in the wrong direction are released until the set of the active supports is stable.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum ReactionDirection {
+    /// The support takes reactions in both directions
+    #[default]
+    Both = 0,
+    /// The support takes only positive reactions (it resists only the negative displacements)
+    Positive = 1,
+    /// The support takes only negative reactions (it resists only the positive displacements)
+    Negative = 2,
 }
 impl Support {
     /// Creates new support that has no locks set (translations and rotation are not locked)
@@ -69,6 +94,30 @@ impl Support {
         }
     }
 
+    /// Returns the allowed direction of the support reaction at given index (0=x, 1=z, 2=r)
+    pub fn get_reaction_direction(&self, index: usize) -> ReactionDirection {
+        match index {
+            0 => self.x_direction,
+            1 => self.z_direction,
+            2 => self.r_direction,
+            _ => panic!(
+                "Tried to get reaction direction from support outside of degrees of freedom count!"
+            ),
+        }
+    }
+
+    /// Sets the support lock value at given index (0=tx, 1=tz, 2=ry)
+    pub fn set_support_lock(&mut self, index: usize, value: bool) {
+        match index {
+            0 => self.tx = value,
+            1 => self.tz = value,
+            2 => self.ry = value,
+            _ => panic!(
+                "Tried to set degree of freedom to support outside of degrees of freedom count!"
+            ),
+        }
+    }
+
     pub fn to_short_string(&self) -> String {
         let mut result = String::with_capacity(6);
 
@@ -89,6 +138,9 @@ impl Default for Support {
             z_spring: 0.0,
             r_spring: 0.0,
             rotation: 0.0,
+            x_direction: ReactionDirection::Both,
+            z_direction: ReactionDirection::Both,
+            r_direction: ReactionDirection::Both,
         }
     }
 }
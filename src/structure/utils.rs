@@ -36,18 +36,20 @@ pub fn get_element_release_count(elements: &Vec<Element>) -> usize {
 /// Elements are also split by the given split positions that is a map of element numbers and the
 /// position where the element should be split. It can be used to split elements in to multiple
 /// calcuation elements if needed or, for example, split the element where a crack is located
-/// (to calculate the eurocode deflections of a concrete beam).
+/// (to calculate the eurocode deflections of a concrete beam). The tapered elements (see
+/// [super::SectionVariation]) are split into segments and the section properties of the segments
+/// are set to the calculation elements.
 ///
 /// ## Parameters:
 /// * 'elements' - the elements of the structure model
 /// * 'nodes' - the nodes of the structure model
-/// * 'split_positions' - a map of element numbers and the position where the element should be split (in millimeters)
+/// * 'split_positions' - a map of element numbers and the positions where the element should be split (in millimeters)
 ///
 /// Returns: Vec<CalculationElement>
 pub fn get_calc_elements<'a>(
     elements: &'a Vec<Element>,
     nodes: &BTreeMap<i32, Node>,
-    split_positions: &HashMap<i32, i64>,
+    split_positions: &HashMap<i32, Vec<i64>>,
     calc_settings: &CalculationSettings,
 ) -> (BTreeMap<i32, Vec<CalculationElement<'a>>>, BTreeMap<i32, Node>) {
     let mut calc_elements: BTreeMap<i32, Vec<CalculationElement<'a>>> = BTreeMap::new();
@@ -57,10 +59,16 @@ pub fn get_calc_elements<'a>(
         calc_elements.insert(e.number, Vec::new());
         let mut e_split_set: BTreeMap<i64, &Node> = BTreeMap::new();
 
-        let e_start = &nodes.get(&e.node_start).unwrap().point;
-        let e_end = &nodes.get(&e.node_end).unwrap().point;
+        // The element is split along its flexible part (between the rigid end offsets)
+        let (start_point, end_point) = e.get_end_points(nodes);
+        let e_start = &start_point;
+        let e_end = &end_point;
         let rotation = geometry2d::get_angle_from_points(e_start, e_end);
         let length = e.get_length(nodes);
+        // The tapered elements are split into segments with the section properties of the segments
+        let tapered_positions = e
+            .section_variation
+            .get_split_positions(length, calc_settings.tapered_elements.segment_count);
         for n in nodes.values() {
             if n.number == e.node_start || n.number == e.node_end {
                 continue;
@@ -77,23 +85,32 @@ pub fn get_calc_elements<'a>(
             }
         }
 
-        if split_positions.contains_key(&e.number) {
-            let split_pos = *split_positions.get(&e.number).unwrap();
-            // Only insert the value if it is not already in the set
-            if !e_split_set.contains_key(&split_pos) {
-                // Create a node point at start of element.X + split_pos
-                let mut node_point = VpPoint::new(e_start.x + split_pos as f64 , 0.0);
-                // Rotate the point around the start point to move it to be in the element
-                node_point = geometry2d::rotate_point(&e_start, &node_point, rotation);
-                // Get the number for the support from node and extra node count
-                let number = (nodes.len() + 1 + extra_nodes.len()) as i32;
-                // Create new node and insert it into the extra nodes
-                let n = Node::new_free(number, node_point);
-                extra_nodes.insert(n.number, n);
-                // Add the split pos into map and add a reference to the node inside the extra nodes map
-                // This makes sure that the node lives longer than this if statement
-                e_split_set.insert(split_pos, &extra_nodes[&number]);
+        // Get the numbers for the extra nodes first and insert the references to the split set after
+        // all nodes are created, so that the extra nodes map is not borrowed while inserting
+        let mut e_extra_nodes: Vec<(i64, i32)> = Vec::new();
+        for split_pos in split_positions.get(&e.number).into_iter().flatten().chain(&tapered_positions) {
+            let split_pos = *split_pos;
+            // Only insert the value if it is not already in the set and it is inside the element
+            if e_split_set.contains_key(&split_pos)
+                || e_extra_nodes.iter().any(|(pos, _)| *pos == split_pos)
+                || split_pos <= 0
+                || split_pos as f64 >= length.round()
+            {
+                continue;
             }
+            // Create a node point at start of element.X + split_pos
+            let mut node_point = VpPoint::new(e_start.x + split_pos as f64, e_start.y);
+            // Rotate the point around the start point to move it to be in the element
+            node_point = geometry2d::rotate_point(&e_start, &node_point, rotation);
+            // Get the number for the support from node and extra node count
+            let number = (nodes.len() + 1 + extra_nodes.len()) as i32;
+            // Create new node and insert it into the extra nodes
+            let n = Node::new_free(number, node_point);
+            extra_nodes.insert(n.number, n);
+            e_extra_nodes.push((split_pos, number));
+        }
+        for (split_pos, number) in e_extra_nodes {
+            e_split_set.insert(split_pos, &extra_nodes[&number]);
         }
         if e_split_set.len() == 0 {
             calc_elements.get_mut(&e.number).unwrap().push(CalculationElement::from(&e, nodes, el_num, calc_settings));
@@ -103,7 +120,6 @@ pub fn get_calc_elements<'a>(
             let mut count = 0;
             let mut prev_split_pos: Option<(i64, i32)> = None;
             for split_pos in e_split_set.iter() {
-                println!("Split pos: {} (nro: {1})", split_pos.0, split_pos.1.number);
                 let mut calc_element = CalculationElement::from(&e, nodes, el_num, calc_settings);
                 if count == 0 {
                     // Create the first element                
@@ -113,6 +129,7 @@ pub fn get_calc_elements<'a>(
                     calc_element.releases.e_tx = false;
                     calc_element.releases.e_tz = false;
                     calc_element.releases.e_ry = false;
+                    calc_element.rigid_offset_end = (0.0, 0.0);
                 } else {
                     // Create the middle element
                     el_num += 1;
@@ -121,6 +138,8 @@ pub fn get_calc_elements<'a>(
                     calc_element.offset_from_model_el = prev_split_pos.unwrap().0 as f64;
                     calc_element.length = (split_pos.0 - prev_split_pos.unwrap().0) as f64;
                     clear_element_releases(&mut calc_element.releases);
+                    calc_element.rigid_offset_start = (0.0, 0.0);
+                    calc_element.rigid_offset_end = (0.0, 0.0);
                 }
                 prev_split_pos = Some((*split_pos.0, split_pos.1.number));
                 count += 1;
@@ -136,12 +155,39 @@ pub fn get_calc_elements<'a>(
             calc_element.releases.s_tx = false;
             calc_element.releases.s_tz = false;
             calc_element.releases.s_ry = false;
+            calc_element.rigid_offset_start = (0.0, 0.0);
             calc_elements.get_mut(&e.number).unwrap().push(calc_element);
         }        
+        if e.section_variation.is_active() {
+            for calc_element in calc_elements.get_mut(&e.number).unwrap() {
+                calc_element.set_section_variation(e, calc_settings);
+            }
+        }
     }
     (calc_elements, extra_nodes)
 }
 
+/// Adds the split positions into the given split positions so that the given elements are split
+/// into equal segments by the segment count.
+pub fn add_split_positions<'a>(
+    split_positions: &mut HashMap<i32, Vec<i64>>,
+    elements: impl IntoIterator<Item = &'a Element>,
+    nodes: &BTreeMap<i32, Node>,
+    segment_count: usize,
+) {
+    let segment_count = segment_count.max(1);
+    for e in elements {
+        let length = e.get_length(nodes);
+        let positions = split_positions.entry(e.number).or_default();
+        for i in 1..segment_count {
+            let position = (length * i as f64 / segment_count as f64).round() as i64;
+            if !positions.contains(&position) {
+                positions.push(position);
+            }
+        }
+    }
+}
+
 pub fn clear_element_releases(release: &mut Release) {
     release.s_tx = false;
     release.s_tz = false;
@@ -149,4 +195,93 @@ pub fn clear_element_releases(release: &mut Release) {
     release.e_tx = false;
     release.e_tz = false;
     release.e_ry = false;
-}
\ No newline at end of file
+}
+
+/// A model element on a path of consecutive elements (e.g. the path of the moving loads)
+#[derive(Debug, Copy, Clone)]
+pub struct PathElement {
+    pub element_number: i32,
+    /// The position of the start of the element on the path (mm)
+    pub path_start: f64,
+    pub length: f64,
+    /// True if the path goes from the end of the element to the start
+    pub reversed: bool,
+    /// True if the element continues from the node where the previous element of the path ends
+    pub connected: bool,
+}
+
+impl PathElement {
+    /// Gets the position on the element (measured from the start of the element) at the path
+    /// position
+    pub fn get_pos_on_element(&self, path_position: f64) -> f64 {
+        let distance = path_position - self.path_start;
+        if self.reversed { self.length - distance } else { distance }
+    }
+}
+
+/// Gets the path of the given model elements. The path continues from the node where the previous
+/// element ends, so an element whose end node is connected to the previous element is traversed
+/// from the end to the start. The elements that are not found are skipped.
+/// * `path_elements` - the model element numbers in the order of the path
+pub fn get_element_path(
+    elements: &[Element],
+    nodes: &BTreeMap<i32, Node>,
+    path_elements: &[i32],
+) -> Vec<PathElement> {
+    let mut path = Vec::new();
+    let mut path_length = 0.0;
+    let mut path_end_node = None;
+    for element_number in path_elements {
+        let Some(element) = elements.iter().find(|e| e.number == *element_number) else {
+            continue;
+        };
+        let length = element.get_length(nodes);
+        let reversed = path_end_node == Some(element.node_end);
+        path.push(PathElement {
+            element_number: element.number,
+            path_start: path_length,
+            length,
+            reversed,
+            connected: reversed || path_end_node == Some(element.node_start),
+        });
+        path_length += length;
+        path_end_node = Some(if reversed { element.node_start } else { element.node_end });
+    }
+    path
+}
+
+/// Gets the path element and the position on the element (measured from the start of the
+/// element) at the path position. The position between two elements is on the first element.
+/// Returns None if the position is outside the path.
+pub fn get_path_location(path: &[PathElement], path_position: f64) -> Option<(i32, f64)> {
+    path.iter()
+        .find(|e| path_position >= e.path_start && path_position <= e.path_start + e.length)
+        .map(|e| (e.element_number, e.get_pos_on_element(path_position).clamp(0.0, e.length)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_extra_node_on_inclined_element() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 1000.0))),
+            (2, Node::new_hinged(2, VpPoint::new(3000.0, 5000.0))),
+        ]);
+        let elements = vec![Element { number: 1, node_start: 1, node_end: 2, ..Element::default() }];
+        let (calc_elements, extra_nodes) = get_calc_elements(
+            &elements,
+            &nodes,
+            &HashMap::from([(1, vec![2500])]),
+            &CalculationSettings::default(),
+        );
+        assert_eq!(1, extra_nodes.len());
+        let extra_node = &extra_nodes[&3];
+        assert!((extra_node.point.x - 1500.0).abs() < 1e-6);
+        assert!((extra_node.point.y - 3000.0).abs() < 1e-6);
+        assert_eq!(2, calc_elements[&1].len());
+        assert_eq!(3, calc_elements[&1][0].node_end);
+        assert_eq!(3, calc_elements[&1][1].node_start);
+    }
+}
@@ -46,6 +46,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&struct_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1]
@@ -121,6 +122,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&struct_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1]
@@ -166,6 +168,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         println!("LENGTH: {:?}", results.internal_force_results.len());
@@ -249,6 +252,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new());
         println!("Results: {results:?}");
@@ -322,6 +326,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1]
@@ -386,6 +391,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1]
@@ -457,6 +463,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1]
@@ -517,6 +524,7 @@ mod axial_deformation_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         struct_model
     }
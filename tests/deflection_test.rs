@@ -5,7 +5,9 @@ mod deflection_tests {
     use approx::relative_eq;
     use vputilslib::{equation_handler::EquationHandler, geometry2d::VpPoint};
 
-    use vefem::{loads::{Load, LoadGroup}, material::{MaterialData, Steel}, profile::Profile, results::ForceType, settings::CalculationSettings, structure::{Element, Node, StructureModel}};
+    use vefem::{loads::{load_combination::LoadCombinationType, Load, LoadCombination, LoadGroup}, material::{Concrete, MaterialData, StandardConcrete, Steel, Timber, TimberStrengthClass}, profile::Profile, results::ForceType, settings::{calc_settings::{BeamTheory, ConcreteDeflectionSettings}, CalculationSettings}, structure::{Element, Node, SectionVariation, StructureModel}};
+    use vefem::fem::concrete_deflection;
+    use vefem::reinforcement::{RebarCollection, RebarData, RebarDistribution, ReinforcementData, Side};
 
 
 
@@ -38,6 +40,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 1000.0)
@@ -104,6 +107,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 0.0)
@@ -141,6 +145,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 4000.0)
@@ -177,6 +182,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 0.0)
@@ -213,6 +219,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 1000.0)
@@ -259,6 +266,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 1000.0)
@@ -335,6 +343,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 1000.0)
@@ -403,6 +412,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 2000.0)
@@ -459,6 +469,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 1000.0)
@@ -527,6 +538,7 @@ mod deflection_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
        let results = &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
         let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 2000.0)
@@ -552,5 +564,494 @@ mod deflection_tests {
         println!("Deflection(2000<45): {} mm", defl);
         assert_eq!(relative_eq!(defl, 2.058, epsilon = 0.01), true);
     }
+
+    #[test]
+    fn t_calculate_deflection_timber_cantilever() {
+        let material = MaterialData::Timber(Timber::standard(TimberStrengthClass::GL30c));
+        let el: Element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R315x90".to_string(), 315.0, 90.0),
+            material,
+        );
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(3000.0, 0.0))),
+        ]);
+        let p_load = Load::new_point_load(
+            "Pointload".to_string(),
+            "1".to_string(),
+            "L".to_string(),
+            "5".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let structure_model = StructureModel {
+            nodes,
+            elements: vec![el],
+            loads: vec![p_load],
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        // PL³ / 3EI with E_0,mean = 13000 MPa
+        let expected = -5e3 * 3000f64.powi(3) / (3.0 * 13000.0 * 90.0 * 315f64.powi(3) / 12.0);
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 3000.0)
+            .unwrap().value_y;
+        println!("Deflection(3000): {} mm (expected {} mm)", defl, expected);
+        assert!(relative_eq!(defl, expected, epsilon = 0.01));
+    }
+
+    #[test]
+    fn t_calculate_deflection_timoshenko_cantilever() {
+        let profile = Profile::new_rectangle("R400x200".to_string(), 400.0, 200.0);
+        let el: Element = Element::new(1, 1, 2, profile.clone(), MaterialData::Steel(Steel::new(210e3)));
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(2000.0, 0.0))),
+        ]);
+        let p_load = Load::new_point_load(
+            "Pointload".to_string(),
+            "1".to_string(),
+            "L".to_string(),
+            "100".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let structure_model = StructureModel {
+            nodes,
+            elements: vec![el],
+            loads: vec![p_load],
+            calc_settings: CalculationSettings {
+                beam_theory: BeamTheory::Timoshenko,
+                ..Default::default()
+            },
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let ei = 210e3 * 200.0 * 400f64.powi(3) / 12.0;
+        let ga_s = 210e3 / 2.6 * 5.0 / 6.0 * 400.0 * 200.0;
+        let p = 100e3;
+        let expected = |x: f64| -(p * x.powi(2) * (3.0 * 2000.0 - x) / (6.0 * ei) + p * x / ga_s);
+
+        let defl = results.node_results.get_global_displacement(2, 1);
+        println!("Tip displacement: {} mm (expected {} mm)", defl, expected(2000.0));
+        assert!(relative_eq!(defl, expected(2000.0), epsilon = 0.01));
+        for x in [500.0, 1000.0, 2000.0] {
+            let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, x)
+                .unwrap().value_y;
+            println!("Deflection({}): {} mm (expected {} mm)", x, defl, expected(x));
+            assert!(relative_eq!(defl, expected(x), epsilon = 0.01));
+        }
+    }
+
+    #[test]
+    fn t_calculate_timoshenko_fixed_beam_pl() {
+        // The point load on the element is compared to the same structure where the load is at the node
+        let profile = Profile::new_rectangle("R600x200".to_string(), 600.0, 200.0);
+        let material = MaterialData::Steel(Steel::new(210e3));
+        let calc_settings = || CalculationSettings {
+            beam_theory: BeamTheory::Timoshenko,
+            ..Default::default()
+        };
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_fixed(2, VpPoint::new(3000.0, 0.0))),
+        ]);
+        let structure_model = StructureModel {
+            nodes,
+            elements: vec![Element::new(1, 1, 2, profile.clone(), material.clone())],
+            loads: vec![Load::new_point_load(
+                "P".to_string(),
+                "1".to_string(),
+                "750".to_string(),
+                "100".to_string(),
+                -90.0,
+                LoadGroup::PERMANENT,
+            )],
+            calc_settings: calc_settings(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(750.0, 0.0))),
+            (3, Node::new_fixed(3, VpPoint::new(3000.0, 0.0))),
+        ]);
+        let structure_model_nodal = StructureModel {
+            nodes,
+            elements: vec![
+                Element::new(1, 1, 2, profile.clone(), material.clone()),
+                Element::new(2, 2, 3, profile.clone(), material.clone()),
+            ],
+            loads: vec![Load::new_point_load(
+                "P".to_string(),
+                "1".to_string(),
+                "L".to_string(),
+                "100".to_string(),
+                -90.0,
+                LoadGroup::PERMANENT,
+            )],
+            calc_settings: calc_settings(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results_nodal = &vefem::fem::fem_handler::calculate(&structure_model_nodal, &EquationHandler::new())[0];
+
+        for dir in 1..3 {
+            let r = results.node_results.get_support_reaction(1, dir);
+            let r_nodal = results_nodal.node_results.get_support_reaction(1, dir);
+            println!("Start reaction {}: {} ({})", dir, r, r_nodal);
+            assert!(relative_eq!(r, r_nodal, max_relative = 1e-6));
+            let r = results.node_results.get_support_reaction(2, dir);
+            let r_nodal = results_nodal.node_results.get_support_reaction(3, dir);
+            println!("End reaction {}: {} ({})", dir, r, r_nodal);
+            assert!(relative_eq!(r, r_nodal, max_relative = 1e-6));
+        }
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 750.0)
+            .unwrap().value_y;
+        let defl_nodal = results_nodal.node_results.get_global_displacement(2, 1);
+        println!("Deflection at load: {} mm ({} mm)", defl, defl_nodal);
+        assert!(relative_eq!(defl, defl_nodal, max_relative = 1e-4));
+    }
+
+    fn get_rc_beam(element_number: i32, node_start: i32, node_end: i32) -> Element {
+        let mut concrete = Concrete::standard(StandardConcrete::C30_37);
+        concrete.reinforcement.main_rebars.push(RebarCollection::new_bot_full(
+            ReinforcementData::Rebar(RebarData::new(500.0, 200e3)),
+            RebarDistribution::Even {
+                diam: 20.0,
+                count: 3,
+                cc_start: "60".to_string(),
+                cc_end: "60".to_string(),
+            },
+            "50-20/2".to_string(),
+        ));
+        concrete.reinforcement.main_rebars.push(RebarCollection {
+            side: Side::BoundingBox { index: 2 },
+            ..RebarCollection::new_bot_full(
+                ReinforcementData::Rebar(RebarData::new(500.0, 200e3)),
+                RebarDistribution::Even {
+                    diam: 16.0,
+                    count: 2,
+                    cc_start: "60".to_string(),
+                    cc_end: "60".to_string(),
+                },
+                "50-16/2".to_string(),
+            )
+        });
+        Element::new(
+            element_number,
+            node_start,
+            node_end,
+            Profile::new_rectangle("R500x300".to_string(), 500.0, 300.0),
+            MaterialData::Concrete(concrete),
+        )
+    }
+
+    #[test]
+    fn t_concrete_deflection_simply_supported() {
+        let length = 6000.0;
+        let q = 20.0;
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(length, 0.0))),
+        ]);
+        let line_load = Load::new_line_load(
+            "Line".to_string(),
+            "1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            q.to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let mut structure_model = StructureModel {
+            nodes,
+            elements: vec![get_rc_beam(1, 1, 2)],
+            loads: vec![line_load],
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let defl_uncracked = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+
+        structure_model.calc_settings.concrete_deflections = ConcreteDeflectionSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        let moment = results.internal_force_results[&1].get_force_at(ForceType::Moment, length / 2.0)
+            .unwrap().value_y;
+        // The moments of statically determinate beam do not depend on the stiffness
+        assert!(relative_eq!(moment, q * length.powi(2) / 8.0, max_relative = 1e-6));
+
+        // The deflection at the middle of the beam by the principle of virtual work with the same
+        // segments and effective second moments of area
+        let element = &structure_model.elements[0];
+        let e_cm = element.get_elastic_modulus();
+        let props = concrete_deflection::get_cracking_properties(element, &structure_model.calc_settings).unwrap();
+        let segment_count = structure_model.calc_settings.concrete_deflections.segment_count;
+        let seg_length = length / segment_count as f64;
+        let moment_at = |x: f64| q * x * (length - x) / 2.0;
+        let unit_moment_at = |x: f64| if x < length / 2.0 { x / 2.0 } else { (length - x) / 2.0 };
+        let mut expected = 0.0;
+        for i in 0..segment_count {
+            let smoa = props.get_effective_smoa(moment_at((i as f64 + 0.5) * seg_length), 1.0);
+            let steps = 100;
+            let dx = seg_length / steps as f64;
+            for j in 0..steps {
+                let x = i as f64 * seg_length + (j as f64 + 0.5) * dx;
+                expected += moment_at(x) * unit_moment_at(x) / (e_cm * smoa) * dx;
+            }
+        }
+        println!("Deflection: {} mm (expected {} mm, uncracked {} mm)", defl, -expected, defl_uncracked);
+        assert!(relative_eq!(defl, -expected, max_relative = 1e-3));
+        assert!(defl < defl_uncracked);
+        let defl_node = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length)
+            .unwrap().value_y;
+        assert!(defl_node.abs() < 1e-6);
+    }
+
+    #[test]
+    fn t_concrete_deflection_tapered_element() {
+        // The cracking and the long-term properties are calculated with the profile of the element,
+        // so the tapered element keeps the short-term section properties of its segments
+        let length = 6000.0;
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(length, 0.0))),
+        ]);
+        let mut element = get_rc_beam(1, 1, 2);
+        element.section_variation =
+            SectionVariation::new_tapered(Profile::new_rectangle("R300x300".to_string(), 300.0, 300.0));
+        let line_load = Load::new_line_load(
+            "Line".to_string(),
+            "1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            "20".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let mut structure_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads: vec![line_load],
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let defl_uncracked = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+
+        structure_model.calc_settings.concrete_deflections = ConcreteDeflectionSettings {
+            enabled: true,
+            long_term: true,
+            ..Default::default()
+        };
+        let properties = concrete_deflection::ConcreteProperties::new(
+            &structure_model.elements,
+            &structure_model.calc_settings,
+        );
+        assert!(properties.cracking.is_empty());
+        assert!(properties.long_term.is_empty());
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        assert!(relative_eq!(defl, defl_uncracked, max_relative = 1e-9));
+    }
+
+    #[test]
+    fn t_concrete_deflection_continuous_beam_converges() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(6000.0, 0.0))),
+            (3, Node::new_hinged(3, VpPoint::new(12000.0, 0.0))),
+        ]);
+        let line_load = Load::new_line_load(
+            "Line".to_string(),
+            "-1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            "25".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let structure_model = StructureModel {
+            nodes,
+            elements: vec![get_rc_beam(1, 1, 2), get_rc_beam(2, 2, 3)],
+            loads: vec![line_load],
+            calc_settings: CalculationSettings {
+                concrete_deflections: ConcreteDeflectionSettings {
+                    enabled: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        // The sum of the support reactions equals the total load
+        let total: f64 = (1..=3).map(|n| results.node_results.get_support_reaction(n, 1)).sum();
+        assert!(relative_eq!(total, 25.0 * 12000.0, max_relative = 1e-6));
+        // Symmetric structure => symmetric results
+        let defl_1 = results.internal_force_results[&1].get_force_at(ForceType::Deflection, 2500.0)
+            .unwrap().value_y;
+        let defl_2 = results.internal_force_results[&2].get_force_at(ForceType::Deflection, 3500.0)
+            .unwrap().value_y;
+        println!("Deflections: {} mm, {} mm", defl_1, defl_2);
+        assert!(relative_eq!(defl_1, defl_2, max_relative = 1e-3));
+        assert!(defl_1 < 0.0);
+        // The moment at the middle support is in equilibrium with the span moments
+        let m_support = results.internal_force_results[&1].get_force_at(ForceType::Moment, 6000.0)
+            .unwrap().value_y;
+        let m_span = results.internal_force_results[&1].get_force_at(ForceType::Moment, 3000.0)
+            .unwrap().value_y;
+        assert!(relative_eq!(m_span - m_support / 2.0, 25.0 * 6000f64.powi(2) / 8.0, max_relative = 1e-3));
+    }
+
+    fn get_quasi_permanent_combination() -> LoadCombination {
+        let mut lc = LoadCombination::new(1, "QP".to_string(), LoadCombinationType::SLSqp { is_auto: false });
+        lc.add_load_n_factor("ALL".to_string(), 1.0);
+        lc
+    }
+
+    #[test]
+    fn t_concrete_long_term_deflection() {
+        let length = 6000.0;
+        let q = 10.0;
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(length, 0.0))),
+        ]);
+        let line_load = Load::new_line_load(
+            "Line".to_string(),
+            "1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            q.to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let calc_settings = CalculationSettings {
+            concrete_deflections: ConcreteDeflectionSettings {
+                long_term: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut structure_model = StructureModel {
+            nodes,
+            elements: vec![Element::new(
+                1,
+                1,
+                2,
+                Profile::new_rectangle("R500x300".to_string(), 500.0, 300.0),
+                MaterialData::Concrete(Concrete::standard(StandardConcrete::C30_37)),
+            )],
+            loads: vec![line_load],
+            calc_settings,
+            load_combinations: vec![get_quasi_permanent_combination()],
+            constraints: vec![],
+        };
+
+        // Without the reinforcement there is no shrinkage curvature, so the long-term deflection
+        // is the short-term deflection multiplied by (1 + φ)
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let long_term = results.long_term_results.as_ref().unwrap();
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        let defl_final = long_term.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        let props = concrete_deflection::get_long_term_properties(
+            &structure_model.elements[0], &structure_model.calc_settings).unwrap();
+        println!("Deflection: {} mm, final: {} mm, φ = {}", defl, defl_final, props.creep_coefficient);
+        assert!(relative_eq!(defl_final, defl * (1.0 + props.creep_coefficient), max_relative = 1e-6));
+
+        // With the reinforcement the shrinkage curvature adds κ * L² / 8 to the deflection
+        structure_model.elements = vec![get_rc_beam(1, 1, 2)];
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let long_term = results.long_term_results.as_ref().unwrap();
+        let defl_final = long_term.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        let props = concrete_deflection::get_long_term_properties(
+            &structure_model.elements[0], &structure_model.calc_settings).unwrap();
+        // Bottom reinforcement dominates => the shrinkage bends the beam downwards
+        assert!(props.shrinkage_curvature_uncracked > 0.0);
+        let expected = -5.0 * q * length.powi(4) / (384.0 * props.elastic_modulus * props.major_smoa)
+            - props.shrinkage_curvature_uncracked * length.powi(2) / 8.0;
+        println!("Final deflection: {} mm (expected {} mm)", defl_final, expected);
+        assert!(relative_eq!(defl_final, expected, max_relative = 1e-6));
+        // The shrinkage curvature does not cause moments to statically determinate structures
+        let moment = long_term.internal_force_results[&1].get_force_at(ForceType::Moment, length / 2.0)
+            .unwrap().value_y;
+        assert!(relative_eq!(moment, q * length.powi(2) / 8.0, max_relative = 1e-6));
+
+        // The long-term results are only calculated for the quasi-permanent combinations
+        structure_model.load_combinations = vec![];
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        assert!(results.long_term_results.is_none());
+    }
+
+    #[test]
+    fn t_concrete_long_term_deflection_fixed_beam_cracked() {
+        let length = 6000.0;
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_fixed(2, VpPoint::new(length, 0.0))),
+        ]);
+        let line_load = Load::new_line_load(
+            "Line".to_string(),
+            "1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            "25".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        );
+        let structure_model = StructureModel {
+            nodes,
+            elements: vec![get_rc_beam(1, 1, 2)],
+            loads: vec![line_load],
+            calc_settings: CalculationSettings {
+                concrete_deflections: ConcreteDeflectionSettings {
+                    enabled: true,
+                    long_term: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            load_combinations: vec![get_quasi_permanent_combination()],
+            constraints: vec![],
+        };
+        let results = &vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new())[0];
+        let long_term = results.long_term_results.as_ref().unwrap();
+        let defl = results.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        let defl_final = long_term.internal_force_results[&1].get_force_at(ForceType::Deflection, length / 2.0)
+            .unwrap().value_y;
+        println!("Deflection: {} mm, final: {} mm", defl, defl_final);
+        assert!(defl < 0.0);
+        assert!(defl_final < defl);
+        // The fixed ends stay in place
+        let defl_end = long_term.internal_force_results[&1].get_force_at(ForceType::Deflection, length)
+            .unwrap().value_y;
+        assert!(defl_end.abs() < 1e-6);
+        // The support reactions are in equilibrium with the load
+        let total: f64 = (1..=2).map(|n| long_term.node_results.get_support_reaction(n, 1)).sum();
+        assert!(relative_eq!(total, 25.0 * length, max_relative = 1e-6));
+    }
 }
 
@@ -0,0 +1,222 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common;
+    use std::collections::{BTreeMap, BTreeSet};
+    use vefem::loads::{load_combination::LoadCombinationType, Load, LoadCombination, LoadGroup};
+    use vefem::material::{MaterialData, Steel};
+    use vefem::profile::Profile;
+    use vefem::results::envelope::{ElementEnvelope, NodeEnvelope};
+    use vefem::results::ResultEnvelope;
+    use vefem::settings::calc_settings::AxleTrainSettings;
+    use vefem::settings::CalculationSettings;
+    use vefem::structure::{Element, Node, StructureModel};
+    use vputilslib::equation_handler::EquationHandler;
+    use vputilslib::geometry2d::VpPoint;
+
+    #[test]
+    fn t_envelope_of_factored_combinations() {
+        let (elements, nodes) = common::get_structure_three_horizontal_elements();
+        let loads = vec![Load::new_line_load(
+            "1".to_string(),
+            "-1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            "10".to_string(),
+            -90.0,
+            LoadGroup::PERMANENT,
+        )];
+        let mut lc1 = LoadCombination::new(1, "LC1".to_string(), LoadCombinationType::ULS { is_auto: false });
+        lc1.add_load_n_factor("ALL".to_string(), 1.0);
+        let mut lc2 = LoadCombination::new(2, "LC2".to_string(), LoadCombinationType::ULS { is_auto: false });
+        lc2.add_load_n_factor("ALL".to_string(), 2.0);
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads,
+            load_combinations: vec![lc1, lc2],
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let envelope = ResultEnvelope::new(&results);
+
+        let support_2 = envelope.node_envelopes[&2].support_reactions[1];
+        println!("Support 2: {:?}", support_2);
+        assert!((support_2.max - 2.0 * results[0].node_results.get_support_reaction(2, 1)).abs() < 1.0);
+        assert_eq!(support_2.max_sub_load_comb_num, 2001);
+        assert!((support_2.min - results[0].node_results.get_support_reaction(2, 1)).abs() < 1.0);
+        assert_eq!(support_2.min_sub_load_comb_num, 1001);
+
+        // The middle support moment (hogging) is governed by LC2 and its absolute value is twice the LC1 value
+        let moments = &envelope.element_envelopes[&1].moment_forces;
+        let end_moment = moments.last().unwrap().value;
+        let lc1_moment = results[0].internal_force_results[&1].moment_forces.last().unwrap().value_y;
+        assert!((end_moment.max.abs().max(end_moment.min.abs()) - 2.0 * lc1_moment.abs()).abs() < 1.0);
+        assert_eq!(moments.len(), results[0].internal_force_results[&1].moment_forces.len());
+
+        let json = serde_json::to_string(&envelope).unwrap();
+        let deserialized: ResultEnvelope = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.node_envelopes[&2].support_reactions[1].max_sub_load_comb_num, 2001);
+        assert!(envelope.node_envelopes.keys().eq(struct_model.nodes.keys()));
+    }
+
+    #[test]
+    fn t_envelope_without_values() {
+        // The envelopes without any added values can be serialized and deserialized
+        let mut envelope = ResultEnvelope::default();
+        envelope.node_envelopes.insert(1, NodeEnvelope::new(1));
+        envelope.element_envelopes.insert(1, ElementEnvelope::new(1));
+        let json = serde_json::to_string(&envelope).unwrap();
+        let deserialized: ResultEnvelope = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.node_envelopes[&1].support_reactions.is_empty());
+        assert!(deserialized.element_envelopes[&1].moment_forces.is_empty());
+    }
+
+    #[test]
+    fn t_envelope_of_moving_loads() {
+        let (elements, nodes) = common::get_structure_three_horizontal_elements();
+        let mut live_load = Load::new_line_load(
+            "q".to_string(),
+            "-1".to_string(),
+            "0".to_string(),
+            "L".to_string(),
+            "10".to_string(),
+            -90.0,
+            LoadGroup::CLASS_A,
+        );
+        live_load.is_moving_load = true;
+        let mut lc = LoadCombination::new(1, "ULS".to_string(), LoadCombinationType::ULS { is_auto: true });
+        lc.add_load_n_factor("ALL".to_string(), 1.0);
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads: vec![live_load],
+            load_combinations: vec![lc],
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        // _LL(KL A) and the patterns [1], [2], [3], [1,2], [2,3], [1,3]
+        assert_eq!(results.len(), 7);
+        let envelope = ResultEnvelope::new(&results);
+        // The reaction at the first middle support is the largest when the adjacent spans are loaded
+        let support_2 = envelope.node_envelopes[&2].support_reactions[1];
+        println!("Support 2: {:?}", support_2);
+        assert_eq!(support_2.max_sub_load_comb_num, 1005);
+        // The reaction at the end support is the smallest when only the middle span is loaded
+        let support_1 = envelope.node_envelopes[&1].support_reactions[1];
+        assert_eq!(support_1.min_sub_load_comb_num, 1003);
+    }
+
+    #[test]
+    fn t_envelope_of_axle_train() {
+        // Two axles (10 kN) with the spacing of 1000 mm moved over a simply supported beam
+        // (L = 4000 mm) in both directions
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let elements = vec![Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        )];
+        let train = Load::new_axle_train(
+            "Crane".to_string(),
+            "1".to_string(),
+            "10;10".to_string(),
+            "1000".to_string(),
+            -90.0,
+            LoadGroup::CLASS_A,
+        );
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads: vec![train],
+            load_combinations: vec![],
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        // The train travels the span and its own length (5000 mm) with the step of 100 mm
+        assert_eq!(results.len(), 2 * 51);
+        assert!(results.iter().all(|r| r.axle_train_position.is_some()));
+        let envelope = ResultEnvelope::new(&results);
+
+        // The maximum reaction is with the axles at 0 and 1000 mm: P + 0.75P
+        let reaction = envelope.node_envelopes[&1].support_reactions[1];
+        assert!((reaction.max - 1.75e4).abs() < 1e-3);
+        let position = envelope.get_axle_train_position(reaction.max_sub_load_comb_num).unwrap();
+        let expected = if position.reversed { 0.0 } else { 1000.0 };
+        assert!((position.path_position - expected).abs() < 1e-6);
+
+        // The maximum midspan moment is with one axle at the midspan: P * (L/4 + 1000 / 2)
+        let midspan = envelope.element_envelopes[&1]
+            .moment_forces
+            .iter()
+            .find(|p| (p.pos_on_element - 2000.0).abs() < 1e-6)
+            .unwrap();
+        assert!((midspan.value.max - 1e4 * 1500.0).abs() < 1.0);
+        let position = envelope.get_axle_train_position(midspan.value.max_sub_load_comb_num).unwrap();
+        let expected = if position.reversed { [1000.0, 2000.0] } else { [2000.0, 3000.0] };
+        assert!(expected.iter().any(|p| (position.path_position - p).abs() < 1e-6));
+    }
+
+    #[test]
+    fn t_envelope_of_axle_train_with_over_999_positions() {
+        // A single axle (10 kN) moved over a simply supported beam (L = 4000 mm) with the step of
+        // 4 mm gives 1001 train positions in both load combinations
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let elements = vec![Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        )];
+        let train = Load::new_axle_train(
+            "Crane".to_string(),
+            "1".to_string(),
+            "10".to_string(),
+            "".to_string(),
+            -90.0,
+            LoadGroup::CLASS_A,
+        );
+        let mut lc1 = LoadCombination::new(1, "LC1".to_string(), LoadCombinationType::ULS { is_auto: false });
+        lc1.add_load_n_factor("ALL".to_string(), 1.0);
+        let mut lc2 = LoadCombination::new(2, "LC2".to_string(), LoadCombinationType::ULS { is_auto: false });
+        lc2.add_load_n_factor("ALL".to_string(), 2.0);
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads: vec![train],
+            load_combinations: vec![lc1, lc2],
+            calc_settings: CalculationSettings {
+                axle_trains: AxleTrainSettings { step: 4.0, both_directions: false },
+                ..Default::default()
+            },
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        assert_eq!(results.len(), 2 * 1001);
+        let sub_numbers: BTreeSet<usize> = results.iter().map(|r| r.sub_load_comb_num).collect();
+        assert_eq!(sub_numbers.len(), results.len());
+        let envelope = ResultEnvelope::new(&results);
+        assert_eq!(envelope.axle_train_positions.len(), results.len());
+
+        // The maximum reaction is with the axle at the support in LC2
+        let reaction = envelope.node_envelopes[&1].support_reactions[1];
+        assert!((reaction.max - 2e4).abs() < 1e-3);
+        let governing = results.iter().find(|r| r.sub_load_comb_num == reaction.max_sub_load_comb_num).unwrap();
+        assert_eq!(governing.load_comb_num, 2);
+        let position = envelope.get_axle_train_position(reaction.max_sub_load_comb_num).unwrap();
+        assert!(position.path_position.abs() < 1e-6);
+    }
+}
@@ -45,6 +45,7 @@ mod readme_test {
             loads,
             calc_settings,
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&calc_model, &mut eq_handler);
         // The default settings divide the internal force calculation points into 100 intervals.
@@ -61,14 +62,20 @@ mod fem_tests {
     use crate::common;
     use approx::relative_eq;
     use std::collections::BTreeMap;
+    use std::f64::consts::PI;
     use std::time::SystemTime;
     use vefem::loads::load_combination::{CalcLoadCombination, LoadCombinationType};
     use vefem::loads::{self, Load, LoadCombination, LoadGroup};
-    use vefem::material::{MaterialData, Steel};
+    use vefem::material::{Concrete, MaterialData, Steel};
     use vefem::profile::{CustomProfile, Profile};
+    use vefem::results::InfluenceQuantity;
+    use vefem::settings::calc_settings::{AnalysisType, FoundationSettings, MassMatrixType, ModalSettings};
     use vefem::settings::CalculationSettings;
     use vefem::structure::Node;
-    use vefem::structure::{Element, StructureModel};
+    use vefem::structure::{
+        Constraint, ElasticFoundation, Element, ElementBehaviour, OffsetCoordinateSystem, ReactionDirection,
+        RigidOffsets, SectionVariation, StructureModel, Support,
+    };
     use vputilslib::equation_handler::EquationHandler;
     use vputilslib::geometry2d;
     use vputilslib::geometry2d::VpPoint;
@@ -592,6 +599,7 @@ mod fem_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let calc_results = vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new());
         let displacement = &calc_results[0].node_results.displacements;
@@ -738,6 +746,46 @@ mod fem_tests {
         assert_eq!(reactions[(11, 0)].round(), 0.0);
     }
 
+    #[test]
+    fn factorization_multiple_load_vectors() {
+        let (elements, nodes) = common::get_structure_fem_matriisit();
+        let loads = common::get_fem_matriisi_loads();
+        let calc_settings = CalculationSettings::default();
+        let calc_model = common::get_calc_model(&elements, &nodes);
+        let calc_loads = loads::utils::extract_calculation_loads(
+            &calc_model,
+            &loads,
+            &CalcLoadCombination::default(),
+            &EquationHandler::new(),
+        );
+        let col_height = vefem::fem::utils::col_height(&nodes);
+        let mut calc_matrices = vefem::fem::matrices::create_global_calculation_matrix(&calc_model, &calc_settings, &calc_loads);
+        let factorization = vefem::fem::fem_handler::StiffnessFactorization::new(
+            &nodes,
+            col_height,
+            calc_matrices.stiffness.clone(),
+        );
+        // The same loads as the second right hand side with doubled values
+        let mut equivalent_loads = nalgebra::DMatrix::zeros(col_height, 2);
+        equivalent_loads.column_mut(0).copy_from(&calc_matrices.equivalent_loads.column(0));
+        equivalent_loads.column_mut(1).copy_from(&(calc_matrices.equivalent_loads.column(0) * 2.0));
+        let displacements = factorization.solve(&equivalent_loads);
+        let reactions = factorization.get_reactions(&displacements, &equivalent_loads);
+
+        let displacement = vefem::fem::fem_handler::calculate_displacements(
+            &nodes,
+            col_height,
+            &mut calc_matrices.stiffness,
+            &mut calc_matrices.equivalent_loads,
+        );
+        for i in 0..col_height {
+            assert!(relative_eq!(displacements[(i, 0)], displacement[(i, 0)], epsilon = 1e-12));
+            assert!(relative_eq!(displacements[(i, 1)], 2.0 * displacement[(i, 0)], epsilon = 1e-12));
+        }
+        assert!(relative_eq!(reactions[(1, 0)], 2.3333e4, max_relative = 0.01));
+        assert!(relative_eq!(reactions[(1, 1)], 2.0 * 2.3333e4, max_relative = 0.01));
+    }
+
     #[test]
     fn reactions_2() {
         let (elements, nodes) = common::get_structure_fem_matriisit_releases();
@@ -882,6 +930,7 @@ mod fem_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let calc_results = vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new());
         println!("Displacements:");
@@ -939,6 +988,7 @@ mod fem_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let calc_results = vefem::fem::fem_handler::calculate(&structure_model, &EquationHandler::new());
         println!("Displacements:");
@@ -1108,6 +1158,7 @@ mod fem_tests {
             loads,
             calc_settings,
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
 
@@ -1147,6 +1198,7 @@ mod fem_tests {
             loads,
             calc_settings,
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
 
@@ -1195,6 +1247,7 @@ mod fem_tests {
             nodes, loads,
             calc_settings,
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
         println!();
@@ -1230,6 +1283,7 @@ mod fem_tests {
             loads,
             calc_settings,
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
 
@@ -1277,6 +1331,7 @@ mod fem_tests {
             loads,
             calc_settings,
             load_combinations,
+            constraints: vec![],
         };
 
         println!("Starting to calculate...");
@@ -1310,6 +1365,7 @@ mod fem_tests {
             loads,
             calc_settings,
             load_combinations,
+            constraints: vec![],
         };
 
         println!("Starting to calculate...");
@@ -1347,6 +1403,7 @@ mod fem_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
@@ -1354,4 +1411,926 @@ mod fem_tests {
         println!("Support reaction start: {}", results[0].node_results.support_reactions[0]);
         println!("Support reaction start: {}", results[0].node_results.support_reactions[1]);
     }
+
+    #[test]
+    fn self_weight_load() {
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(6000.0, 0.0))),
+            (3, Node::new_fixed(3, VpPoint::new(8000.0, 0.0))),
+            (4, Node::new_free(4, VpPoint::new(8000.0, 3000.0))),
+        ]);
+        let column_profile = Profile::CustomProfile(CustomProfile {
+            custom_area: 3000.0,
+            custom_major_sec_mom_of_area: 2e7,
+            custom_weight_per_meter: 50.0,
+            ..Default::default()
+        });
+        let elements = vec![
+            Element::new(1, 1, 2,
+                Profile::new_rectangle("R500x300".to_string(), 500.0, 300.0),
+                MaterialData::Concrete(Concrete { density: 2500.0, ..Concrete::new() })),
+            Element::new(2, 3, 4, column_profile, MaterialData::Steel(Steel::default())),
+        ];
+        let loads = vec![Load::new_self_weight_load(
+            "SelfWeight".to_string(),
+            "-1".to_string(),
+            LoadGroup::PERMANENT,
+        )];
+        let mut lc = LoadCombination::new(1, "ULS".to_string(), vefem::loads::load_combination::LoadCombinationType::ULS { is_auto: false });
+        lc.add_load_n_factor("SelfWeight".to_string(), 1.35);
+        let mut struct_model = StructureModel {
+            nodes,
+            elements,
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        // 0.5 m * 0.3 m * 2500 kg/m³ * 9.81 m/s² = 3678.75 N/m
+        let beam_reaction = 0.5 * 0.3 * 2500.0 * 9.81 * 6.0 / 2.0;
+        // 50 kg/m * 3 m * 9.81 m/s²
+        let column_reaction = 50.0 * 3.0 * 9.81;
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let reactions = &results[0].node_results;
+        assert!(relative_eq!(reactions.get_support_reaction(1, 1), beam_reaction, max_relative = 1e-6));
+        assert!(relative_eq!(reactions.get_support_reaction(2, 1), beam_reaction, max_relative = 1e-6));
+        assert!(relative_eq!(reactions.get_support_reaction(3, 1), column_reaction, max_relative = 1e-6));
+        assert!(reactions.get_support_reaction(3, 0).abs() < 1e-6);
+
+        struct_model.load_combinations = vec![lc];
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let reactions = &results[0].node_results;
+        assert!(relative_eq!(reactions.get_support_reaction(1, 1), 1.35 * beam_reaction, max_relative = 1e-6));
+        assert!(relative_eq!(reactions.get_support_reaction(3, 1), 1.35 * column_reaction, max_relative = 1e-6));
+    }
+
+    #[test]
+    fn loads_on_split_element() {
+        // The element is split into two calculation elements by the node 3 in the middle of the
+        // element. The results should be the same as without the split.
+        let get_model = |with_middle_node: bool| {
+            let mut nodes = BTreeMap::from([
+                (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+                (2, Node::new_hinged(2, VpPoint::new(6000.0, 0.0))),
+            ]);
+            if with_middle_node {
+                nodes.insert(3, Node::new_free(3, VpPoint::new(2000.0, 0.0)));
+            }
+            let elements = vec![Element::new(
+                1,
+                1,
+                2,
+                Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+                MaterialData::Steel(Steel::default()),
+            )];
+            let loads = vec![
+                Load::new_point_load("P".to_string(), "1".to_string(), "1000".to_string(),
+                    "10".to_string(), -90.0, LoadGroup::PERMANENT),
+                Load::new_point_load("P".to_string(), "1".to_string(), "2000".to_string(),
+                    "10".to_string(), -90.0, LoadGroup::PERMANENT),
+                Load::new_rotational_load("M".to_string(), "1".to_string(), "4000".to_string(),
+                    "5".to_string(), LoadGroup::PERMANENT),
+                Load::new_triangular_load("T".to_string(), "1".to_string(), "L".to_string(),
+                    "0".to_string(), "5".to_string(), -90.0, LoadGroup::PERMANENT),
+            ];
+            StructureModel {
+                nodes,
+                elements,
+                loads,
+                calc_settings: CalculationSettings::default(),
+                load_combinations: vec![],
+                constraints: vec![],
+            }
+        };
+        let results = vefem::fem::fem_handler::calculate(&get_model(false), &EquationHandler::new());
+        let results_split = vefem::fem::fem_handler::calculate(&get_model(true), &EquationHandler::new());
+        for node in [1, 2] {
+            let reaction = results[0].node_results.get_support_reaction(node, 1);
+            let reaction_split = results_split[0].node_results.get_support_reaction(node, 1);
+            println!("Reaction at node {}: {} ({})", node, reaction, reaction_split);
+            assert!(relative_eq!(reaction, reaction_split, max_relative = 1e-6));
+        }
+        for x in [500.0, 2000.0, 3000.0, 4500.0] {
+            let moment = results[0].internal_force_results[&1]
+                .get_force_at(vefem::results::ForceType::Moment, x).unwrap().value_y;
+            let moment_split = results_split[0].internal_force_results[&1]
+                .get_force_at(vefem::results::ForceType::Moment, x).unwrap().value_y;
+            println!("Moment at {}: {} ({})", x, moment, moment_split);
+            // The results are sampled by the split interval, so the positions don't match exactly
+            assert!(relative_eq!(moment, moment_split, max_relative = 1e-2));
+        }
+    }
+
+    #[test]
+    fn second_order_cantilever() {
+        // A cantilever column with an axial and a lateral load (kN) at the top. The critical load
+        // of the column is π²EI/(2L)² = 270 kN.
+        let get_model = |axial_load: &str, analysis_type| {
+            let nodes = BTreeMap::from([
+                (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+                (2, Node::new_free(2, VpPoint::new(0.0, 4000.0))),
+            ]);
+            let elements = vec![Element::new(
+                1,
+                1,
+                2,
+                Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+                MaterialData::Steel(Steel::new(210e3)),
+            )];
+            let loads = vec![
+                Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+                    axial_load.to_string(), -90.0, LoadGroup::PERMANENT),
+                Load::new_point_load("H".to_string(), "1".to_string(), "L".to_string(),
+                    "1".to_string(), 0.0, LoadGroup::PERMANENT),
+            ];
+            StructureModel {
+                nodes,
+                elements,
+                loads,
+                calc_settings: CalculationSettings { analysis_type, ..Default::default() },
+                load_combinations: vec![],
+                constraints: vec![],
+            }
+        };
+        let results = vefem::fem::fem_handler::calculate(
+            &get_model("100", AnalysisType::SecondOrder),
+            &EquationHandler::new(),
+        );
+        let second_order = results[0].second_order_results.as_ref().unwrap();
+        assert!(second_order.converged);
+        // The exact displacement is H/(P k) (tan(kL) - kL) = 19.27 mm, where k = √(P/EI)
+        let displacement = results[0].node_results.get_global_displacement(2, 0).abs();
+        assert!(relative_eq!(displacement, 19.27, max_relative = 0.01));
+        // The base moment H L + P δ
+        let moment = results[0].node_results.get_support_reaction(1, 2).abs();
+        assert!(relative_eq!(moment, 1000.0 * 4000.0 + 1e5 * 19.27, max_relative = 0.01));
+        // The element end forces include the geometric stiffness, so the moment of the element
+        // is the base moment at the support and zero at the free end
+        let forces = &results[0].internal_force_results[&1];
+        let base_moment = forces.get_force_at(vefem::results::ForceType::Moment, 0.0).unwrap().value_y;
+        let top_moment = forces.get_force_at(vefem::results::ForceType::Moment, 4000.0).unwrap().value_y;
+        assert!(relative_eq!(base_moment.abs(), moment, max_relative = 0.01));
+        assert!(top_moment.abs() < 0.01 * moment);
+        assert!(relative_eq!(second_order.moment_amplifications[&1], 5.927 / 4.0, max_relative = 0.01));
+
+        let first_order = vefem::fem::fem_handler::calculate(
+            &get_model("100", AnalysisType::FirstOrder),
+            &EquationHandler::new(),
+        );
+        assert!(first_order[0].second_order_results.is_none());
+        let displacement = first_order[0].node_results.get_global_displacement(2, 0).abs();
+        assert!(relative_eq!(displacement, 1000.0 * 4000f64.powi(3) / (3.0 * 210e3 * 100f64.powi(4) / 12.0), max_relative = 1e-6));
+
+        // The axial load exceeds the critical load
+        let results = vefem::fem::fem_handler::calculate(
+            &get_model("300", AnalysisType::SecondOrder),
+            &EquationHandler::new(),
+        );
+        assert!(!results[0].second_order_results.as_ref().unwrap().converged);
+    }
+
+    #[test]
+    fn linear_buckling_column() {
+        // A pinned column with an axial load of 100 kN. The critical load of the column is
+        // π²EI/L² = 1079.5 kN.
+        let get_model = |support: fn(i32, VpPoint) -> Node| {
+            // The top of the column is free to move vertically
+            let mut roller = Support::new();
+            roller.tx = true;
+            let nodes = BTreeMap::from([
+                (1, support(1, VpPoint::new(0.0, 0.0))),
+                (2, Node::new(2, VpPoint::new(0.0, 4000.0), roller)),
+            ]);
+            let elements = vec![Element::new(
+                1,
+                1,
+                2,
+                Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+                MaterialData::Steel(Steel::new(210e3)),
+            )];
+            let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+                "100".to_string(), -90.0, LoadGroup::PERMANENT)];
+            StructureModel {
+                nodes,
+                elements,
+                loads,
+                calc_settings: CalculationSettings::default(),
+                load_combinations: vec![],
+                constraints: vec![],
+            }
+        };
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let critical_load = PI.powi(2) * ei / 4000f64.powi(2);
+        let results = vefem::fem::fem_handler::calculate_buckling(&get_model(Node::new_hinged), &EquationHandler::new());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].converged);
+        let modes = &results[0].modes;
+        assert_eq!(modes.len(), 3);
+        // The geometric stiffness of the split elements overestimates the critical load slightly
+        assert!(relative_eq!(modes[0].critical_load_factor, critical_load / 100e3, max_relative = 0.01));
+        // The higher modes are (2π)² and (3π)² times the lowest one
+        assert!(relative_eq!(modes[1].critical_load_factor, 4.0 * critical_load / 100e3, max_relative = 0.05));
+        assert!(modes[2].critical_load_factor > modes[1].critical_load_factor);
+        assert!(relative_eq!(modes[0].buckling_lengths[&1], 4000.0, max_relative = 0.01));
+        // The largest translation of the first mode is the horizontal translation at the middle
+        let max_translation = modes[0].mode_shape.iter().fold(0.0f64, |acc, v| acc.max(v.abs()));
+        assert!(relative_eq!(max_translation, 1.0));
+
+        // A fixed base (the buckling length is about 0.7 L)
+        let results = vefem::fem::fem_handler::calculate_buckling(&get_model(Node::new_fixed), &EquationHandler::new());
+        let modes = &results[0].modes;
+        assert!(relative_eq!(modes[0].critical_load_factor, 2.046 * critical_load / 100e3, max_relative = 0.02));
+        assert!(relative_eq!(modes[0].buckling_lengths[&1], 0.699 * 4000.0, max_relative = 0.02));
+    }
+
+    #[test]
+    fn modal_simply_supported_beam() {
+        // A simply supported beam. The natural angular frequencies are (nπ/L)² √(EI/m).
+        let get_model = |modal: ModalSettings| {
+            let nodes = BTreeMap::from([
+                (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+                (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+            ]);
+            let elements = vec![Element::new(
+                1,
+                1,
+                2,
+                Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+                MaterialData::Steel(Steel::new(210e3)),
+            )];
+            let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L/2".to_string(),
+                "1".to_string(), -90.0, LoadGroup::PERMANENT)];
+            let mut load_combination = LoadCombination::new(1, "MASS".to_string(), LoadCombinationType::None);
+            load_combination.add_load_n_factor("P".to_string(), 1.0);
+            StructureModel {
+                nodes,
+                elements,
+                loads,
+                calc_settings: CalculationSettings { modal, ..Default::default() },
+                load_combinations: vec![load_combination],
+                constraints: vec![],
+            }
+        };
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        // t/mm
+        let mass_per_length = 100.0 * 100.0 * 7850.0 * 1e-12;
+        let angular_frequency = (PI / 4000.0).powi(2) * (ei / mass_per_length).sqrt();
+        let total_mass = 100.0 * 100.0 * 4000.0 * 7850.0 * 1e-9;
+
+        let results = vefem::fem::fem_handler::calculate_modal(&get_model(ModalSettings::default()), &EquationHandler::new());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].converged);
+        assert!(relative_eq!(results[0].total_mass, total_mass, max_relative = 1e-9));
+        let modes = &results[0].modes;
+        assert_eq!(modes.len(), 3);
+        assert!(relative_eq!(modes[0].angular_frequency, angular_frequency, max_relative = 1e-3));
+        assert!(relative_eq!(modes[0].frequency, angular_frequency / (2.0 * PI), max_relative = 1e-3));
+        assert!(relative_eq!(modes[1].angular_frequency, 4.0 * angular_frequency, max_relative = 1e-3));
+        // The participation factor of the sine mode is 4/π and the effective mass 8/π² of the total mass
+        assert!(relative_eq!(modes[0].participation_factor_z.abs(), 4.0 / PI, max_relative = 0.01));
+        assert!(relative_eq!(modes[0].effective_mass_z, 8.0 / PI.powi(2) * total_mass, max_relative = 0.01));
+        assert!(modes[0].effective_mass_x.abs() < 1e-6);
+        // The antisymmetric mode has no effective mass
+        assert!(modes[1].effective_mass_z.abs() < 1e-6);
+
+        // The lumped masses give nearly the same first frequency
+        let lumped = ModalSettings { mass_matrix: MassMatrixType::Lumped, ..Default::default() };
+        let results = vefem::fem::fem_handler::calculate_modal(&get_model(lumped), &EquationHandler::new());
+        assert!(relative_eq!(results[0].modes[0].angular_frequency, angular_frequency, max_relative = 0.01));
+
+        // The point load (1 kN) at the middle is converted into a mass of 1000 N / g. The frequency
+        // is estimated with the Rayleigh quotient of the sine mode.
+        let with_loads = ModalSettings { mass_load_combination: "MASS".to_string(), ..Default::default() };
+        let results = vefem::fem::fem_handler::calculate_modal(&get_model(with_loads), &EquationHandler::new());
+        assert_eq!(results[0].load_combination, "MASS");
+        let load_mass = 1000.0 / 9.81;
+        assert!(relative_eq!(results[0].total_mass, total_mass + load_mass, max_relative = 1e-9));
+        let expected = angular_frequency * (total_mass / 2.0 / (total_mass / 2.0 + load_mass)).sqrt();
+        assert!(relative_eq!(results[0].modes[0].angular_frequency, expected, max_relative = 0.01));
+    }
+
+    #[test]
+    fn tension_only_bracing() {
+        // A portal frame with hinged bases and tension-only diagonals. The lateral load (10 kN) at
+        // the top is taken by the diagonal in tension and the compressed diagonal is inactive.
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+            (3, Node::new_free(3, VpPoint::new(0.0, 4000.0))),
+            (4, Node::new_free(4, VpPoint::new(4000.0, 4000.0))),
+        ]);
+        let profile = Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0);
+        let material = MaterialData::Steel(Steel::new(210e3));
+        let mut elements = vec![
+            Element::new(1, 1, 3, profile.clone(), material.clone()),
+            Element::new(2, 2, 4, profile.clone(), material.clone()),
+            Element::new(3, 3, 4, profile.clone(), material.clone()),
+            Element::new(4, 1, 4, profile.clone(), material.clone()),
+            Element::new(5, 3, 2, profile.clone(), material.clone()),
+        ];
+        elements[3].behaviour = ElementBehaviour::TensionOnly;
+        elements[4].behaviour = ElementBehaviour::TensionOnly;
+        let loads = vec![Load::new_point_load("H".to_string(), "3".to_string(), "0".to_string(),
+            "10".to_string(), 0.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let active_set = results[0].active_set_results.as_ref().unwrap();
+        assert!(active_set.converged);
+        assert_eq!(active_set.elements, BTreeMap::from([(4, true), (5, false)]));
+        assert!(active_set.supports.is_empty());
+        // The frame is much more flexible than the diagonal, so the diagonal takes nearly all the
+        // load: N = √2 H
+        let axial_force = |el_number: i32| {
+            results[0].internal_force_results[&el_number]
+                .get_force_at(vefem::results::ForceType::Axial, 1000.0).unwrap().value_y
+        };
+        assert!(relative_eq!(axial_force(4), 2f64.sqrt() * 10e3, max_relative = 0.01));
+        assert_eq!(axial_force(5), 0.0);
+
+        // A structure without the conditions has no active set results
+        let results = vefem::fem::fem_handler::calculate(&get_two_span_beam(ReactionDirection::Both), &EquationHandler::new());
+        assert!(results[0].active_set_results.is_none());
+    }
+
+    #[test]
+    fn lift_off_support() {
+        // A two-span beam with a point load (10 kN) at the middle of the second span. The end
+        // support of the first span would be pulled down (-3P/32), so it lifts off and the
+        // second span acts as a simply supported beam.
+        let results = vefem::fem::fem_handler::calculate(&get_two_span_beam(ReactionDirection::Positive), &EquationHandler::new());
+        let active_set = results[0].active_set_results.as_ref().unwrap();
+        assert!(active_set.converged);
+        assert_eq!(active_set.supports[&1], [true, false, true]);
+        assert_eq!(active_set.supports[&3], [true, true, true]);
+        let node_results = &results[0].node_results;
+        assert!(node_results.get_support_reaction(1, 1).abs() < 1e-6);
+        assert!(node_results.get_global_displacement(1, 1) > 0.0);
+        assert!(relative_eq!(node_results.get_support_reaction(2, 1), 5e3, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(3, 1), 5e3, max_relative = 1e-6));
+
+        // The support that takes reactions in both directions holds the beam down
+        let results = vefem::fem::fem_handler::calculate(&get_two_span_beam(ReactionDirection::Both), &EquationHandler::new());
+        assert!(relative_eq!(results[0].node_results.get_support_reaction(1, 1), -3.0 / 32.0 * 10e3, max_relative = 1e-3));
+    }
+
+    /// Gets a two-span beam with a point load at the middle of the second span. The end supports
+    /// have the given reaction direction in the Z-axis.
+    fn get_two_span_beam(direction: ReactionDirection) -> StructureModel {
+        let mut end_support = Support::new();
+        end_support.tz = true;
+        end_support.z_direction = direction;
+        let nodes = BTreeMap::from([
+            (1, Node::new(1, VpPoint::new(0.0, 0.0), end_support)),
+            (2, Node::new_hinged(2, VpPoint::new(4000.0, 0.0))),
+            (3, Node::new(3, VpPoint::new(8000.0, 0.0), end_support)),
+        ]);
+        let profile = Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0);
+        let material = MaterialData::Steel(Steel::new(210e3));
+        let elements = vec![
+            Element::new(1, 1, 2, profile.clone(), material.clone()),
+            Element::new(2, 2, 3, profile, material),
+        ];
+        let loads = vec![Load::new_point_load("P".to_string(), "2".to_string(), "L/2".to_string(),
+            "10".to_string(), -90.0, LoadGroup::PERMANENT)];
+        StructureModel {
+            nodes,
+            elements,
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        }
+    }
+
+    #[test]
+    fn support_settlement() {
+        // A two-span beam where the middle support settles 10 mm. The middle support reaction is
+        // the point load that deflects a simply supported beam of 2L by 10 mm: 6EI d / L³.
+        let get_model = |loads: Vec<Load>, load_combinations: Vec<LoadCombination>| {
+            let mut roller = Support::new();
+            roller.tz = true;
+            let nodes = BTreeMap::from([
+                (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+                (2, Node::new(2, VpPoint::new(4000.0, 0.0), roller)),
+                (3, Node::new(3, VpPoint::new(8000.0, 0.0), roller)),
+            ]);
+            let profile = Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0);
+            let material = MaterialData::Steel(Steel::new(210e3));
+            let elements = vec![
+                Element::new(1, 1, 2, profile.clone(), material.clone()),
+                Element::new(2, 2, 3, profile, material),
+            ];
+            StructureModel {
+                nodes,
+                elements,
+                loads,
+                calc_settings: CalculationSettings::default(),
+                load_combinations,
+                constraints: vec![],
+            }
+        };
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let reaction = 6.0 * ei * 10.0 / 4000f64.powi(3);
+        let settlement = Load::new_support_displacement("S".to_string(), "2".to_string(),
+            "10".to_string(), -90.0, LoadGroup::PERMANENT);
+        let results = vefem::fem::fem_handler::calculate(&get_model(vec![settlement.clone()], vec![]), &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        assert!(relative_eq!(node_results.get_global_displacement(2, 1), -10.0, max_relative = 1e-9));
+        // The beam pulls the settled support down and the end supports take the reaction
+        assert!(relative_eq!(node_results.get_support_reaction(2, 1), -reaction, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 1), reaction / 2.0, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(3, 1), reaction / 2.0, max_relative = 1e-6));
+        let moment = results[0].internal_force_results[&1]
+            .get_force_at(vefem::results::ForceType::Moment, 4000.0).unwrap().value_y;
+        assert!(relative_eq!(moment.abs(), reaction / 2.0 * 4000.0, max_relative = 1e-6));
+        // The settlement is not applied to the free translation (X) of the roller
+        assert!(node_results.get_global_displacement(2, 0).abs() < 1e-9);
+
+        // The settlement takes part in the load combinations with its factor
+        let mut load_combination = LoadCombination::new(1, "LC".to_string(), LoadCombinationType::None);
+        load_combination.add_load_n_factor("S".to_string(), 2.0);
+        let results = vefem::fem::fem_handler::calculate(&get_model(vec![settlement], vec![load_combination]), &EquationHandler::new());
+        assert!(relative_eq!(results[0].node_results.get_support_reaction(2, 1), -2.0 * reaction, max_relative = 1e-6));
+    }
+
+    #[test]
+    fn support_rotation() {
+        // A propped cantilever where the fixed support rotates 0.001 rad. The moment at the fixed
+        // support is 3EI θ / L.
+        let mut roller = Support::new();
+        roller.tz = true;
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new(2, VpPoint::new(4000.0, 0.0), roller)),
+        ]);
+        let elements = vec![Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        )];
+        let loads = vec![Load::new_support_rotation("R".to_string(), "1".to_string(),
+            "0.001".to_string(), LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        assert!(relative_eq!(node_results.get_global_displacement(1, 2), 0.001, max_relative = 1e-9));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 2).abs(), 3.0 * ei * 0.001 / 4000.0, max_relative = 1e-6));
+        // The roller end rotates back by half of the support rotation
+        assert!(relative_eq!(node_results.get_global_displacement(2, 2), -0.0005, max_relative = 1e-6));
+    }
+
+    #[test]
+    fn beam_on_elastic_foundation() {
+        // A long beam on an elastic foundation with a point load (100 kN) at the middle. The beam
+        // has no supports. The results at the load are w = Pλ/2k and M = P/4λ, where
+        // λ = ⁴√(k / 4EI) and k is the subgrade modulus times the width.
+        let nodes = BTreeMap::from([
+            (1, Node::new_free(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(10000.0, 0.0))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.foundation = ElasticFoundation {
+            subgrade_modulus: 20000.0,
+            horizontal_subgrade_modulus: 10000.0,
+            width: 1000.0,
+        };
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L/2".to_string(),
+            "100".to_string(), -90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings {
+                foundation: FoundationSettings { segment_count: 40 },
+                ..Default::default()
+            },
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let forces = &results[0].internal_force_results[&1];
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        // N/mm²
+        let k = 20000.0 * 1e-6 * 1000.0;
+        let lambda = (k / (4.0 * ei)).powf(0.25);
+        let deflection = forces.get_force_at(vefem::results::ForceType::Deflection, 5000.0).unwrap();
+        assert!(relative_eq!(deflection.value_y, -1e5 * lambda / (2.0 * k), max_relative = 1e-2));
+        // No horizontal displacements
+        assert!(deflection.value_x.abs() < 1e-9);
+        let moment = forces.get_force_at(vefem::results::ForceType::Moment, 5000.0).unwrap().value_y;
+        assert!(relative_eq!(moment.abs(), 1e5 / (4.0 * lambda), max_relative = 1e-2));
+        let pressure = forces.get_force_at(vefem::results::ForceType::SoilPressure, 5000.0).unwrap();
+        assert!(relative_eq!(pressure.value_y, 20000.0 * 1e-6 * 1e5 * lambda / (2.0 * k), max_relative = 1e-2));
+        // The soil pressures take the whole load
+        let total: f64 = forces
+            .soil_pressures
+            .windows(2)
+            .map(|p| (p[0].value_y + p[1].value_y) / 2.0 * (p[1].pos_on_element - p[0].pos_on_element) * 1000.0)
+            .sum();
+        assert!(relative_eq!(total, 1e5, max_relative = 1e-2));
+        // The elements without a foundation have no soil pressures
+        let results = vefem::fem::fem_handler::calculate(&get_two_span_beam(ReactionDirection::Both), &EquationHandler::new());
+        assert!(results[0].internal_force_results[&1].soil_pressures.is_empty());
+    }
+
+    #[test]
+    fn inclined_beam_on_elastic_foundation() {
+        // The beam of the previous test inclined by 30 degrees with the point load perpendicular
+        // to the beam. The soil pressures are perpendicular to the beam, so there are no axial
+        // forces and the moment is the same as for the horizontal beam.
+        let angle = 30f64.to_radians();
+        let nodes = BTreeMap::from([
+            (1, Node::new_free(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(10000.0 * angle.cos(), 10000.0 * angle.sin()))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.foundation = ElasticFoundation {
+            subgrade_modulus: 20000.0,
+            horizontal_subgrade_modulus: 10000.0,
+            width: 1000.0,
+        };
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L/2".to_string(),
+            "100".to_string(), 30.0 - 90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings {
+                foundation: FoundationSettings { segment_count: 40 },
+                ..Default::default()
+            },
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let forces = &results[0].internal_force_results[&1];
+        for x in [1000.0, 2500.0, 5000.0, 7500.0, 9000.0] {
+            let axial = forces.get_force_at(vefem::results::ForceType::Axial, x).unwrap().value_y;
+            assert!(axial.abs() < 1e-6 * 1e5, "Axial force {} at {}", axial, x);
+            let deflection = forces.get_force_at(vefem::results::ForceType::Deflection, x).unwrap();
+            assert!(deflection.value_x.abs() < 1e-6);
+        }
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let k = 20000.0 * 1e-6 * 1000.0;
+        let lambda = (k / (4.0 * ei)).powf(0.25);
+        let moment = forces.get_force_at(vefem::results::ForceType::Moment, 5000.0).unwrap().value_y;
+        assert!(relative_eq!(moment.abs(), 1e5 / (4.0 * lambda), max_relative = 1e-2));
+    }
+
+    #[test]
+    fn rigid_end_offset() {
+        // A 3 m cantilever that is fixed to the face of a 2 m wide wall, so the first 1 m of the
+        // element is rigid. The point load (10 kN) at the end deflects the flexible part of the
+        // element (L = 2 m) by PL³/3EI, but the support moment is from the whole lever arm.
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(3000.0, 0.0))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.rigid_offsets = RigidOffsets { start_x: 1000.0, ..Default::default() };
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+            "10".to_string(), -90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let length = 2000.0_f64;
+        assert!(relative_eq!(node_results.get_global_displacement(2, 1), -1e4 * length.powi(3) / (3.0 * ei), max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_global_displacement(2, 2).abs(), 1e4 * length.powi(2) / (2.0 * ei), max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 1), 1e4, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 2).abs(), 1e4 * 3000.0, max_relative = 1e-6));
+        // The internal forces are only over the flexible part
+        let forces = &results[0].internal_force_results[&1];
+        let moment_start = forces.get_force_at(vefem::results::ForceType::Moment, 0.0).unwrap().value_y;
+        assert!(relative_eq!(moment_start.abs(), 1e4 * length, max_relative = 1e-6));
+        assert!(relative_eq!(forces.moment_forces.last().unwrap().pos_on_element, length, max_relative = 1e-9));
+    }
+
+    #[test]
+    fn eccentric_element() {
+        // A cantilever whose axis is 100 mm above the nodes. The axial load (10 kN) at the end
+        // causes no bending in the element, but the support takes the moment of the eccentricity.
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(2000.0, 0.0))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.rigid_offsets = RigidOffsets {
+            start_z: 100.0,
+            end_z: 100.0,
+            coordinate_system: OffsetCoordinateSystem::Global,
+            ..Default::default()
+        };
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+            "10".to_string(), 0.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ea = 210e3 * 100.0 * 100.0;
+        assert!(relative_eq!(node_results.get_global_displacement(2, 0), 1e4 * 2000.0 / ea, max_relative = 1e-6));
+        assert!(node_results.get_global_displacement(2, 1).abs() < 1e-9);
+        assert!(node_results.get_global_displacement(2, 2).abs() < 1e-12);
+        assert!(relative_eq!(node_results.get_support_reaction(1, 0), -1e4, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 2).abs(), 1e4 * 100.0, max_relative = 1e-6));
+        let forces = &results[0].internal_force_results[&1];
+        assert!(forces.moment_forces.iter().all(|m| m.value_y.abs() < 1e-3));
+        let axial = forces.get_force_at(vefem::results::ForceType::Axial, 1000.0).unwrap().value_y;
+        assert!(relative_eq!(axial, 1e4, max_relative = 1e-6));
+    }
+
+    #[test]
+    fn semi_rigid_release() {
+        // A cantilever whose connection to the support is semi-rigid (k = 1000 kNm/rad). The point
+        // load (10 kN) at the end rotates the connection by PL/k, which adds PL²/k to the
+        // deflection PL³/3EI of the cantilever.
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.releases.s_ry = true;
+        element.releases.s_r_spring = 1000.0;
+        assert_eq!(element.releases.to_string(), "xxsxxx");
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+            "10".to_string(), -90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        // kNm/rad => Nmm/rad
+        let k = 1000.0 * 1e6;
+        let rotation = 1e4 * 4000.0 / k;
+        let deflection = 1e4 * 4000f64.powi(3) / (3.0 * ei) + rotation * 4000.0;
+        assert!(relative_eq!(node_results.get_global_displacement(2, 1), -deflection, max_relative = 1e-6));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 2).abs(), 1e4 * 4000.0, max_relative = 1e-6));
+        let forces = &results[0].internal_force_results[&1];
+        assert!(relative_eq!(forces.end_rotations[0], -rotation, max_relative = 1e-6));
+        assert_eq!(forces.end_rotations[1], 0.0);
+        // The deflections of the element start from the rotated connection
+        let end_deflection = forces.get_force_at(vefem::results::ForceType::Deflection, 4000.0).unwrap().value_y;
+        assert!(relative_eq!(end_deflection, -deflection, max_relative = 1e-6));
+        let moment_start = forces.get_force_at(vefem::results::ForceType::Moment, 0.0).unwrap().value_y;
+        assert!(relative_eq!(moment_start.abs(), 1e4 * 4000.0, max_relative = 1e-6));
+    }
+
+    #[test]
+    fn rigid_link() {
+        // A simply supported beam (q = 10 kN/m) whose right end rests on an eccentric bearing 300 mm
+        // below the beam axis. The bearing is a roller connected to the beam with a rigid link, so
+        // the end of the beam does not deflect and the bearing moves horizontally by the end
+        // rotation of the beam times the eccentricity.
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(4000.0, 0.0))),
+            (3, Node::new(3, VpPoint::new(4000.0, -300.0), Support { tz: true, ..Support::new() })),
+        ]);
+        let element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        let loads = vec![Load::new_line_load("q".to_string(), "1".to_string(), "0".to_string(),
+            "L".to_string(), "10".to_string(), -90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![Constraint::new_rigid_link(2, 3)],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let end_rotation = 10.0 * 4000f64.powi(3) / (24.0 * ei);
+        assert!(node_results.get_global_displacement(2, 1).abs() < 1e-6);
+        assert!(relative_eq!(node_results.get_global_displacement(2, 2), end_rotation, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_global_displacement(3, 2), end_rotation, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_global_displacement(3, 0), 300.0 * end_rotation, max_relative = 1e-5));
+        // The reaction of the bearing includes the force of the link
+        assert!(relative_eq!(node_results.get_support_reaction(3, 1), 2e4, max_relative = 1e-5));
+        // The link carries the reaction of the bearing to the end of the beam
+        assert!(relative_eq!(node_results.get_constraint_force(2, 1), 2e4, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_constraint_force(3, 1), -2e4, max_relative = 1e-5));
+        assert_eq!(node_results.get_constraint_force(1, 1), 0.0);
+    }
+
+    #[test]
+    fn equal_dof_constraint() {
+        // Two equal cantilever columns whose tops are tied horizontally (e.g. a rigid floor). The
+        // horizontal point load (10 kN) at the top of the first column is shared equally by the
+        // columns.
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(0.0, 3000.0))),
+            (3, Node::new_fixed(3, VpPoint::new(6000.0, 0.0))),
+            (4, Node::new_free(4, VpPoint::new(6000.0, 3000.0))),
+        ]);
+        let profile = Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0);
+        let elements = vec![
+            Element::new(1, 1, 2, profile.clone(), MaterialData::Steel(Steel::new(210e3))),
+            Element::new(2, 3, 4, profile, MaterialData::Steel(Steel::new(210e3))),
+        ];
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+            "10".to_string(), 0.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![Constraint::new_equal_dof(2, 4, true, false, false)],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let node_results = &results[0].node_results;
+        let ei = 210e3 * 100f64.powi(4) / 12.0;
+        let displacement = 5e3 * 3000f64.powi(3) / (3.0 * ei);
+        assert!(relative_eq!(node_results.get_global_displacement(2, 0), displacement, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_global_displacement(4, 0), displacement, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 0), -5e3, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_support_reaction(3, 0), -5e3, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_constraint_force(2, 0), -5e3, max_relative = 1e-5));
+        assert!(relative_eq!(node_results.get_constraint_force(4, 0), 5e3, max_relative = 1e-5));
+        assert_eq!(node_results.get_constraint_force(4, 1), 0.0);
+    }
+
+    #[test]
+    fn influence_line() {
+        // The influence lines of a simply supported beam (L = 4000 mm) modelled with two elements.
+        // The reaction at the left support decreases linearly from the unit load (1 kN) to zero
+        // and the moment at the midspan is the triangle with the maximum PL/4 at the midspan.
+        let nodes = BTreeMap::from([
+            (1, Node::new_hinged(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(2000.0, 0.0))),
+            (3, Node::new_hinged(3, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let profile = Profile::new_rectangle("R100x100".to_string(), 100.0, 100.0);
+        let elements = vec![
+            Element::new(1, 1, 2, profile.clone(), MaterialData::Steel(Steel::new(210e3))),
+            Element::new(2, 2, 3, profile, MaterialData::Steel(Steel::new(210e3))),
+        ];
+        let struct_model = StructureModel {
+            nodes,
+            elements,
+            loads: vec![],
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let reaction = InfluenceQuantity::SupportReaction { node_number: 1, direction: 1 };
+        let influence_line = vefem::fem::fem_handler::calculate_influence_line(
+            &struct_model, &EquationHandler::new(), &reaction, &[1, 2]);
+        // The shared node is included once
+        assert_eq!(influence_line.ordinates.len(), 41);
+        for ordinate in influence_line.ordinates.iter() {
+            let expected = 1e3 * (1.0 - ordinate.path_position / 4000.0);
+            assert!((ordinate.value - expected).abs() < 1e-6);
+        }
+        let last = influence_line.ordinates.last().unwrap();
+        assert_eq!(last.element_number, 2);
+        assert!(relative_eq!(last.pos_on_element, 2000.0));
+
+        let moment = InfluenceQuantity::InternalForce {
+            element_number: 1,
+            position: 2000.0,
+            force_type: vefem::results::ForceType::Moment,
+        };
+        let influence_line = vefem::fem::fem_handler::calculate_influence_line(
+            &struct_model, &EquationHandler::new(), &moment, &[1, 2]);
+        let max = influence_line.get_max().unwrap();
+        assert!(relative_eq!(max.path_position, 2000.0));
+        assert!(relative_eq!(max.value, 1e3 * 4000.0 / 4.0, max_relative = 1e-6));
+        assert!(relative_eq!(influence_line.get_value_at(1000.0).unwrap(), 1e3 * 1000.0 / 2.0, max_relative = 1e-6));
+        assert!(relative_eq!(influence_line.get_value_at(3500.0).unwrap(), 1e3 * 500.0 / 2.0, max_relative = 1e-6));
+        assert!(influence_line.get_value_at(4100.0).is_none());
+    }
+
+    #[test]
+    fn tapered_element() {
+        // A tapered cantilever (L = 4000 mm) whose height varies linearly from 400 mm at the fixed
+        // end to 200 mm at the free end. The point load (10 kN) is at the free end. The deflections
+        // are compared to the integrals of M m / EI(x) with the unit load at the position.
+        let nodes = BTreeMap::from([
+            (1, Node::new_fixed(1, VpPoint::new(0.0, 0.0))),
+            (2, Node::new_free(2, VpPoint::new(4000.0, 0.0))),
+        ]);
+        let mut element = Element::new(
+            1,
+            1,
+            2,
+            Profile::new_rectangle("R400x100".to_string(), 400.0, 100.0),
+            MaterialData::Steel(Steel::new(210e3)),
+        );
+        element.section_variation =
+            SectionVariation::new_tapered(Profile::new_rectangle("R200x100".to_string(), 200.0, 100.0));
+        let loads = vec![Load::new_point_load("P".to_string(), "1".to_string(), "L".to_string(),
+            "10".to_string(), -90.0, LoadGroup::PERMANENT)];
+        let struct_model = StructureModel {
+            nodes,
+            elements: vec![element],
+            loads,
+            calc_settings: CalculationSettings::default(),
+            load_combinations: vec![],
+            constraints: vec![],
+        };
+        let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
+        let get_deflection = |a: f64| {
+            let count = 10000;
+            let dx = a / count as f64;
+            (0..count)
+                .map(|i| {
+                    let x = (i as f64 + 0.5) * dx;
+                    let smoa = 100.0 * (400.0 - 200.0 * x / 4000.0).powi(3) / 12.0;
+                    1e4 * (4000.0 - x) * (a - x) / (210e3 * smoa) * dx
+                })
+                .sum::<f64>()
+        };
+        let node_results = &results[0].node_results;
+        let tip = node_results.get_global_displacement(2, 1);
+        assert!(relative_eq!(tip, -get_deflection(4000.0), max_relative = 0.01));
+        // The stiffness of the tapered element differs from the prismatic elements at both ends
+        let ei_start = 210e3 * 100.0 * 400f64.powi(3) / 12.0;
+        let ei_end = 210e3 * 100.0 * 200f64.powi(3) / 12.0;
+        assert!(tip.abs() > 1e4 * 4000f64.powi(3) / (3.0 * ei_start));
+        assert!(tip.abs() < 1e4 * 4000f64.powi(3) / (3.0 * ei_end));
+
+        // The internal forces are statically determinate and the deflections follow the varying EI
+        let forces = &results[0].internal_force_results[&1];
+        let moment = forces.get_force_at(vefem::results::ForceType::Moment, 2000.0).unwrap().value_y;
+        assert!(relative_eq!(moment.abs(), 2e7, max_relative = 1e-6));
+        let deflection = forces.get_force_at(vefem::results::ForceType::Deflection, 2000.0).unwrap().value_y;
+        assert!(relative_eq!(deflection.abs(), get_deflection(2000.0), max_relative = 0.01));
+        assert!(relative_eq!(node_results.get_support_reaction(1, 1), 1e4, max_relative = 1e-6));
+    }
 }
@@ -43,6 +43,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results =
             &vefem::fem::fem_handler::calculate(&structure_model, &mut EquationHandler::new())[0];
@@ -127,6 +128,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -187,6 +189,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -307,6 +310,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -358,6 +362,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -451,6 +456,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -507,6 +513,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -603,6 +610,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -673,6 +681,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -769,6 +778,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -854,6 +864,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -915,6 +926,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1023,6 +1035,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1163,6 +1176,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1286,6 +1300,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1366,6 +1381,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1421,6 +1437,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1526,6 +1543,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1655,6 +1673,7 @@ mod internal_forces_tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
 
         let results =
@@ -1747,6 +1766,7 @@ mod internal_forces_tests {
             loads: loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let calc_model = common::get_calc_model(&struct_model.elements, &struct_model.nodes);
         let results =
@@ -1790,7 +1810,8 @@ mod internal_forces_tests {
             elements,
             loads,
             load_combinations: vec![load_combination],
-            calc_settings: CalculationSettings::default()
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&calc_model, &EquationHandler::new());
 
@@ -33,7 +33,7 @@ mod tests {
         load_combination2.add_load_n_factor(String::from("2"), 1.4);
         load_combination2.add_load_n_factor(String::from("3"), 2.0);
         
-        let calc_lc2 = &loads::lc_utils::get_calc_load_combinations(&load_combination2, &loads)[0];
+        let calc_lc2 = &loads::lc_utils::get_calc_load_combinations(&load_combination2, &loads, &elements, &CalculationSettings::default().partial_factors)[0];
 
         let calc_loads = loads::utils::extract_calculation_loads(
             &calc_model,
@@ -53,7 +53,8 @@ mod tests {
             elements,   
             loads,
             load_combinations: vec![load_combination, load_combination2],
-            calc_settings: CalculationSettings::default()
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&calc_model, &EquationHandler::new());
 
@@ -90,7 +91,7 @@ mod tests {
         );
         load_combination.add_load_n_factor(String::from("ALL"), 1.0);
 
-        let calc_lc = &loads::lc_utils::get_calc_load_combinations(&load_combination, &loads)[0];
+        let calc_lc = &loads::lc_utils::get_calc_load_combinations(&load_combination, &loads, &elements, &CalculationSettings::default().partial_factors)[0];
 
         let calc_loads = loads::utils::extract_calculation_loads(
             &calc_model,
@@ -110,7 +111,8 @@ mod tests {
             elements,
             loads,
             load_combinations: vec![load_combination],
-            calc_settings: CalculationSettings::default()
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&calc_model, &EquationHandler::new());
 
@@ -168,7 +170,8 @@ mod tests {
             elements,
             loads,
             load_combinations: vec![load_combination],
-            calc_settings: CalculationSettings::default()
+            calc_settings: CalculationSettings::default(),
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&calc_model, &EquationHandler::new());
         println!("Results count: {0}", results.len());
@@ -179,4 +182,94 @@ mod tests {
         assert!((results[0].node_results.support_reactions[1] - (21600.0)).abs() < 10.0);
         assert!((results[0].node_results.support_reactions[1*3+1] - (59400.0)).abs() < 10.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_load_case_superposition() {
+        let get_model = |superposition: bool| {
+            let mut moving = Load::new_line_load(
+                "ClassA".to_string(),
+                "-1".to_string(),
+                "0".to_string(),
+                "L".to_string(),
+                "10".to_string(),
+                -90.0,
+                LoadGroup::CLASS_A,
+            );
+            moving.is_moving_load = true;
+            moving.moving_percent = 20.0;
+            let loads = vec![
+                Load::new_line_load(
+                    "perm".to_string(),
+                    "-1".to_string(),
+                    "0".to_string(),
+                    "L".to_string(),
+                    "10".to_string(),
+                    -90.0,
+                    LoadGroup::PERMANENT,
+                ),
+                moving,
+                Load::new_point_load(
+                    "ClassB".to_string(),
+                    "2".to_string(),
+                    "1000".to_string(),
+                    "5000".to_string(),
+                    -90.0,
+                    LoadGroup::CLASS_B,
+                ),
+            ];
+            let (elements, nodes) = common::get_structure_three_horizontal_elements();
+            let mut load_combination = vefem::loads::LoadCombination::new(
+                1,
+                String::from("Load combination 1"),
+                load_combination::LoadCombinationType::ULS { is_auto: true },
+            );
+            load_combination.add_load_n_factor(String::from("ALL"), 1.0);
+            vefem::structure::StructureModel {
+                nodes,
+                elements,
+                loads,
+                load_combinations: vec![load_combination],
+                calc_settings: CalculationSettings {
+                    load_case_superposition: superposition,
+                    ..Default::default()
+                },
+                constraints: vec![],
+            }
+        };
+        let model = get_model(false);
+        let results = vefem::fem::fem_handler::calculate(&model, &EquationHandler::new());
+        let results_superposed =
+            vefem::fem::fem_handler::calculate(&get_model(true), &EquationHandler::new());
+        assert_eq!(results.len(), results_superposed.len());
+        for (r, s) in results.iter().zip(results_superposed.iter()) {
+            assert_eq!(r.sub_load_comb_num, s.sub_load_comb_num);
+            for (a, b) in r.node_results.support_reactions.iter()
+                .zip(s.node_results.support_reactions.iter()) {
+                assert!((a - b).abs() < 1e-3, "{a} != {b}");
+            }
+            for (a, b) in r.node_results.displacements.iter()
+                .zip(s.node_results.displacements.iter()) {
+                assert!((a - b).abs() < 1e-6, "{a} != {b}");
+            }
+            for (el, forces) in r.internal_force_results.iter() {
+                let forces_s = &s.internal_force_results[el];
+                for (a, b) in forces.moment_forces.iter().zip(forces_s.moment_forces.iter()) {
+                    assert_eq!(a.pos_on_element, b.pos_on_element);
+                    assert!((a.value_y - b.value_y).abs() < 1.0, "{} != {}", a.value_y, b.value_y);
+                }
+            }
+        }
+
+        // The load case results equal the results of the single loads with the factor 1.0
+        let load_cases = vefem::fem::fem_handler::calculate_load_cases(&model, &EquationHandler::new());
+        let names: Vec<&str> = load_cases.iter().map(|c| c.load_name.as_str()).collect();
+        assert_eq!(names, vec!["perm", "ClassA", "ClassB"]);
+        let reaction_sum = |results: &vefem::results::NodeResults| {
+            (1..=4).map(|n| results.get_support_reaction(n, 1)).sum::<f64>()
+        };
+        // The moving load is on all the elements with full strength
+        assert!((reaction_sum(&load_cases[0].node_results)
+            - reaction_sum(&load_cases[1].node_results)).abs() < 1e-3);
+        assert!((reaction_sum(&load_cases[2].node_results) - 5000.0e3).abs() < 1e-3);
+    }
+}
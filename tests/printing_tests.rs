@@ -18,6 +18,7 @@ mod tests {
             loads,
             calc_settings: CalculationSettings::default(),
             load_combinations: vec![],
+            constraints: vec![],
         };
         let results = vefem::fem::fem_handler::calculate(&struct_model, &EquationHandler::new());
         printing::print_results(&results, &struct_model, true);
@@ -35,6 +35,7 @@ pub mod serializing_tests {
             load_combinations,
             loads,
             calc_settings,
+            constraints: vec![],
         };
         let calc_model_json = serde_json::to_string_pretty(&calc_model).unwrap();
         println!("Calculation model JSON: {}", calc_model_json);
@@ -144,6 +145,7 @@ pub mod serializing_tests {
             load_combinations,
             loads,
             calc_settings,
+            constraints: vec![],
         };
         let calc_model_json = serde_json::to_string_pretty(&calc_model).unwrap();
         println!("Calculation model JSON: {}", calc_model_json);